@@ -1,12 +1,62 @@
-from typing import List, Optional
+from typing import Callable, Dict, List, Optional, Tuple
+
+SCHEMA_VERSION: int
+"""The version of the replay JSON schema, saved as `schema_version` in every replay. It's bumped whenever a field of the replay changes."""
+
+def render_frames(replay: str) -> List[str]:
+    """Renders every turn of a saved replay as a plain string, the same as `Game.render` at the end of that turn.
+
+    The board is reconstructed by applying the events of each turn, in order, to the map the game started from.
+    Replays don't record the game settings, so razed hills are always drawn as in `HillRazeMode.Disable`. Hills placed with `Game.place_hill` are only drawn for replays with schema version 10 or later. Corpses and the food carried by ants are not reconstructed either, since they are not logged as events.
+
+    :raises ValueError: If the replay is not valid JSON, is missing a field, has a malformed map or an event outside it, or was saved with a schema version older than 3.
+
+    :param replay: The contents of the replay file.
+    :type replay: str
+    :return: The rendered board of every turn, starting with the start of the game.
+    :rtype: List[str]
+    """
+
+def transform_map(map_contents: str, transform: MapTransform, show_ants_on_hill: bool = True) -> str:
+    """Transforms a map, moving every entity to its new location while keeping its owner.
+
+    The result is in the same format as the given map, so it can be used to create a new game.
+
+    :param map_contents: The contents of the map to transform.
+    :type map_contents: str
+    :param transform: The transformation to apply.
+    :type transform: MapTransform
+    :param show_ants_on_hill: Whether ants standing on their hill are written as such (e.g. `A`), otherwise they are written as plain ants (e.g. `a`) and their hill is left out. Defaults to `True`.
+    :type show_ants_on_hill: bool
+    :raises ValueError: If the map is malformed, e.g. if it has an invalid character.
+    :return: The contents of the transformed map.
+    :rtype: str
+    """
+
+def translate_map(map_contents: str, tile_map: TileMap, show_ants_on_hill: bool = True) -> str:
+    """Translates a map that uses custom characters to the usual ones, so that it can be used to create a new game.
+
+    :param map_contents: The contents of the map to translate.
+    :type map_contents: str
+    :param tile_map: The tile each custom character stands for. Other characters keep their usual meaning.
+    :type tile_map: TileMap
+    :param show_ants_on_hill: Whether ants standing on their hill are written as such (e.g. `A`), otherwise they are written as plain ants (e.g. `a`) and their hill is left out. Defaults to `True`.
+    :type show_ants_on_hill: bool
+    :raises ValueError: If the map is malformed, e.g. if it has a character that is neither usual nor in the table.
+    :return: The contents of the map with the usual characters.
+    :rtype: str
+    """
 
 class Action:
     """A class representing an action that an ant can take.
 
+    Maps don't wrap around unless their header has a `wrap 1` line. On a bounded map, moving off any of the four edges is never a valid move: the ant stays in place, no `Move` event is logged and a move of several steps stops at the edge. When `Game.log_blocked_moves` is enabled, the move is logged as a `BlockedMove` with the "OutOfBounds" reason. On a wrapping map, moving off an edge comes back on the opposite edge.
+
     Attributes:
         row (int): The row of the location of the ant.
         col (int): The column of the location of the ant.
         direction (Direction): The direction of the movement.
+        steps (int): The maximum number of cells to move in the direction. Defaults to 1.
     """
 
     row: int
@@ -15,6 +65,42 @@ class Action:
     """The column of the location of the ant."""
     direction: Direction
     """The direction of the movement."""
+    steps: int
+    """The maximum number of cells to move in the direction. Defaults to 1."""
+
+    @staticmethod
+    def dig(row: int, col: int, direction: Direction) -> Action:
+        """Creates an action that digs the water cell next to the ant, in the given direction, turning it into land.
+
+        The ant stays in place. Digging must be enabled with `Game.dig_cost` and it's paid with food from the player's hive. If the cell is not water or there isn't enough food in the hive, the action is ignored.
+
+        :param row: The row of the ant that digs.
+        :type row: int
+        :param col: The column of the ant that digs.
+        :type col: int
+        :param direction: The direction of the water cell to dig.
+        :type direction: Direction
+        :return: The new action.
+        :rtype: Action
+        """
+
+    @staticmethod
+    def new_with_steps(row: int, col: int, direction: Direction, steps: int) -> Action:
+        """Creates an action that moves the ant up to `steps` cells in the same direction.
+
+        The ant stops at the first cell it can't move into, e.g. water, food or the edge of the map, and collides with the first ant it runs into. Every step is logged as a separate move in the replay.
+
+        :param row: The row of the ant to move.
+        :type row: int
+        :param col: The column of the ant to move.
+        :type col: int
+        :param direction: The direction the ant should move.
+        :type direction: Direction
+        :param steps: The maximum number of cells to move.
+        :type steps: int
+        :return: The new action.
+        :rtype: Action
+        """
 
 class Ant:
     """A class representing an ant.
@@ -26,6 +112,13 @@ class Ant:
         player (int): The player that owns the ant.
         alive (bool): Whether the ant is alive.
         field_of_vision (List[Entity]): The field of vision of the ant as a list of entities the ant can see.
+        visible_cells (List[Tuple[int, int]]): The coordinates of all the cells the ant can see, including empty ones and its own cell.
+        last_direction (Optional[Direction]): The direction the ant was ordered to move in on the last turn, or `None` if it wasn't ordered to move, e.g. because it was just spawned.
+        last_move_succeeded (bool): Whether the ant actually moved on the last turn, i.e. at least one step of its move was not blocked.
+        owner_score (int): The current score of the player who owns the ant, the same as in `GameState.scores`.
+        owner_hive (int): The amount of food in the hive of the player who owns the ant, the same as in `GameState.hive`.
+        carrying (bool): Whether the ant is carrying food back to a hill, only when `Game.carry_food` is enabled.
+        idle_turns (int): The number of turns in a row the ant has been idle, only counted when `Game.idle_patience` is set.
     """
 
     id: str
@@ -40,6 +133,34 @@ class Ant:
     """Whether the ant is alive."""
     field_of_vision: List[Entity]
     """The field of vision of the ant as a list of entities the ant can see."""
+    visible_cells: List[Tuple[int, int]]
+    """The coordinates of all the cells the ant can see, including empty ones and its own cell.
+    Unlike `field_of_vision`, this tells the cells seen as empty apart from the cells the ant has no information about."""
+    last_direction: Optional[Direction]
+    """The direction the ant was ordered to move in on the last turn, or `None` if it wasn't ordered to move, e.g. because it was just spawned."""
+    last_move_succeeded: bool
+    """Whether the ant actually moved on the last turn, i.e. at least one step of its move was not blocked."""
+    owner_score: int
+    """The current score of the player who owns the ant, the same as in `GameState.scores`."""
+    owner_hive: int
+    """The amount of food in the hive of the player who owns the ant, the same as in `GameState.hive`."""
+    carrying: bool
+    """Whether the ant is carrying food back to a hill, only when `Game.carry_food` is enabled."""
+    idle_turns: int
+    """The number of turns in a row the ant has been idle, only counted when `Game.idle_patience` is set."""
+
+class ContestedFood:
+    """An enum representing what happens to food surrounded by ants of more than one player.
+
+    Attributes:
+        Destroy (str): The food is destroyed without being harvested by anyone. This is the default.
+        Majority (str): The player with strictly more ants around the food harvests it, and only ties destroy it.
+    """
+
+    Destroy: str
+    """The food is destroyed without being harvested by anyone. This is the default."""
+    Majority: str
+    """The player with strictly more ants around the food harvests it, and only ties destroy it."""
 
 class Direction:
     """An enum representing a direction.
@@ -60,27 +181,110 @@ class Direction:
     West: str
     """The West direction."""
 
+class DistanceMetric:
+    """An enum representing how the distance between two cells is measured for the field of vision, attack and food ranges.
+
+    Ranges are always given as a radius **squared**, whatever the metric: a cell is within range when the distance to it, squared, is at most the given radius squared.
+
+    Attributes:
+        Euclidean2 (str): The straight line distance, i.e. `sqrt(rows^2 + cols^2)`. This is the default.
+        Manhattan (str): The number of steps when only moving in the 4 cardinal directions, i.e. `rows + cols`.
+        Chebyshev (str): The number of steps when also moving diagonally, i.e. `max(rows, cols)`.
+    """
+
+    Euclidean2: str
+    """The straight line distance, i.e. `sqrt(rows^2 + cols^2)`. This is the default."""
+    Manhattan: str
+    """The number of steps when only moving in the 4 cardinal directions, i.e. `rows + cols`."""
+    Chebyshev: str
+    """The number of steps when also moving diagonally, i.e. `max(rows, cols)`."""
+
 class Entity:
     """A class representing an entity.
 
     Attributes:
-        name (str): The name of the entity. "Ant", "Food", "Hill" or "Water".
+        name (str): The name of the entity. "Ant", "Corpse", "Food", "Hill" or "Water".
         row (int): The row of the location of the entity.
         col (int): The column of the location of the entity.
         player (int): The player that owns the entity.
-        alive (bool): Whether the entity is alive, only applicable to ants.
+        alive (Optional[bool]): Whether the entity is alive, only applicable to ants and hills. For a hill, `False` means it was razed, which is clearer to read from `razed`.
+        razed (Optional[bool]): Whether the hill was razed by an enemy ant, only for hills. Unlike a dead ant, a razed hill can stay on the map.
     """
 
     name: str
-    """The name of the entity. "Ant", "Food", "Hill" or "Water"."""
+    """The name of the entity. "Ant", "Corpse", "Food", "Hill" or "Water"."""
     row: int
     """The row of the location of the entity."""
     col: int
     """The column of the location of the entity."""
     player: int
     """The player that owns the entity."""
-    alive: bool
-    """Whether the entity is alive, only applicable to ants."""
+    alive: Optional[bool]
+    """Whether the entity is alive, only applicable to ants and hills. For a hill, `False` means it was razed, which is clearer to read from `razed`."""
+    razed: Optional[bool]
+    """Whether the hill was razed by an enemy ant, only for hills. Unlike a dead ant, a razed hill can stay on the map."""
+
+class Event:
+    """A class representing an event in the game, as logged in the replay.
+
+    Attributes:
+        event_type (EventType): The type of the event.
+        entity (str): The name of the entity the event is about, i.e. "Ant", "Food", "Hill" or "Water".
+        entity_id (Optional[str]): The id of the entity, if it has one.
+        player (Optional[int]): The player that owns the entity, only set when an ant is spawned, or the player that stored the food for harvests.
+        location (Optional[Tuple[int, int]]): The location of the entity.
+        destination (Optional[Tuple[int, int]]): The destination of the ant for moves and blocked moves, or the location of the attacked ant for attacks.
+        harvester_id (Optional[str]): The id of the ant that harvested the food, only set for harvests and for food picked up by a carrying ant.
+        reason (Optional[str]): Why the ant couldn't move, only set for blocked moves, i.e. "OutOfBounds", "NoLiveAnt", "Water", "Food" or "DeadAnt".
+        initial (bool): Whether the entity was spawned as part of the initial setup of the game, i.e. on turn 0, only set for spawns.
+        target_id (Optional[str]): The id of the attacked ant, only set for attacks. Together with `entity_id`, the id of the attacking ant, it tells who killed whom.
+    """
+
+    event_type: EventType
+    """The type of the event."""
+    entity: str
+    """The name of the entity the event is about, i.e. "Ant", "Food", "Hill" or "Water"."""
+    entity_id: Optional[str]
+    """The id of the entity, if it has one."""
+    player: Optional[int]
+    """The player that owns the entity, only set when an ant is spawned, or the player that stored the food for harvests."""
+    location: Optional[Tuple[int, int]]
+    """The location of the entity."""
+    destination: Optional[Tuple[int, int]]
+    """The destination of the ant for moves and blocked moves, or the location of the attacked ant for attacks."""
+    harvester_id: Optional[str]
+    """The id of the ant that harvested the food, only set for harvests and for food picked up by a carrying ant."""
+    reason: Optional[str]
+    """Why the ant couldn't move, only set for blocked moves, i.e. "OutOfBounds", "NoLiveAnt", "Water", "Food" or "DeadAnt"."""
+    initial: bool
+    """Whether the entity was spawned as part of the initial setup of the game, i.e. on turn 0, only set for spawns."""
+    target_id: Optional[str]
+    """The id of the attacked ant, only set for attacks. Together with `entity_id`, the id of the attacking ant, it tells who killed whom, since an ant is only attacked when the battle kills it."""
+
+class EventType:
+    """An enum representing the type of an event in the game.
+
+    Attributes:
+        Spawn (str): An entity was added to the map.
+        Remove (str): An entity was removed from the map, e.g. a dead ant, destroyed food, a razed hill or dug water.
+        Move (str): An ant moved.
+        Attack (str): An ant attacked another ant.
+        BlockedMove (str): An ant tried to move but couldn't, only logged when `Game.log_blocked_moves` is enabled.
+        Harvest (str): Food was harvested and stored in the hive of a player, which removes it from the map.
+    """
+
+    Spawn: str
+    """An entity was added to the map."""
+    Remove: str
+    """An entity was removed from the map, e.g. a dead ant, destroyed food, a razed hill or dug water."""
+    Move: str
+    """An ant moved."""
+    Attack: str
+    """An ant attacked another ant."""
+    BlockedMove: str
+    """An ant tried to move but couldn't, only logged when `Game.log_blocked_moves` is enabled."""
+    Harvest: str
+    """Food was harvested and stored in the hive of a player, which removes it from the map."""
 
 class FinishedReason:
     """An enum representing the reason the game finished.
@@ -90,6 +294,7 @@ class FinishedReason:
         RankStabilized (str): The game ended because the rank stabilized, i.e. no player can surpass the current leader anymore.
         TooMuchFood (str): The game ended because food was not being consumed and it reached 90% or more of the map.
         TurnLimitReached (str): The game ended because the maximum number of turns was reached.
+        Stalemate (str): The game ended because, for too many turns, no ant could reach food nor an enemy ant or hill.
     """
 
     LoneSurvivor: str
@@ -100,6 +305,44 @@ class FinishedReason:
     """The game ended because food was not being consumed and it reached 90% or more of the map."""
     TurnLimitReached: str
     """The game ended because the maximum number of turns was reached."""
+    Stalemate: str
+    """The game ended because, for too many turns, no ant could reach food nor an enemy ant or hill."""
+
+class FoodSpawnStrategy:
+    """An enum representing the strategy used to choose where food spawns.
+
+    Attributes:
+        Random: Food spawns on random land cells. This is the default.
+        SymmetricMirror: Food spawns on random land cells and on all their images under the map's symmetries (rotational or mirror), so that every player sees equivalent food. If the map is not symmetric, this behaves like `Random`.
+        Fixed: Food spawns on the given locations, in order, whenever they are empty land.
+        FarFromHills: Food spawns on random land cells, preferring the ones that are farther away from every live hill by the number of moves, to encourage exploring the map. The starting food around the hills is still random.
+        Spread: Food spawns on random land cells spread evenly across the map, to avoid clustering it by chance. The map is partitioned into a grid of square buckets, about one per food spawned on a turn, and each food goes to a random cell of a different bucket, starting with the buckets that have the least food. The starting food around the hills is still random.
+    """
+
+    class Random(FoodSpawnStrategy):
+        """Food spawns on random land cells. This is the default."""
+
+        def __init__(self) -> None: ...
+
+    class SymmetricMirror(FoodSpawnStrategy):
+        """Food spawns on random land cells and on all their images under the map's symmetries (rotational or mirror), so that every player sees equivalent food. If the map is not symmetric, this behaves like `Random`."""
+
+        def __init__(self) -> None: ...
+
+    class Fixed(FoodSpawnStrategy):
+        """Food spawns on the given locations, in order, whenever they are empty land."""
+
+        def __init__(self, locations: List[Tuple[int, int]]) -> None: ...
+
+    class FarFromHills(FoodSpawnStrategy):
+        """Food spawns on random land cells, preferring the ones that are farther away from every live hill by the number of moves, to encourage exploring the map. The starting food around the hills is still random."""
+
+        def __init__(self) -> None: ...
+
+    class Spread(FoodSpawnStrategy):
+        """Food spawns on random land cells spread evenly across the map, to avoid clustering it by chance. The map is partitioned into a grid of square buckets, about one per food spawned on a turn, and each food goes to a random cell of a different bucket, starting with the buckets that have the least food. The starting food around the hills is still random."""
+
+        def __init__(self) -> None: ...
 
 class Game:
     """A class representing the Ants game. Main entry point for the environment.
@@ -110,7 +353,7 @@ class Game:
     :type fov_radius2: int
     :param attack_radius2: The squared radius of the attack range of the ants.
     :type attack_radius2: int
-    :param food_radius2: The squared radius of the range around ants to harvest food.
+    :param food_radius2: The squared radius of the range around ants to harvest food. `0` means the ant must be right next to the food, i.e. north, east, south or west of it.
     :type food_radius2: int
     :param food_rate: The amount of food to spawn *per player* on each round.
     :type food_rate: int
@@ -118,10 +361,26 @@ class Game:
     :type max_turns: int
     :param max_colony_size: The maximum number of live ants a player can have at any time.
     :type max_colony_size: int
-    :param seed: The seed for the random number generator.
+    :param seed: The seed for the random number generator. Each player gets its own stream derived from it.
     :type seed: int
     :param replay_filename: The filename to save the replay of the game to. If `None`, no replay will be saved.
     :type replay_filename: str, optional
+    :param player_names: The name of each player, used by `draw`, `render` and the replay. Defaults to "Player N".
+    :type player_names: List[str], optional
+    :param player_colors: The RGB color of each player, used by `draw` and the replay. Defaults to the theme's palette.
+    :type player_colors: List[Tuple[int, int, int]], optional
+    :param strict: Whether to raise an error if the game is not configured consistently, see `validate`. Defaults to `False`, since some rulesets are inconsistent on purpose.
+    :type strict: bool, optional
+    :param idle_patience: The number of turns in a row an ant can stay idle before it has to eat, see `idle_patience`. Defaults to `0`, i.e. ants can stay idle forever.
+    :type idle_patience: int, optional
+    :param starting_ants_per_hill: The number of ants each hill starts the game with, see `starting_ants_per_hill`. Defaults to `1`.
+    :type starting_ants_per_hill: int, optional
+    :param hill_defense: Whether ants standing on their own live hill can't be killed in battle, see `hill_defense`. Defaults to `False`.
+    :type hill_defense: bool, optional
+    :param starting_food_per_hill: The amount of food spawned around each hill at the start of the game, see `starting_food_per_hill`. Defaults to `3`.
+    :type starting_food_per_hill: int, optional
+    :raises ValueError: If the map is malformed, e.g. it has an invalid character, has no players, an ant or hill belongs to an undeclared player or a player has no hill, or if there isn't exactly one name or color for each player.
+    :raises RuntimeError: If `strict` is enabled and the game is not configured consistently.
     """
 
     def __init__(
@@ -134,7 +393,14 @@ class Game:
         max_turns: int,
         max_colony_size: int,
         seed: int,
-        replay_filename: Optional[str],
+        replay_filename: Optional[str] = None,
+        player_names: Optional[List[str]] = None,
+        player_colors: Optional[List[Tuple[int, int, int]]] = None,
+        strict: bool = False,
+        idle_patience: int = 0,
+        starting_ants_per_hill: int = 1,
+        hill_defense: bool = False,
+        starting_food_per_hill: int = 3,
     ) -> None: ...
     def width(self) -> int:
         """Returns the width of the map.
@@ -157,6 +423,279 @@ class Game:
         :rtype: int
         """
 
+    def food_on_map(self) -> int:
+        """Returns the total amount of food on the map.
+
+        :return: The total amount of food on the map.
+        :rtype: int
+        """
+
+    def statistics(self) -> List[PlayerStats]:
+        """Returns the cumulative statistics since the game started for each player.
+
+        :return: The statistics for each player where the index is the player number.
+        :rtype: List[PlayerStats]
+        """
+
+    def ranking(self) -> List[int]:
+        """Returns the players sorted by their current score, from first to last place.
+
+        Ties are broken by the number of live ants, then by the number of live hills and finally by the player number.
+
+        :return: The player numbers sorted from first to last place.
+        :rtype: List[int]
+        """
+
+    def current_standings(self) -> List[Tuple[int, int, int, int]]:
+        """Returns the final standings as if the game ended now, with the players sorted like in `ranking`.
+
+        :return: The `(player, score, live ants, live hills)` of each player from first to last place.
+        :rtype: List[Tuple[int, int, int, int]]
+        """
+
+    def winner(self) -> Optional[int]:
+        """Returns the winner of the game, the same as the `winner` of the game state, i.e. the first player in the `ranking` when the game finished, whatever the reason.
+
+        :return: The winner, or `None` if the game has not finished or if the first two players were tied on score, live ants and live hills.
+        :rtype: Optional[int]
+        """
+
+    fov_radius2: int
+    """The squared radius of the field of vision the game was created with. Read-only, players can see with a different radius, see `fov_radius2_per_player`."""
+    attack_radius2: int
+    """The squared radius of the attack range of each ant. Read-only."""
+    food_radius2: int
+    """The squared radius of the range around ants to harvest food. Read-only."""
+    food_rate: int
+    """The amount of food to spawn per player on each round. Read-only."""
+    max_turns: int
+    """The maximum number of turns before the game ends. Read-only."""
+    player_names: List[str]
+    """The name of each player, "Player N" unless they were named when creating the game. Read-only."""
+    player_colors: Optional[List[Tuple[int, int, int]]]
+    """The RGB color of each player, or `None` if they use the theme's palette. Read-only."""
+    turn: int
+    """The current turn number, the same as in the last game state. Read-only."""
+    is_finished: bool
+    """Whether the game has finished, the same as in the last game state. Read-only."""
+    finished_reason: Optional[FinishedReason]
+    """The reason the game finished. `None` if the game has not finished. Read-only."""
+    last_rewards: Optional[List[float]]
+    """The reward of each player for the last turn, as computed by the reward hook. `None` if there is no reward hook or no turn has been played yet. Read-only."""
+    fov_radius2_per_player: List[int]
+    """The squared radius of the field of vision of the ants of each player. Defaults to `fov_radius2` for every player. Only affects what the ants see, the attack and food radii are the same for all players."""
+    distance_metric: DistanceMetric
+    """How distances are measured for the field of vision, attack and food ranges. Defaults to `DistanceMetric.Euclidean2`."""
+    phase_order: List[TurnPhase]
+    """The order in which the phases of each turn run, e.g. to raze hills before the ants fight. Each phase must appear exactly once. Defaults to move, attack, raze, spawn ants, harvest and spawn food."""
+    line_of_sight: bool
+    """Whether water blocks the field of vision of the ants, in which case they can't see the cells behind water. Only affects what the ants see, attacks and harvesting still reach over water. Defaults to `False`, i.e. ants see through water."""
+    food_spawn_strategy: FoodSpawnStrategy
+    """The strategy used to choose where food spawns. Defaults to `FoodSpawnStrategy.Random()`."""
+    min_food_ant_distance: int
+    """The minimum distance between randomly spawned food and any live ant, measured with the distance metric, so that food doesn't appear right next to an ant to be harvested for free. If there aren't enough cells that far from the ants, the distance is relaxed one cell at a time for the rest of the food. Fixed food locations and the symmetric images of `SymmetricMirror` are not affected, since all of them are spawned. Defaults to `0`, i.e. food can spawn anywhere."""
+    hill_raze_mode: HillRazeMode
+    """What happens to a hill after an enemy ant razes it. Defaults to `HillRazeMode.Disable`."""
+    starting_score: StartingScore
+    """The score each player starts the game with. Defaults to `StartingScore.HillCount()`."""
+    contested_food: ContestedFood
+    """What happens to food surrounded by ants of more than one player. Defaults to `ContestedFood.Destroy`."""
+    corpse_lifetime: int
+    """The number of turns a dead ant stays on the map as a `Corpse`, visible in the field of vision of nearby ants. Corpses don't block movement, an ant that walks over a corpse removes it. Ants that die on a hill don't leave a corpse, and corpses are not logged in the replay. Defaults to `0`, i.e. no corpses."""
+    dig_cost: Optional[int]
+    """The amount of hive food it costs to dig a water cell, turning it into land, with `Action.dig`. Digs run in the same phase and order as the moves, and are logged as `Remove` events of the water. `None`, the default, means digging is disabled."""
+    step_to_harvest: bool
+    """Whether ants can move onto food to harvest it, in which case the food goes to the hive of the ant that moved and the ant ends on its cell. Food around ants is still harvested at the end of the turn either way. Defaults to `False`, i.e. food blocks movement."""
+    carry_food: bool
+    """Whether ants carry food back to their hills instead of harvesting the food around them. An ant that moves onto food picks it up and ends on its cell, and the food is only stored in the hive once the ant stands on one of its own live hills at the harvest phase. Ants carry one food at a time, so food blocks the ants that are already carrying, and the food is lost if the ant dies before delivering it. Defaults to `False`, i.e. the food around ants is harvested."""
+    signed_scores: bool
+    """Whether scores can go below 0, e.g. when a player with no points loses a hill. Otherwise, scores are clamped at 0. Defaults to `False`."""
+    hill_defense: bool
+    """Whether ants standing on their own live hill can't be killed in battle. They still attack as usual, so the enemies fighting them can die. Defaults to `False`."""
+    reveal_hill_owner: bool
+    """Whether ants see who owns the enemy hills in their field of vision. When hidden, enemy hills are seen with no player, while a player's own hills are always seen as theirs. Defaults to `True`."""
+    log_blocked_moves: bool
+    """Whether moves that fail are logged as `BlockedMove` events, with the reason they failed. This helps debugging what agents tried to do versus what happened, at the cost of larger replays. Defaults to `False`."""
+    track_explored: bool
+    """Whether to track the cells that each player has ever seen, i.e. that were ever within the field of vision of any of their ants. They are included in the game state as `explored`. When enabled mid-game, only the cells seen from then on are explored. Defaults to `False` to avoid the memory cost."""
+    track_board_hash: bool
+    """Whether to compute the hash of the board every turn, see `board_hash`. It's included in the game state as `board_hash` and in every turn of the replay, which lets two runs of the same game be compared turn by turn to detect when they diverge. Defaults to `False` to avoid the cost of hashing every cell."""
+    skip_penalty: int
+    """The points a player loses every time they skip a turn, see `skip_player_turn`. Defaults to 0, i.e. no penalty."""
+    idle_patience: int
+    """The number of turns in a row an ant can stay idle before it has to eat, to discourage passive play. An ant is idle on a turn when it doesn't move, harvest, pick up or deliver food, or attack an enemy, e.g. because it got no action or its move was blocked. Ants that were just spawned and ants of players that skipped the turn are not idle. Once an ant has been idle for this many turns, it eats one food from its player's hive and its count starts over, or it starves to death if the hive is empty. Defaults to `0`, i.e. ants can stay idle forever."""
+    max_hive: Optional[int]
+    """The maximum amount of food a player can store in their hive. Food harvested while the hive is full is still removed from the map, but it's not stored. Defaults to `None`, i.e. no limit."""
+    per_hill_hive: bool
+    """Whether the hive of each player is split into a bucket per hill, to model localized production. Harvested food is banked at the player's live hill nearest to the ant that harvested it, and it's not stored when the player has no live hill left. Each hill only spawns ants with the food banked at it, while digging and idle ants eat from the hills nearest to the ant. Food can be moved between hills with `transfer_hive_food`. The hive in the game state is still the total food of each player, across all their hills. When a hill is razed, the food banked at it is moved to the player's nearest live hill. Can only be set before the game starts, otherwise it raises an exception. Defaults to `False`, i.e. a single hive shared by all the hills."""
+    max_food_per_ant_per_turn: int
+    """The maximum amount of food each ant can harvest on a turn, e.g. when it's surrounded by food. Must be at least `1`. Defaults to `1`."""
+    stalemate_patience: Optional[int]
+    """The number of turns in a stalemate after which the game ends, won by the player first in the ranking if any. A turn is in a stalemate when no ant can walk to any food, and no ant can walk to an enemy ant or to an enemy live hill, e.g. because the ants are walled off from each other by water. Defaults to `None`, i.e. stalemates are not detected."""
+    reachable_food_only: bool
+    """Whether only the food some ant can reach counts when checking if the food is not being gathered, which ends the game with `FinishedReason.TooMuchFood` when it lasts too long. With this option, food walled off from all the ants, e.g. by water, is left out so it can't end the game on its own. Defaults to `False`."""
+    starting_ants_per_hill: int
+    """The number of ants each hill starts the game with. The first ant is spawned on the hill and the rest on the land around it. Defaults to 1."""
+    starting_food_per_hill: int
+    """The amount of food spawned around each hill at the start of the game. Food is only spawned on the land right next to the hill, so if there isn't enough land, as much food as fits is spawned. Defaults to `3`."""
+    spawn_burst: int
+    """The maximum number of ants each hill can spawn from the hive on a turn. The first ant is spawned on the hill and the rest on the land right next to it, as long as there is food in the hive. Must be at least 1. Defaults to `1`."""
+
+    def add_event_observer(self, observer: Callable[[int, Event], None]) -> None:
+        """Registers a callable that is called with the turn and the event for every event in the game, as they happen and in the same order as in the replay, e.g. to compute custom metrics or shape rewards.
+
+        Observers are called whether or not a replay is saved. If the callable raises an exception, the call that played the turn, e.g. `update`, raises it once the turn is played.
+
+        :param observer: The callable to call with the turn and the event.
+        :type observer: Callable[[int, Event], None]
+        """
+
+    def set_reward_hook(
+        self,
+        hook: Callable[[GameState, List[Action], GameState, List[Event]], List[float]],
+    ) -> None:
+        """Sets a callable that computes the reward of each player at the end of every turn, e.g. to train agents with custom rewards without diffing the states.
+
+        It's called with the state before the turn, the actions, the state after the turn and the events of the turn, and must return a list with the reward of each player. The rewards are returned as `GameState.rewards`. If it's set after calling `start`, the first rewards are computed from the state at the time it's set. If the callable raises an exception, the call that played the turn, e.g. `update`, raises it once the turn is played.
+
+        :param hook: The callable that computes the reward of each player.
+        :type hook: Callable[[GameState, List[Action], GameState, List[Event]], List[float]]
+        """
+
+    def ant_by_id(self, id: str) -> Optional[Ant]:
+        """Returns the live ant with the given id. Ant ids are stable across turns, so agents can use them to keep track of each ant.
+
+        :param id: The id of the ant.
+        :type id: str
+        :return: The ant, or `None` if there is no such ant on the map.
+        :rtype: Optional[Ant]
+        """
+
+    def legal_moves(self, row: int, col: int) -> List[Direction]:
+        """Returns the directions in which the ant at the given cell can move, in the order North, East, South and West.
+
+        Moves off the map or onto water, food or a dead ant are not legal. Moving onto a live ant is, even though both ants die.
+
+        :param row: The row of the ant.
+        :type row: int
+        :param col: The column of the ant.
+        :type col: int
+        :return: The legal directions, empty if there is no live ant at the cell.
+        :rtype: List[Direction]
+        """
+
+    def entities(self) -> List[Entity]:
+        """Returns every entity on the map, one per occupied cell in board order, regardless of the field of vision of any ant.
+
+        This is full information meant for spectators such as custom renderers, so it shouldn't be given to competing agents.
+
+        :return: The entities on the map.
+        :rtype: List[Entity]
+        """
+
+    def board_hash(self) -> int:
+        """Returns a hash of the board, i.e. of the position and state of every entity on the map.
+
+        It doesn't depend on the order the entities are visited in nor on their random ids, so two runs with the same map, seed and actions have the same hash on every turn, and a different hash means the runs diverged.
+
+        :return: The hash of the board.
+        :rtype: int
+        """
+
+    def debug_state(self) -> GameState:
+        """Returns the current state with the whole board revealed in `GameState.board`, regardless of the field of vision of any ant.
+
+        This is full information meant to debug agents, e.g. to compare what they observe with the actual board, so it shouldn't be given to competing agents. The states returned by `start` and `update` never reveal the board.
+
+        :return: The current state with the board revealed.
+        :rtype: GameState
+        """
+
+    def place_ant(self, player: int, row: int, col: int) -> None:
+        """Places an ant of the given player on the map to set up a scenario. Placed entities are kept every time the game is started.
+
+        :raises ValueError: If the game has started, the player is invalid, or the cell is out of bounds or occupied.
+
+        :param player: The player that owns the ant.
+        :type player: int
+        :param row: The row of the cell.
+        :type row: int
+        :param col: The column of the cell.
+        :type col: int
+        """
+
+    def place_food(self, row: int, col: int) -> None:
+        """Places food on the map to set up a scenario. Placed entities are kept every time the game is started.
+
+        :raises ValueError: If the game has started, or the cell is out of bounds or occupied.
+
+        :param row: The row of the cell.
+        :type row: int
+        :param col: The column of the cell.
+        :type col: int
+        """
+
+    def place_hill(self, player: int, row: int, col: int, alive: bool) -> None:
+        """Places a hill of the given player on the map to set up a scenario. Placed entities are kept every time the game is started.
+
+        :raises ValueError: If the game has started, the player is invalid, or the cell is out of bounds or occupied.
+
+        :param player: The player that owns the hill.
+        :type player: int
+        :param row: The row of the cell.
+        :type row: int
+        :param col: The column of the cell.
+        :type col: int
+        :param alive: Whether the hill is alive, i.e. not razed.
+        :type alive: bool
+        """
+
+    def hill_hive(self, row: int, col: int) -> int:
+        """Returns the food banked at the hill at the given location, see `per_hill_hive`.
+
+        :param row: The row of the hill.
+        :type row: int
+        :param col: The column of the hill.
+        :type col: int
+        :return: The food banked at the hill, `0` if there is no food banked there, e.g. because the hive is not split per hill.
+        :rtype: int
+        """
+
+    def transfer_hive_food(self, player: int, from_hill: Tuple[int, int], to_hill: Tuple[int, int], amount: int) -> int:
+        """Moves food banked at one of the player's hills to another one of their live hills, see `per_hill_hive`.
+
+        At most the food banked at the source hill is moved. Raises an exception if the hive is not split per hill, or if the destination is not a live hill of the player.
+
+        :param player: The player that owns both hills.
+        :type player: int
+        :param from_hill: The row and column of the hill to take the food from.
+        :type from_hill: Tuple[int, int]
+        :param to_hill: The row and column of the hill to bank the food at.
+        :type to_hill: Tuple[int, int]
+        :param amount: The amount of food to move.
+        :type amount: int
+        :return: The amount of food actually moved.
+        :rtype: int
+        """
+
+    def skip_player_turn(self, player: int) -> None:
+        """Marks the player as having failed to respond in time, e.g. because they ran out of their time budget.
+
+        On the next update, all the actions for the ants of this player are ignored so their ants hold their position, and the player loses the points set by `skip_penalty`. The rest of the turn is played as usual.
+
+        :param player: The player that skips the next turn.
+        :type player: int
+        """
+
+    def eliminate_player(self, player: int) -> None:
+        """Eliminates the player from the game, e.g. because they resigned or timed out too many times.
+
+        All of their ants are killed and removed from the map, and their hills become land so no more ants are spawned for them. The player is still listed in the game state, with no ants, but no longer counts as a remaining player for the endgame.
+
+        :param player: The player to eliminate.
+        :type player: int
+        """
+
     def start(self) -> GameState:
         """Starts the game.
 
@@ -165,6 +704,16 @@ class Game:
         :return: The initial game state.
         :rtype: GameState
         """
+    def start_with_seed(self, seed: int) -> GameState:
+        """Starts the game after reseeding it, as if it had been created with the given seed.
+
+        This allows running many differently seeded games with the same `Game`, instead of creating a new one each time.
+
+        :param seed: The new seed for the random number generator. Each player gets its own stream derived from it.
+        :type seed: int
+        :return: The initial game state.
+        :rtype: GameState
+        """
 
     def update(self, actions: List[Action]) -> GameState:
         """Updates the game state with the given actions.
@@ -175,31 +724,159 @@ class Game:
         :rtype: GameState
         """
 
+    def try_update(self, actions: List[Action]) -> GameState:
+        """Updates the game state with the given actions, raising an error instead of panicking if the game has not started or is already finished.
+
+        :param actions: The actions to take for each ant.
+        :type actions: List[Action]
+        :return: The updated game state.
+        :rtype: GameState
+        :raises RuntimeError: If the game has not started or is already finished.
+        """
+
+    def validate(self) -> None:
+        """Checks that the game is configured consistently, raising an error otherwise. The game itself is permissive, so this check is opt-in, e.g. for tournament setups.
+
+        Currently, the attack radius must not be larger than the field of vision of any player, since their ants could otherwise be attacked by enemies they can't see.
+
+        :raises RuntimeError: If the game is not configured consistently.
+        """
+
+    def fast_forward(self, actions_per_turn: List[List[Action]]) -> GameState:
+        """Fast-forwards the game by updating it with each turn's actions in order, e.g. to reach a recorded turn. Stops early if the game finishes, in which case the remaining actions are ignored.
+
+        :param actions_per_turn: The actions to take for each ant, for each turn.
+        :type actions_per_turn: List[List[Action]]
+        :return: The game state after the last turn played, or the current state if there are no turns to play.
+        :rtype: GameState
+        """
+
+    def path(
+        self, from_: Tuple[int, int], to: Tuple[int, int], avoid_entities: bool = False
+    ) -> Optional[List[Direction]]:
+        """Returns the directions of the shortest path between two cells.
+
+        Water always blocks the path, while food and ants only do if `avoid_entities` is `True`. The destination itself is never avoided.
+
+        :param from_hill: The `(row, col)` to start from.
+        :type from_hill: Tuple[int, int]
+        :param to: The `(row, col)` to reach.
+        :type to: Tuple[int, int]
+        :param avoid_entities: Whether food and ants block the path.
+        :type avoid_entities: bool
+        :return: The directions to follow, or `None` if the destination can't be reached.
+        :rtype: Optional[List[Direction]]
+        """
+
+    def distance(
+        self, from_: Tuple[int, int], to: Tuple[int, int], avoid_entities: bool = False
+    ) -> Optional[int]:
+        """Returns the number of moves of the shortest path between two cells.
+
+        See `path` for which cells block the path.
+
+        :param from_hill: The `(row, col)` to start from.
+        :type from_hill: Tuple[int, int]
+        :param to: The `(row, col)` to reach.
+        :type to: Tuple[int, int]
+        :param avoid_entities: Whether food and ants block the path.
+        :type avoid_entities: bool
+        :return: The number of moves, or `None` if the destination can't be reached.
+        :rtype: Optional[int]
+        """
+
+    def nearest_food(
+        self, row: int, col: int, within_fov: bool = False
+    ) -> Optional[Tuple[int, int]]:
+        """Returns the location of the food closest to the given cell by the number of moves.
+
+        :param row: The row of the cell, usually the location of an ant.
+        :type row: int
+        :param col: The column of the cell, usually the location of an ant.
+        :type col: int
+        :param within_fov: Whether to only consider food within the field of vision of the ant in the cell, so that agents don't use information they can't see.
+        :type within_fov: bool
+        :return: The `(row, col)` of the nearest food, or `None` if no food can be reached.
+        :rtype: Optional[Tuple[int, int]]
+        """
+
+    def regions(self) -> List[List[Optional[int]]]:
+        """Returns the connected region of each cell, where water belongs to no region.
+
+        Two cells are in the same region if an ant can walk from one to the other, ignoring food and other ants. Regions are numbered from 0 in the order their first cell appears, row by row.
+
+        :return: The grid of region ids, `None` for water.
+        :rtype: List[List[Optional[int]]]
+        """
+
+    def region_of(self, row: int, col: int) -> Optional[int]:
+        """Returns the id of the connected region of the given cell.
+
+        :param row: The row of the cell.
+        :type row: int
+        :param col: The column of the cell.
+        :type col: int
+        :return: The region id, or `None` if the cell is water.
+        :rtype: Optional[int]
+        """
+
+    def territory_sizes(self) -> List[int]:
+        """Returns the number of cells controlled by each player.
+
+        A cell is controlled by the player with the closest live hill by the number of moves. Cells equally close to different players are contested and not counted.
+
+        :return: The number of cells controlled by each player where the index is the player number.
+        :rtype: List[int]
+        """
+
     def draw(self) -> None:
         """Draws the current state of the game."""
 
+    def render(self) -> str:
+        """Renders the current state of the game to a plain string, i.e. the same visual as `draw` but without colors or terminal commands.
+
+        :return: The rendered game.
+        :rtype: str
+        """
+
 class GameState:
     """A class representing the state of the game.
 
     Attributes:
         turn (int): The current turn number.
-        scores (List[int]): The scores for each player.
+        max_turns (int): The maximum number of turns before the game ends.
+        turns_remaining (int): The number of turns left before the turn limit is reached.
+        progress (float): The fraction of the maximum number of turns that has been played, from 0 to 1.
+        scores (List[int]): The scores for each player. Scores never go below 0 unless `Game.signed_scores` is enabled.
         ants (List[List[Ant]]): The list of ants for each player.
-        hive (List[int]): The list of the number of ants in each player's hive.
+        hive (List[int]): The amount of food stored in each player's hive. Each unit of food is spent to spawn one ant.
+        total_food (int): The total amount of food on the map.
         turn_stats (List[TurnStats]): The list of turn stats for each player.
         finished (bool): Whether the game has finished.
         finished_reason (Optional[FinishedReason]): The reason the game finished. Only present if the game has finished.
-        winner (Optional[int]): The player that won the game. Only present if the game has finished and there is a winner.
+        winner (Optional[int]): The player that won the game, the first in the ranking when it finished. Only present if the game has finished and the first two players were not tied.
+        explored (List[List[List[bool]]]): The cells that each player has ever seen, indexed by player, row and column. Empty unless `Game.track_explored` is enabled.
+        rewards (Optional[List[float]]): The reward of each player for the last turn, as computed by the reward hook. `None` if there is no reward hook.
+        board_hash (Optional[int]): The hash of the board at the end of the turn. Only present if `Game.track_board_hash` is enabled.
+        board (Optional[List[Entity]]): Every entity on the map regardless of the field of vision of any ant. Only present in the state returned by `Game.debug_state`.
     """
 
     turn: int
     """The current turn number."""
+    max_turns: int
+    """The maximum number of turns before the game ends."""
+    turns_remaining: int
+    """The number of turns left before the turn limit is reached."""
+    progress: float
+    """The fraction of the maximum number of turns that has been played, from 0 to 1."""
     scores: List[int]
-    """The scores for each player."""
+    """The scores for each player. Scores never go below 0 unless `Game.signed_scores` is enabled."""
     ants: List[List[Ant]]
     """The list of ants for each player."""
     hive: List[int]
-    """The list of the number of ants in each player's hive."""
+    """The amount of food stored in each player's hive. Each unit of food is spent to spawn one ant. This is full information, i.e. it includes the hive of every player."""
+    total_food: int
+    """The total amount of food on the map."""
     turn_stats: List[TurnStats]
     """The list of turn stats for each player."""
     finished: bool
@@ -207,7 +884,254 @@ class GameState:
     finished_reason: Optional[FinishedReason]
     """The reason the game finished. Only present if the game has finished."""
     winner: Optional[int]
-    """The player that won the game. Only present if the game has finished and there is a winner."""
+    """The player that won the game, the first in the ranking when it finished. Only present if the game has finished and the first two players were not tied."""
+    explored: List[List[List[bool]]]
+    """The cells that each player has ever seen, indexed by player, row and column. Empty unless `Game.track_explored` is enabled."""
+    rewards: Optional[List[float]]
+    """The reward of each player for the last turn, as computed by the reward hook. `None` if there is no reward hook, see `Game.set_reward_hook`."""
+    board_hash: Optional[int]
+    """The hash of the board at the end of the turn, see `Game.board_hash`. Only present if `Game.track_board_hash` is enabled, otherwise it's `None`."""
+    board: Optional[List[Entity]]
+    """Every entity on the map regardless of the field of vision of any ant, see `Game.entities`. Only present in the state returned by `Game.debug_state`, `None` in the states given to agents."""
+
+    def as_arrays(self) -> StateArrays:
+        """Returns the ants of all players, and their fields of vision, as flat parallel lists.
+
+        This allows building tensors with vectorized operations instead of reading each ant one attribute at a time.
+
+        :return: The ants as flat parallel lists.
+        :rtype: StateArrays
+        """
+
+    def to_bytes(self) -> bytes:
+        """Returns the state in a compact binary encoding, e.g. to send it to remote agents over a socket.
+
+        All numbers are little endian and the first byte is the version of the encoding. It's followed by the turn, the scores, hive and turn stats of each player, the ants of each player with their fields of vision, where entities are located relative to the ant, and the explored cells packed as bits.
+
+        :return: The encoded state, which can be decoded with `from_bytes`.
+        :rtype: bytes
+        """
+
+    @staticmethod
+    def from_bytes(bytes: bytes) -> GameState:
+        """Decodes a state encoded with `to_bytes`.
+
+        :raises ValueError: If the bytes are not a valid encoded state, e.g. if they were encoded with another version or place something outside the map.
+
+        :param bytes: The encoded state.
+        :type bytes: bytes
+        :return: The decoded state.
+        :rtype: GameState
+        """
+
+class HillRazeMode:
+    """An enum representing what happens to a hill after an enemy ant razes it.
+
+    Attributes:
+        Disable (str): The hill stays on the map but it's no longer alive, so it stops spawning ants. This is the default.
+        Remove (str): The hill is removed from the map, so the cell becomes land once the ant leaves it or dies.
+        ScoreOnly (str): The hill keeps spawning ants for its owner and only the scores change. Each hill can only be razed once.
+    """
+
+    Disable: str
+    """The hill stays on the map but it's no longer alive, so it stops spawning ants. This is the default."""
+    Remove: str
+    """The hill is removed from the map, so the cell becomes land once the ant leaves it or dies."""
+    ScoreOnly: str
+    """The hill keeps spawning ants for its owner and only the scores change. Each hill can only be razed once."""
+
+class MapTransform:
+    """An enum representing a transformation of a map, e.g. to generate variants of a map for data augmentation.
+
+    Attributes:
+        Rotate90 (str): Rotates the map 90 degrees clockwise, swapping its width and height.
+        MirrorHorizontal (str): Mirrors the map from left to right.
+        MirrorVertical (str): Mirrors the map from top to bottom.
+    """
+
+    Rotate90: str
+    """Rotates the map 90 degrees clockwise, swapping its width and height."""
+    MirrorHorizontal: str
+    """Mirrors the map from left to right."""
+    MirrorVertical: str
+    """Mirrors the map from top to bottom."""
+
+class Tile:
+    """An enum representing what a character of a map stands for, see `TileMap`.
+
+    Attributes:
+        Land: An empty land cell.
+        Water: Water.
+        Food: Food.
+        Ant: An ant of the given player.
+        AntOnHill: An ant of the given player standing on its hill.
+        Hill: A hill of the given player.
+    """
+
+    class Land(Tile):
+        """An empty land cell."""
+
+        def __init__(self) -> None: ...
+
+    class Water(Tile):
+        """Water."""
+
+        def __init__(self) -> None: ...
+
+    class Food(Tile):
+        """Food."""
+
+        def __init__(self) -> None: ...
+
+    class Ant(Tile):
+        """An ant of the given player."""
+
+        def __init__(self, player: int) -> None: ...
+
+    class AntOnHill(Tile):
+        """An ant of the given player standing on its hill."""
+
+        def __init__(self, player: int) -> None: ...
+
+    class Hill(Tile):
+        """A hill of the given player."""
+
+        def __init__(self, player: int) -> None: ...
+
+class TileMap:
+    """A class representing the characters of a map and the tiles they stand for, e.g. to load maps authored in other tools with `translate_map`.
+
+    Characters that are not in the table keep their usual meaning, e.g. `%` for water.
+
+    :param tiles: The tile each character stands for, overriding its usual meaning if any.
+    :type tiles: Dict[str, Tile]
+    """
+
+    def __init__(self, tiles: Dict[str, Tile]) -> None: ...
+
+class PlayerStats:
+    """A class representing the cumulative stats of a player over the whole game.
+
+    Attributes:
+        food_harvested (int): The amount of food harvested by the player.
+        ants_spawned (int): The number of ants spawned by the player, including the starting ants.
+        ants_killed (int): The number of ants killed by the player.
+        hills_razed (int): The number of hills razed by the player.
+        ants_lost (int): The number of ants lost by the player, either in battle or from collisions.
+        hills_lost (int): The number of hills lost by the player.
+    """
+
+    food_harvested: int
+    """The amount of food harvested by the player."""
+    ants_spawned: int
+    """The number of ants spawned by the player, including the starting ants."""
+    ants_killed: int
+    """The number of ants killed by the player."""
+    hills_razed: int
+    """The number of hills razed by the player."""
+    ants_lost: int
+    """The number of ants lost by the player, either in battle or from collisions."""
+    hills_lost: int
+    """The number of hills lost by the player."""
+
+class StartingScore:
+    """An enum representing the score each player starts the game with.
+
+    Maps can give some players a head start on top of it, with optional `bonus <player> <points>` lines.
+
+    Attributes:
+        HillCount: Each player starts with 1 point per hill. This is the default.
+        Zero: Every player starts with 0 points.
+        Fixed: Every player starts with the given number of points.
+    """
+
+    class HillCount(StartingScore):
+        """Each player starts with 1 point per hill. This is the default."""
+
+        def __init__(self) -> None: ...
+
+    class Zero(StartingScore):
+        """Every player starts with 0 points."""
+
+        def __init__(self) -> None: ...
+
+    class Fixed(StartingScore):
+        """Every player starts with the given number of points."""
+
+        def __init__(self, points: int) -> None: ...
+
+class StateArrays:
+    """A class representing the ants of a game state as flat, parallel lists, where the index is the ant number. Ants are ordered player by player, as in `GameState.ants`.
+
+    Attributes:
+        ids (List[str]): The id of each ant.
+        rows (List[int]): The row of each ant.
+        cols (List[int]): The column of each ant.
+        players (List[int]): The player who owns each ant.
+        alive (List[bool]): Whether each ant is alive.
+        fov_offsets (List[int]): Where the field of vision of each ant starts and ends in the `fov_*` lists, with one more entry than there are ants. The field of vision of ant `i` is `fov_offsets[i]:fov_offsets[i + 1]`.
+        fov_names (List[str]): The name of each entity seen.
+        fov_rows (List[int]): The row of each entity seen.
+        fov_cols (List[int]): The column of each entity seen.
+        fov_players (List[int]): The player who owns each entity seen, or `-1` if it doesn't belong to a player, e.g. food.
+        fov_alive (List[bool]): Whether each entity seen is alive, `False` if it doesn't have an alive state, e.g. food.
+    """
+
+    ids: List[str]
+    """The id of each ant."""
+    rows: List[int]
+    """The row of each ant."""
+    cols: List[int]
+    """The column of each ant."""
+    players: List[int]
+    """The player who owns each ant."""
+    alive: List[bool]
+    """Whether each ant is alive."""
+    fov_offsets: List[int]
+    """Where the field of vision of each ant starts and ends in the `fov_*` lists, with one more entry than there are ants. The field of vision of ant `i` is `fov_offsets[i]:fov_offsets[i + 1]`."""
+    fov_names: List[str]
+    """The name of each entity seen."""
+    fov_rows: List[int]
+    """The row of each entity seen."""
+    fov_cols: List[int]
+    """The column of each entity seen."""
+    fov_players: List[int]
+    """The player who owns each entity seen, or `-1` if it doesn't belong to a player, e.g. food."""
+    fov_alive: List[bool]
+    """Whether each entity seen is alive, `False` if it doesn't have an alive state, e.g. food."""
+
+class TurnPhase:
+    """An enum representing a phase of a turn. Every turn runs all the phases, in the order set by `Game.phase_order`.
+
+    Attributes:
+        Move (str): The ants move according to the actions.
+        Attack (str): The ants fight the enemies within their attack range.
+        Raze (str): The enemy hills with a live ant on them are razed.
+        SpawnAnts (str): The hives spawn new ants on their hills.
+        Harvest (str): The ants harvest the food around them.
+        SpawnFood (str): New food spawns on the map.
+    """
+
+    Move: str
+    """The ants move according to the actions."""
+    Attack: str
+    """The ants fight the enemies within their attack range."""
+    Raze: str
+    """The enemy hills with a live ant on them are razed."""
+    SpawnAnts: str
+    """The hives spawn new ants on their hills."""
+    Harvest: str
+    """The ants harvest the food around them."""
+    SpawnFood: str
+    """New food spawns on the map."""
+
+    @staticmethod
+    def default_order() -> List[TurnPhase]:
+        """Returns the order used by the original game, which is the default.
+
+        :return: Move, attack, raze, spawn ants, harvest and spawn food.
+        :rtype: List[TurnPhase]
+        """
 
 class TurnStats:
     """A class representing the stats for a turn.
@@ -52,18 +52,21 @@ class Event:
     """A class representing an event that occurred in the game.
 
     Attributes:
-        event_type (str): The type of event. "Spawn", "Remove", "Move" or "Attack".
-        entity (str): The entity associated with the event. "Ant", "Food" or "Hill".
+        event_type (str): The type of event. "Spawn", "Remove", "Harvest", "Move" or "Attack".
+        entity (str): The entity associated with the event. "Ant", "Food", "Hill" or "Water".
         entity_id (str): The entity ID associated with the
         player (int): The player that owns the entity.
         location (tuple[int]): The location of the entity as a tuple of (row, col).
         destination (tuple[int]): The destination of the entity as a tuple of (row, col). Only used for "Move" and "Attack" events.
+        harvester_id (Optional[str]): The ID of the ant that harvested the food. Only used for "Harvest" events and for food picked up by a carrying ant.
+        initial (bool): Whether the entity was spawned as part of the initial setup of the game. Only used for "Spawn" events.
+        target_id (Optional[str]): The ID of the attacked ant. Only used for "Attack" events.
     """
 
     event_type: str
-    """The type of event. "Spawn", "Remove", "Move" or "Attack"."""
+    """The type of event. "Spawn", "Remove", "Harvest", "Move" or "Attack"."""
     entity: str
-    """The entity associated with the event. "Ant", "Food" or "Hill"."""
+    """The entity associated with the event. "Ant", "Food", "Hill" or "Water"."""
     entity_id: str
     """The entity ID associated with the event."""
     player: int
@@ -72,6 +75,12 @@ class Event:
     """The location of the entity as a tuple of (row, col)."""
     destination: tuple[int]
     """The destination of the entity as a tuple of (row, col). Only used for "Move" and "Attack" events."""
+    harvester_id: Optional[str] = None
+    """The ID of the ant that harvested the food. Only used for "Harvest" events and for food picked up by a carrying ant."""
+    initial: bool = False
+    """Whether the entity was spawned as part of the initial setup of the game. Only used for "Spawn" events."""
+    target_id: Optional[str] = None
+    """The ID of the attacked ant. Only used for "Attack" events."""
 
 
 @dataclass
@@ -109,7 +118,16 @@ class Turn:
         # Group events by phase
         events = defaultdict(list)
         for event in dict["events"]:
-            phase = TurnPhase(event["event_type"])
+            # Blocked moves don't change the map, so there is nothing to draw for them
+            if event["event_type"] == "BlockedMove":
+                continue
+
+            # Harvested food leaves the map, so it's drawn in the same phase as any other removal
+            phase = (
+                TurnPhase.Remove
+                if event["event_type"] == "Harvest"
+                else TurnPhase(event["event_type"])
+            )
             events[phase].append(Event(**event))
 
         return cls(
@@ -794,7 +812,7 @@ class Visualizer:
         for event in events:
             if event.event_type == "Spawn":
                 self._replay_spawn(event)
-            elif event.event_type == "Remove":
+            elif event.event_type in ["Remove", "Harvest"]:
                 self._replay_remove(event)
             elif event.event_type == "Move":
                 self._replay_move(event)
@@ -814,6 +832,9 @@ class Visualizer:
             )
         elif event.entity == "Food":
             self._food[location] = self._spawn_food(location)
+        elif event.entity == "Hill":
+            # Hills placed after the start of the game; a hill placed razed is razed by the `Remove` event that follows
+            self._hills[location] = self._spawn_hill(location, event.player)
         else:
             raise RuntimeError(
                 f"Invalid 'Spawn' event for entity '{event.entity}': {event}."
@@ -831,6 +852,10 @@ class Visualizer:
             location = tuple(event.location)
             # When hills are removed they are "razed", not removed from the map
             self._hills[location].alive = False
+        elif event.entity == "Water":
+            # Water is removed when it's dug by an ant
+            location = tuple(event.location)
+            self._water = [water for water in self._water if water.location != location]
         else:
             raise RuntimeError(
                 f"Invalid 'Remove' event for entity '{event.entity}': {event}."
@@ -903,19 +928,7 @@ class Visualizer:
 
                 # Max 10 players
                 if "0" <= char <= "9":
-                    player = int(char)
-                    sprites = [
-                        self._hill_sprites[0].copy(),
-                        self._hill_sprites[1].copy(),
-                    ]
-                    self._hills[location] = Hill(
-                        id=f"Hill(p={player},loc=({location}))",
-                        location=location,
-                        scale=self._scale,
-                        alive=True,
-                        player=player,
-                        sprites=sprites,
-                    )
+                    self._hills[location] = self._spawn_hill(location, int(char))
                 elif char == "*":
                     self._food[location] = self._spawn_food(location)
                 elif char == "%":
@@ -944,6 +957,20 @@ class Visualizer:
             player=player,
         )
 
+    def _spawn_hill(self, location: tuple[int], player: int) -> Hill:
+        sprites = [
+            self._hill_sprites[0].copy(),
+            self._hill_sprites[1].copy(),
+        ]
+        return Hill(
+            id=f"Hill(p={player},loc=({location}))",
+            location=location,
+            scale=self._scale,
+            alive=True,
+            player=player,
+            sprites=sprites,
+        )
+
     def _spawn_food(self, location: tuple[int]) -> Food:
         return Food(
             id=f"Food(loc=({location}))",
@@ -0,0 +1,55 @@
+#[cfg(feature = "bench")]
+use ants_engine::benchmark_map_storage;
+use ants_engine::{Action, Game};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+fn main() {
+    let map_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/maps/tutorial.map");
+    let map_contents = match fs::read_to_string(map_file) {
+        Ok(contents) => contents,
+        Err(e) => panic!("Error reading map file: {}", e),
+    };
+
+    // A fixed scenario so that runs can be compared with each other
+    let mut game = Game::new(&map_contents, 4, 5, 1, 5, 1500, 500, 0, None);
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut ants_processed = 0;
+
+    let start = Instant::now();
+    let state = game.run_to_completion(|state| {
+        // Every ant of every player moves in a random direction
+        let actions: Vec<Action> = state
+            .ants
+            .iter()
+            .flatten()
+            .map(|ant| Action::new(ant.row, ant.col, rng.gen()))
+            .collect();
+        ants_processed += actions.len();
+
+        actions
+    });
+    let elapsed = start.elapsed();
+
+    println!("Turns: {}", state.turn);
+    println!("Ants processed: {}", ants_processed);
+    println!("Elapsed: {:.3}s", elapsed.as_secs_f64());
+    println!(
+        "Turns per second: {:.1}",
+        state.turn as f64 / elapsed.as_secs_f64()
+    );
+    println!("Game finished due to: {:?}", state.finished_reason.unwrap());
+
+    // The same map operations on the boxed map the game plays on and on the compact prototype
+    #[cfg(feature = "bench")]
+    {
+        let iterations = 100;
+        let (boxed, compact) = benchmark_map_storage(&map_contents, iterations);
+        println!("Map storage over {} iterations:", iterations);
+        println!("  Boxed: {:.3}s", boxed.as_secs_f64());
+        println!("  Compact: {:.3}s", compact.as_secs_f64());
+    }
+}
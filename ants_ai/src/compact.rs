@@ -0,0 +1,454 @@
+//! A prototype of a compact storage for the map, with the same methods as `Map` so that the two can be compared.
+//!
+//! `Map` stores a boxed entity per occupied cell, so every read goes through a pointer and a vtable and the map
+//! can't be cheaply copied. Here every cell is a small `TileKind` in a dense list, and ants, the only entities with
+//! more state than a few bytes, live in a side table. Cloning the whole map, e.g. for a snapshot, is then a copy of
+//! both lists.
+//!
+//! The game still plays on `Map`, see `benchmark_map_storage` with the `bench` feature to compare both representations.
+
+use crate::map::{
+    cells_within, in_line_of_sight, offset_cell, BlockedMoveReason, DistanceMetric, Map,
+};
+#[cfg(feature = "bench")]
+use std::hint::black_box;
+#[cfg(feature = "bench")]
+use std::time::{Duration, Instant};
+
+/// Represents what is in a cell of a `CompactMap`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TileKind {
+    Land,
+    Water,
+    Food,
+    /// An ant, where the number is its slot in the side table of ants.
+    Ant(u32),
+    Hill {
+        player: u16,
+        alive: bool,
+    },
+    /// The remains of a dead ant of the given player.
+    Corpse(u16),
+}
+
+/// Represents the state of an ant of a `CompactMap`, the same as the one of an `Ant` entity.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompactAnt {
+    pub id: String,
+    pub player: usize,
+    pub alive: bool,
+    pub carrying: bool,
+    /// The player and whether it's alive of the hill the ant is standing on, if any.
+    pub on_ant_hill: Option<(usize, bool)>,
+    /// The index of the cell of the ant, to find it again when its slot changes.
+    cell: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct CompactMap {
+    width: usize,
+    height: usize,
+    players: usize,
+    wrap: bool,
+    tiles: Vec<TileKind>,
+    ants: Vec<CompactAnt>,
+}
+
+impl CompactMap {
+    pub fn parse(map_contents: &str) -> Result<CompactMap, String> {
+        Ok(CompactMap::from_map(&Map::parse(map_contents)?))
+    }
+
+    /// Creates a compact copy of the given map, where every entity keeps its owner and id.
+    pub fn from_map(map: &Map) -> CompactMap {
+        let mut compact = CompactMap {
+            width: map.width(),
+            height: map.height(),
+            players: map.players(),
+            wrap: map.wraps(),
+            tiles: vec![TileKind::Land; map.width() * map.height()],
+            ants: Vec::new(),
+        };
+
+        for (entity, row, col) in map.entities() {
+            match entity.name() {
+                "Ant" => compact.set_ant(
+                    row,
+                    col,
+                    CompactAnt {
+                        id: entity.id().to_string(),
+                        player: entity.player().unwrap(),
+                        alive: entity.alive().unwrap(),
+                        carrying: entity.carrying().unwrap(),
+                        on_ant_hill: entity
+                            .on_ant_hill()
+                            .map(|hill| (hill.player().unwrap(), hill.alive().unwrap())),
+                        cell: 0,
+                    },
+                ),
+                "Hill" => compact.set(
+                    row,
+                    col,
+                    TileKind::Hill {
+                        player: entity.player().unwrap() as u16,
+                        alive: entity.alive().unwrap(),
+                    },
+                ),
+                "Corpse" => {
+                    compact.set(row, col, TileKind::Corpse(entity.player().unwrap() as u16))
+                }
+                "Food" => compact.set(row, col, TileKind::Food),
+                "Water" => compact.set(row, col, TileKind::Water),
+                name => panic!("Entity {} can't be stored in a compact map.", name),
+            }
+        }
+
+        compact
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> TileKind {
+        self.tiles[row * self.width + col]
+    }
+
+    /// Returns the ant in the given cell, `None` if there is no ant.
+    pub fn ant(&self, row: usize, col: usize) -> Option<&CompactAnt> {
+        match self.get(row, col) {
+            TileKind::Ant(slot) => Some(&self.ants[slot as usize]),
+            _ => None,
+        }
+    }
+
+    pub fn ant_mut(&mut self, row: usize, col: usize) -> Option<&mut CompactAnt> {
+        match self.get(row, col) {
+            TileKind::Ant(slot) => Some(&mut self.ants[slot as usize]),
+            _ => None,
+        }
+    }
+
+    /// Sets the cell to anything but an ant, which is set with `set_ant` instead.
+    pub fn set(&mut self, row: usize, col: usize, value: TileKind) {
+        if matches!(value, TileKind::Ant(_)) {
+            panic!("Ants must be set with their state, use set_ant instead.");
+        }
+
+        self.remove(row, col);
+        self.tiles[row * self.width + col] = value;
+    }
+
+    pub fn set_ant(&mut self, row: usize, col: usize, mut ant: CompactAnt) {
+        self.remove(row, col);
+        ant.cell = row * self.width + col;
+        self.tiles[ant.cell] = TileKind::Ant(self.ants.len() as u32);
+        self.ants.push(ant);
+    }
+
+    pub fn remove(&mut self, row: usize, col: usize) {
+        let index = row * self.width + col;
+        if let TileKind::Ant(slot) = self.tiles[index] {
+            // The last ant takes the freed slot, so its cell has to point to it
+            self.ants.swap_remove(slot as usize);
+            if let Some(moved) = self.ants.get(slot as usize) {
+                self.tiles[moved.cell] = TileKind::Ant(slot);
+            }
+        }
+
+        self.tiles[index] = TileKind::Land;
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn players(&self) -> usize {
+        self.players
+    }
+
+    /// Returns the ants, row by row like `Map::ants`.
+    pub fn ants(&self) -> Vec<(&CompactAnt, usize, usize)> {
+        self.all(|tile| matches!(tile, TileKind::Ant(_)))
+            .into_iter()
+            .map(|(row, col)| (self.ant(row, col).unwrap(), row, col))
+            .collect()
+    }
+
+    pub fn food(&self) -> Vec<(usize, usize)> {
+        self.all(|tile| tile == TileKind::Food)
+    }
+
+    pub fn land(&self) -> Vec<(usize, usize)> {
+        self.all(|tile| tile == TileKind::Land)
+    }
+
+    pub fn land_around(&self, row: usize, col: usize) -> Vec<(usize, usize)> {
+        let mut lands = Vec::new();
+
+        // For each coordinate around the given one in all 8 directions, in the same order as `Map::land_around`
+        for i in -1..=1 {
+            for j in -1..=1 {
+                let cell = offset_cell((self.width, self.height), self.wrap, (row, col), (i, j));
+                if let Some((n_row, n_col)) = cell {
+                    if self.get(n_row, n_col) == TileKind::Land && !lands.contains(&(n_row, n_col))
+                    {
+                        lands.push((n_row, n_col));
+                    }
+                }
+            }
+        }
+
+        lands
+    }
+
+    /// Returns all the cells, including the center, that are within the radius of the center, see `Map::cells_within`.
+    pub fn cells_within(
+        &self,
+        center: (usize, usize),
+        radius2: usize,
+        metric: &DistanceMetric,
+        line_of_sight: bool,
+    ) -> Vec<(usize, usize)> {
+        cells_within(
+            (self.width, self.height),
+            self.wrap,
+            center,
+            radius2,
+            metric,
+            line_of_sight,
+            |cell| self.get(cell.0, cell.1) == TileKind::Water,
+        )
+    }
+
+    pub fn in_line_of_sight(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        in_line_of_sight((self.width, self.height), self.wrap, from, to, |cell| {
+            self.get(cell.0, cell.1) == TileKind::Water
+        })
+    }
+
+    /// Moves the ant in `from` to `to` with the same rules as `Map::move_entity`.
+    pub fn move_entity(&mut self, from: (usize, usize), to: (usize, usize)) -> bool {
+        if self.blocked_move_reason(from, to).is_some() {
+            return false;
+        }
+
+        // If there was a collision, both ants die
+        if self.ant(to.0, to.1).is_some() {
+            self.ant_mut(to.0, to.1).unwrap().alive = false;
+            self.ant_mut(from.0, from.1).unwrap().alive = false;
+
+            // Even though the ants died from a collision, a movement still occurred
+            return true;
+        }
+
+        // The ant leaves its hill behind, if any, and stands on the hill it moves to, if any
+        let mut ant = self.ant(from.0, from.1).unwrap().clone();
+        let left_behind = match ant.on_ant_hill {
+            Some((player, alive)) => TileKind::Hill {
+                player: player as u16,
+                alive,
+            },
+            None => TileKind::Land,
+        };
+        ant.on_ant_hill = match self.get(to.0, to.1) {
+            TileKind::Hill { player, alive } => Some((player as usize, alive)),
+            _ => None,
+        };
+
+        self.set(from.0, from.1, left_behind);
+        self.set_ant(to.0, to.1, ant);
+
+        true
+    }
+
+    /// Returns why an ant can't move from `from` to `to`, see `Map::blocked_move_reason`.
+    pub fn blocked_move_reason(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+    ) -> Option<BlockedMoveReason> {
+        if from == to || to.0 >= self.height || to.1 >= self.width {
+            return Some(BlockedMoveReason::OutOfBounds);
+        }
+
+        let is_live_ant = from.0 < self.height
+            && from.1 < self.width
+            && self.ant(from.0, from.1).is_some_and(|ant| ant.alive);
+        if !is_live_ant {
+            return Some(BlockedMoveReason::NoLiveAnt);
+        }
+
+        match self.get(to.0, to.1) {
+            TileKind::Water => Some(BlockedMoveReason::Water),
+            TileKind::Food => Some(BlockedMoveReason::Food),
+            TileKind::Ant(slot) if !self.ants[slot as usize].alive => {
+                Some(BlockedMoveReason::DeadAnt)
+            }
+            _ => None,
+        }
+    }
+
+    fn all(&self, filter: impl Fn(TileKind) -> bool) -> Vec<(usize, usize)> {
+        self.tiles
+            .iter()
+            .enumerate()
+            .filter(|(_, tile)| filter(**tile))
+            .map(|(index, _)| (index / self.width, index % self.width))
+            .collect()
+    }
+}
+
+/// Times the same workload on the boxed `Map` and on the `CompactMap` of the given map, returning both durations in that order.
+///
+/// On each iteration, the food, land and ants are listed, the cells each ant can see with line of sight are computed
+/// and every live ant tries to move one step east, so both maps change the same way across iterations.
+/// Panics if the map is malformed.
+#[cfg(feature = "bench")]
+pub fn benchmark_map_storage(map_contents: &str, iterations: usize) -> (Duration, Duration) {
+    let metric = DistanceMetric::Euclidean2;
+
+    let mut map = Map::parse(map_contents).unwrap();
+    let start = Instant::now();
+    for _ in 0..iterations {
+        black_box(map.food().len() + map.land().len());
+        let ants: Vec<(usize, usize)> = map
+            .ants()
+            .into_iter()
+            .map(|(_, row, col)| (row, col))
+            .collect();
+        for (row, col) in ants {
+            black_box(map.cells_within((row, col), 77, &metric, true).len());
+            black_box(map.land_around(row, col).len());
+            map.move_entity((row, col), (row, col + 1));
+        }
+    }
+    let boxed = start.elapsed();
+
+    let mut map = CompactMap::parse(map_contents).unwrap();
+    let start = Instant::now();
+    for _ in 0..iterations {
+        black_box(map.food().len() + map.land().len());
+        let ants: Vec<(usize, usize)> = map
+            .ants()
+            .into_iter()
+            .map(|(_, row, col)| (row, col))
+            .collect();
+        for (row, col) in ants {
+            black_box(map.cells_within((row, col), 77, &metric, true).len());
+            black_box(map.land_around(row, col).len());
+            map.move_entity((row, col), (row, col + 1));
+        }
+    }
+    let compact = start.elapsed();
+
+    (boxed, compact)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "\
+        rows 4
+        cols 5
+        players 2
+        m A.*%1
+        m .b%..
+        m *..a.
+        m ..%.B";
+
+    #[test]
+    fn when_creating_a_compact_map_it_has_the_same_cells_as_the_map() {
+        let map = Map::parse(MAP).unwrap();
+        let compact = CompactMap::from_map(&map);
+
+        assert_eq!(compact.food(), map.food());
+        assert_eq!(compact.land(), map.land());
+        let ants: Vec<(String, usize, usize)> = map
+            .ants()
+            .iter()
+            .map(|(ant, row, col)| (ant.id().to_string(), *row, *col))
+            .collect();
+        let compact_ants: Vec<(String, usize, usize)> = compact
+            .ants()
+            .iter()
+            .map(|(ant, row, col)| (ant.id.clone(), *row, *col))
+            .collect();
+        assert_eq!(compact_ants, ants);
+        assert_eq!(compact.ant(0, 0).unwrap().on_ant_hill, Some((0, true)));
+        assert_eq!(
+            compact.get(0, 4),
+            TileKind::Hill {
+                player: 1,
+                alive: true
+            }
+        );
+        for row in 0..map.height() {
+            for col in 0..map.width() {
+                assert_eq!(compact.land_around(row, col), map.land_around(row, col));
+                assert_eq!(
+                    compact.cells_within((row, col), 9, &DistanceMetric::Euclidean2, true),
+                    map.cells_within((row, col), 9, &DistanceMetric::Euclidean2, true)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn when_moving_ants_in_a_compact_map_they_move_like_in_the_map() {
+        let mut map = Map::parse(MAP).unwrap();
+        let mut compact = CompactMap::from_map(&map);
+        let moves = [
+            ((0, 0), (0, 1)),
+            ((1, 1), (1, 2)),
+            ((2, 3), (1, 3)),
+            ((1, 3), (0, 3)),
+            ((1, 3), (0, 4)),
+            ((3, 4), (2, 4)),
+            ((2, 4), (1, 4)),
+            ((0, 1), (1, 1)),
+            ((1, 1), (2, 1)),
+        ];
+
+        for (from, to) in moves {
+            assert_eq!(compact.move_entity(from, to), map.move_entity(from, to));
+        }
+
+        assert_eq!(compact.land(), map.land());
+        for (ant, row, col) in map.ants() {
+            let compact_ant = compact.ant(row, col).unwrap();
+            assert_eq!(compact_ant.id, ant.id());
+            assert_eq!(compact_ant.alive, ant.alive().unwrap());
+            assert_eq!(
+                compact_ant.on_ant_hill,
+                ant.on_ant_hill()
+                    .map(|hill| (hill.player().unwrap(), hill.alive().unwrap()))
+            );
+        }
+    }
+
+    #[test]
+    fn when_removing_an_ant_from_a_compact_map_the_other_ants_keep_their_state() {
+        let mut compact = CompactMap::parse(MAP).unwrap();
+        let last = compact.ant(3, 4).unwrap().clone();
+
+        compact.remove(0, 0);
+
+        assert!(compact.ant(0, 0).is_none());
+        assert_eq!(compact.ants().len(), 3);
+        assert_eq!(compact.ant(3, 4).unwrap(), &last);
+    }
+
+    #[test]
+    fn when_cloning_a_compact_map_the_clone_is_independent() {
+        let mut compact = CompactMap::parse(MAP).unwrap();
+        let snapshot = compact.clone();
+
+        compact.move_entity((2, 3), (2, 4));
+
+        assert!(snapshot.ant(2, 3).is_some());
+        assert!(snapshot.ant(2, 4).is_none());
+        assert!(compact.ant(2, 4).is_some());
+    }
+}
@@ -0,0 +1,513 @@
+//! A compact binary encoding of the game state, e.g. to send it to remote agents over a socket.
+//!
+//! # Layout
+//! All numbers are little endian. An optional number is encoded as the number itself, with its maximum value
+//! (e.g. `u32::MAX`) standing for `None`.
+//!
+//! * `u8` - The version of the encoding, see `ENCODING_VERSION`.
+//! * `u32` turn, `u32` max turns, `u32` total food, `u8` finished, `u8` finished reason (`0` for `None`,
+//!   then `LoneSurvivor`, `RankStabilized`, `TooMuchFood`, `TurnLimitReached` and `Stalemate`), `u32` optional winner.
+//! * `u16` number of players, then for each player: `i64` score, `u32` hive and the turn stats as 7 `u32`
+//!   (turn, food harvested, ants spawned, ants killed, hills razed, ants lost and hills lost).
+//! * For each player, `u32` number of ants, then for each ant:
+//!   * `u8` length of the id and its UTF-8 bytes, `u16` row, `u16` col, `u8` alive,
+//!     `u8` last direction (`0` for `None`, then `North`, `East`, `South` and `West`), `u8` last move succeeded,
+//!     `u8` carrying and `u16` idle turns.
+//!   * `u16` number of entities in the field of vision, then for each entity: `u8` name (`Ant`, `Corpse`, `Food`,
+//!     `Hill` or `Water`), `i16` row and `i16` col relative to the ant, `u16` optional player
+//!     and `u8` alive (`0` for `false`, `1` for `true` and `2` for `None`).
+//!   * `u16` number of visible cells, then for each cell: `i16` row and `i16` col relative to the ant.
+//! * `u16` rows and `u16` cols of the explored cells, both `0` when they are not tracked,
+//!   then for each player the cells packed as bits, row by row, in as many bytes as needed.
+//! * `u8` whether there is a board hash, then the `u64` board hash only if there is one. Every `u64` is a valid hash,
+//!   so unlike the other optional numbers it can't use its maximum value for `None`.
+//!
+//! The rewards are not encoded, they are only computed for the game that owns the reward hook.
+//! Neither is the revealed board of `Game::debug_state`, which is only meant for debugging.
+
+use crate::game::{Direction, FinishedReason, GameState, PlayerAnt, StateEntity, TurnStats};
+
+/// The version of the binary layout, the first byte of every encoded state.
+/// It must be bumped whenever the layout changes so that decoders can reject states they don't understand.
+pub const ENCODING_VERSION: u8 = 5;
+
+const ENTITY_NAMES: [&str; 5] = ["Ant", "Corpse", "Food", "Hill", "Water"];
+
+const FINISHED_REASONS: [FinishedReason; 5] = [
+    FinishedReason::LoneSurvivor,
+    FinishedReason::RankStabilized,
+    FinishedReason::TooMuchFood,
+    FinishedReason::TurnLimitReached,
+    FinishedReason::Stalemate,
+];
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::North,
+    Direction::East,
+    Direction::South,
+    Direction::West,
+];
+
+/// Encodes the game state in the binary layout described in this module.
+pub fn encode(state: &GameState) -> Vec<u8> {
+    let mut bytes = vec![ENCODING_VERSION];
+
+    put_u32(&mut bytes, state.turn);
+    put_u32(&mut bytes, state.max_turns);
+    put_u32(&mut bytes, state.total_food);
+    bytes.push(state.finished as u8);
+    bytes.push(code(&FINISHED_REASONS, state.finished_reason.as_ref()));
+    put_u32(&mut bytes, state.winner.unwrap_or(u32::MAX as usize));
+
+    put_u16(&mut bytes, state.scores.len());
+    for player in 0..state.scores.len() {
+        bytes.extend((state.scores[player] as i64).to_le_bytes());
+        put_u32(&mut bytes, state.hive[player]);
+
+        let stats = &state.turn_stats[player];
+        for value in [
+            stats.turn,
+            stats.food_harvested,
+            stats.ants_spawned,
+            stats.ants_killed,
+            stats.hills_razed,
+            stats.ants_lost,
+            stats.hills_lost,
+        ] {
+            put_u32(&mut bytes, value);
+        }
+    }
+
+    for ants in &state.ants {
+        put_u32(&mut bytes, ants.len());
+        for ant in ants {
+            encode_ant(&mut bytes, ant);
+        }
+    }
+
+    let rows = state.explored.first().map_or(0, |cells| cells.len());
+    let cols = state
+        .explored
+        .first()
+        .and_then(|cells| cells.first())
+        .map_or(0, |row| row.len());
+    put_u16(&mut bytes, rows);
+    put_u16(&mut bytes, cols);
+    for cells in &state.explored {
+        let bits = cells.iter().flatten().collect::<Vec<&bool>>();
+        for chunk in bits.chunks(8) {
+            let byte = chunk
+                .iter()
+                .enumerate()
+                .fold(0u8, |byte, (bit, seen)| byte | ((**seen as u8) << bit));
+            bytes.push(byte);
+        }
+    }
+
+    bytes.push(state.board_hash.is_some() as u8);
+    if let Some(hash) = state.board_hash {
+        bytes.extend(hash.to_le_bytes());
+    }
+
+    bytes
+}
+
+/// Decodes a game state encoded with `encode`.
+/// Returns an error if the bytes are not a valid encoded state.
+pub fn decode(bytes: &[u8]) -> Result<GameState, String> {
+    let mut reader = Reader { bytes, position: 0 };
+
+    let version = reader.u8()?;
+    if version != ENCODING_VERSION {
+        return Err(format!(
+            "Unsupported game state encoding version {}, expected {}.",
+            version, ENCODING_VERSION
+        ));
+    }
+
+    let turn = reader.u32()?;
+    let max_turns = reader.u32()?;
+    let total_food = reader.u32()?;
+    let finished = reader.u8()? != 0;
+    let finished_reason = decode_code(&FINISHED_REASONS, reader.u8()?)?;
+    let winner = Some(reader.u32()?).filter(|winner| *winner != u32::MAX as usize);
+
+    let players = reader.u16()?;
+    let mut scores = Vec::with_capacity(players);
+    let mut hive = Vec::with_capacity(players);
+    let mut turn_stats = Vec::with_capacity(players);
+    for _ in 0..players {
+        scores.push(reader.i64()? as isize);
+        hive.push(reader.u32()?);
+        turn_stats.push(TurnStats {
+            turn: reader.u32()?,
+            food_harvested: reader.u32()?,
+            ants_spawned: reader.u32()?,
+            ants_killed: reader.u32()?,
+            hills_razed: reader.u32()?,
+            ants_lost: reader.u32()?,
+            hills_lost: reader.u32()?,
+        });
+    }
+
+    let mut ants = Vec::with_capacity(players);
+    for player in 0..players {
+        let player_ants = (0..reader.u32()?)
+            .map(|_| decode_ant(&mut reader, player, scores[player], hive[player]))
+            .collect::<Result<Vec<_>, _>>()?;
+        ants.push(player_ants);
+    }
+
+    let rows = reader.u16()?;
+    let cols = reader.u16()?;
+    let mut explored = Vec::new();
+    if rows * cols > 0 {
+        for _ in 0..players {
+            let packed = reader.take((rows * cols).div_ceil(8))?;
+            explored.push(
+                (0..rows)
+                    .map(|row| {
+                        (0..cols)
+                            .map(|col| {
+                                let bit = row * cols + col;
+                                packed[bit / 8] & (1 << (bit % 8)) != 0
+                            })
+                            .collect()
+                    })
+                    .collect(),
+            );
+        }
+        // The explored cells are the only part of the state that knows the size of the map
+        check_within_map(&ants, rows, cols)?;
+    }
+
+    let board_hash = match reader.u8()? {
+        0 => None,
+        _ => Some(reader.u64()?),
+    };
+
+    if reader.position != bytes.len() {
+        return Err(
+            "Invalid game state bytes, there are bytes left after the end of the state."
+                .to_string(),
+        );
+    }
+
+    Ok(GameState {
+        turn,
+        max_turns,
+        scores,
+        ants,
+        hive,
+        total_food,
+        turn_stats,
+        finished,
+        finished_reason,
+        winner,
+        explored,
+        rewards: None,
+        board_hash,
+        board: None,
+    })
+}
+
+/// Checks that every ant, and everything they see, is within a map of the given size.
+fn check_within_map(ants: &[Vec<PlayerAnt>], rows: usize, cols: usize) -> Result<(), String> {
+    for ant in ants.iter().flatten() {
+        let cells = std::iter::once((ant.row, ant.col))
+            .chain(
+                ant.field_of_vision
+                    .iter()
+                    .map(|entity| (entity.row, entity.col)),
+            )
+            .chain(ant.visible_cells.iter().copied());
+        for (row, col) in cells {
+            if row >= rows || col >= cols {
+                return Err(format!(
+                    "Invalid game state bytes, ({}, {}) is outside the {}x{} map.",
+                    row, col, rows, cols
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn encode_ant(bytes: &mut Vec<u8>, ant: &PlayerAnt) {
+    bytes.push(ant.id.len() as u8);
+    bytes.extend(ant.id.as_bytes());
+    put_u16(bytes, ant.row);
+    put_u16(bytes, ant.col);
+    bytes.push(ant.alive as u8);
+    bytes.push(code(&DIRECTIONS, ant.last_direction.as_ref()));
+    bytes.push(ant.last_move_succeeded as u8);
+    bytes.push(ant.carrying as u8);
+    put_u16(bytes, ant.idle_turns);
+
+    put_u16(bytes, ant.field_of_vision.len());
+    for entity in &ant.field_of_vision {
+        let name = ENTITY_NAMES
+            .iter()
+            .position(|name| *name == entity.name)
+            .unwrap_or_else(|| panic!("Entity {} can't be encoded.", entity.name));
+        bytes.push(name as u8);
+        // Entities are within the field of vision, so they are close enough to the ant for a small offset
+        bytes.extend((entity.row as i16 - ant.row as i16).to_le_bytes());
+        bytes.extend((entity.col as i16 - ant.col as i16).to_le_bytes());
+        put_u16(bytes, entity.player.unwrap_or(u16::MAX as usize));
+        bytes.push(entity.alive.map_or(2, |alive| alive as u8));
+    }
+
+    put_u16(bytes, ant.visible_cells.len());
+    for (cell_row, cell_col) in &ant.visible_cells {
+        bytes.extend((*cell_row as i16 - ant.row as i16).to_le_bytes());
+        bytes.extend((*cell_col as i16 - ant.col as i16).to_le_bytes());
+    }
+}
+
+/// Decodes an ant, whose owner's score and hive are not encoded again since they are the same for all their ants.
+fn decode_ant(
+    reader: &mut Reader,
+    player: usize,
+    owner_score: isize,
+    owner_hive: usize,
+) -> Result<PlayerAnt, String> {
+    let id_length = reader.u8()? as usize;
+    let id = String::from_utf8(reader.take(id_length)?.to_vec())
+        .map_err(|_| "Invalid game state bytes, an ant id is not valid UTF-8.".to_string())?;
+    let row = reader.u16()?;
+    let col = reader.u16()?;
+    let alive = reader.u8()? != 0;
+    let last_direction = decode_code(&DIRECTIONS, reader.u8()?)?;
+    let last_move_succeeded = reader.u8()? != 0;
+    let carrying = reader.u8()? != 0;
+    let idle_turns = reader.u16()?;
+
+    let field_of_vision = (0..reader.u16()?)
+        .map(|_| {
+            let name = reader.u8()? as usize;
+            let name = ENTITY_NAMES
+                .get(name)
+                .ok_or_else(|| format!("Invalid game state bytes, unknown entity {}.", name))?;
+            let row = reader.offset(row)?;
+            let col = reader.offset(col)?;
+            let player = Some(reader.u16()?).filter(|player| *player != u16::MAX as usize);
+            let alive = match reader.u8()? {
+                2 => None,
+                alive => Some(alive != 0),
+            };
+            // Whether a hill was razed is not encoded again, it's the opposite of whether it's alive
+            let razed = match *name {
+                "Hill" => alive.map(|alive| !alive),
+                _ => None,
+            };
+            Ok(StateEntity {
+                name: name.to_string(),
+                row,
+                col,
+                player,
+                alive,
+                razed,
+            })
+        })
+        .collect::<Result<_, String>>()?;
+
+    let visible_cells = (0..reader.u16()?)
+        .map(|_| Ok((reader.offset(row)?, reader.offset(col)?)))
+        .collect::<Result<_, String>>()?;
+
+    Ok(PlayerAnt {
+        id,
+        row,
+        col,
+        player,
+        alive,
+        field_of_vision,
+        visible_cells,
+        last_direction,
+        last_move_succeeded,
+        owner_score,
+        owner_hive,
+        carrying,
+        idle_turns,
+    })
+}
+
+/// Returns the code of an optional value, `0` for `None` and the position of the value plus 1 otherwise.
+fn code<T: PartialEq>(values: &[T], value: Option<&T>) -> u8 {
+    value.map_or(0, |value| {
+        values.iter().position(|other| other == value).unwrap() as u8 + 1
+    })
+}
+
+fn decode_code<T: Clone>(values: &[T], code: u8) -> Result<Option<T>, String> {
+    match code {
+        0 => Ok(None),
+        code => values
+            .get(code as usize - 1)
+            .map(|value| Some(value.clone()))
+            .ok_or_else(|| format!("Invalid game state bytes, unknown code {}.", code)),
+    }
+}
+
+fn put_u16(bytes: &mut Vec<u8>, value: usize) {
+    bytes.extend((value as u16).to_le_bytes());
+}
+
+fn put_u32(bytes: &mut Vec<u8>, value: usize) {
+    bytes.extend((value as u32).to_le_bytes());
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, length: usize) -> Result<&[u8], String> {
+        let end = self.position + length;
+        if end > self.bytes.len() {
+            return Err(
+                "Invalid game state bytes, they ended before the end of the state.".to_string(),
+            );
+        }
+
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<usize, String> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()) as usize)
+    }
+
+    fn i16(&mut self) -> Result<i16, String> {
+        Ok(i16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<usize, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()) as usize)
+    }
+
+    fn i64(&mut self) -> Result<i64, String> {
+        Ok(i64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    /// Reads a coordinate relative to `origin`, which must land on a coordinate that can be encoded as a `u16`.
+    fn offset(&mut self, origin: usize) -> Result<usize, String> {
+        let offset = self.i16()?;
+        let coordinate = origin as isize + offset as isize;
+        if coordinate < 0 || coordinate > u16::MAX as isize {
+            return Err(format!(
+                "Invalid game state bytes, the offset {} from {} is outside the map.",
+                offset, origin
+            ));
+        }
+
+        Ok(coordinate as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Action, Game};
+
+    fn played_state() -> GameState {
+        let map = "\
+            rows 4
+            cols 5
+            players 2
+            m 0a...
+            m ..%..
+            m .*...
+            m ...b1";
+        let mut game = Game::new(map, 9, 1, 1, 2, 1500, 500, 0, None);
+        game.set_track_explored(true);
+        game.set_track_board_hash(true);
+        game.start();
+        game.update(vec![Action::new(0, 1, Direction::South)])
+    }
+
+    #[test]
+    fn when_encoding_a_state_and_decoding_it_the_same_state_is_returned() {
+        let state = played_state();
+
+        let decoded = decode(&encode(&state)).unwrap();
+
+        assert_eq!(decoded, state);
+    }
+
+    #[test]
+    fn when_encoding_a_state_the_first_byte_is_the_encoding_version() {
+        let bytes = encode(&played_state());
+
+        assert_eq!(bytes[0], ENCODING_VERSION);
+    }
+
+    #[test]
+    fn when_decoding_bytes_with_another_version_an_error_is_returned() {
+        let mut bytes = encode(&played_state());
+        bytes[0] = 0;
+
+        assert_eq!(
+            decode(&bytes),
+            Err("Unsupported game state encoding version 0, expected 5.".to_string())
+        );
+    }
+
+    #[test]
+    fn when_decoding_truncated_bytes_an_error_is_returned() {
+        let bytes = encode(&played_state());
+
+        assert_eq!(
+            decode(&bytes[..bytes.len() - 1]),
+            Err("Invalid game state bytes, they ended before the end of the state.".to_string())
+        );
+    }
+
+    #[test]
+    fn when_decoding_an_entity_before_the_first_row_an_error_is_returned() {
+        let mut state = played_state();
+        let ant = &mut state.ants[0][0];
+        // The relative offset is truncated to an `i16`, so this row ends up one row before the first one
+        ant.field_of_vision[0].row = u16::MAX as usize;
+        let row = ant.row;
+
+        assert_eq!(
+            decode(&encode(&state)),
+            Err(format!(
+                "Invalid game state bytes, the offset -{} from {} is outside the map.",
+                row + 1,
+                row
+            ))
+        );
+    }
+
+    #[test]
+    fn when_decoding_an_ant_outside_the_explored_map_an_error_is_returned() {
+        let mut state = played_state();
+        let ant = &mut state.ants[0][0];
+        ant.row = 4;
+        ant.field_of_vision.clear();
+        ant.visible_cells.clear();
+        let col = ant.col;
+
+        assert_eq!(
+            decode(&encode(&state)),
+            Err(format!(
+                "Invalid game state bytes, (4, {}) is outside the 4x5 map.",
+                col
+            ))
+        );
+    }
+}
@@ -1,4 +1,3 @@
-use crossterm::style::Color;
 use std::any::type_name;
 use uuid::Uuid;
 
@@ -22,20 +21,19 @@ pub trait Entity: Send + Sync {
     #[allow(unused_variables)]
     fn set_alive(&mut self, value: bool) {}
 
-    fn on_ant_hill(&self) -> Option<&Box<dyn Entity>> {
+    fn on_ant_hill(&self) -> Option<&dyn Entity> {
         None
     }
 
     #[allow(unused_variables)]
     fn set_on_ant_hill(&mut self, value: Box<dyn Entity>) {}
 
-    fn char(&self) -> char {
-        '!'
+    fn carrying(&self) -> Option<bool> {
+        None
     }
 
-    fn color(&self) -> Color {
-        Color::White
-    }
+    #[allow(unused_variables)]
+    fn set_carrying(&mut self, value: bool) {}
 }
 
 pub struct Ant {
@@ -43,6 +41,8 @@ pub struct Ant {
     player: usize,
     alive: bool,
     on_ant_hill: Option<Box<dyn Entity>>,
+    /// Whether the ant is carrying food back to a hill, only used when ants carry food.
+    carrying: bool,
 }
 
 impl Ant {
@@ -57,15 +57,7 @@ impl Ant {
             player,
             alive,
             on_ant_hill,
-        }
-    }
-
-    pub fn from_ant_hill(player: usize, ant_hill: Box<dyn Entity>) -> Ant {
-        Ant {
-            id: Uuid::new_v4().to_string(),
-            player,
-            alive: true,
-            on_ant_hill: Some(ant_hill),
+            carrying: false,
         }
     }
 }
@@ -87,44 +79,45 @@ impl Entity for Ant {
         self.alive = value;
     }
 
-    fn on_ant_hill(&self) -> Option<&Box<dyn Entity>> {
-        self.on_ant_hill.as_ref()
+    fn on_ant_hill(&self) -> Option<&dyn Entity> {
+        self.on_ant_hill.as_deref()
     }
 
     fn set_on_ant_hill(&mut self, value: Box<dyn Entity>) {
         self.on_ant_hill = Some(value);
     }
 
-    fn char(&self) -> char {
-        match self.alive {
-            true => match self.on_ant_hill {
-                Some(_) => (self.player + 'A' as usize) as u8 as char,
-                None => (self.player + 'a' as usize) as u8 as char,
-            },
-            false => '.', // Dead ants are removed from the map
-        }
+    fn carrying(&self) -> Option<bool> {
+        Some(self.carrying)
     }
 
-    fn color(&self) -> Color {
-        match self.alive {
-            true => player_to_color(self.player),
-            false => Color::White, // Dead ants are removed from the map
-        }
+    fn set_carrying(&mut self, value: bool) {
+        self.carrying = value;
     }
 }
 
-pub struct Food;
+/// The remains of a dead ant, left on the board for a few turns so that it can still be seen.
+/// Unlike a dead ant, a corpse doesn't block movement, it's replaced by the ant that walks over it.
+pub struct Corpse {
+    player: usize,
+}
 
-impl Entity for Food {
-    fn char(&self) -> char {
-        '*'
+impl Corpse {
+    pub fn new(player: usize) -> Corpse {
+        Corpse { player }
     }
+}
 
-    fn color(&self) -> Color {
-        Color::Grey
+impl Entity for Corpse {
+    fn player(&self) -> Option<usize> {
+        Some(self.player)
     }
 }
 
+pub struct Food;
+
+impl Entity for Food {}
+
 pub struct Hill {
     player: usize,
     // For a hill, `alive` means it hasn't been razed by an enemy ant
@@ -149,33 +142,15 @@ impl Entity for Hill {
     fn set_alive(&mut self, value: bool) {
         self.alive = value;
     }
-
-    fn char(&self) -> char {
-        match self.alive {
-            true => (self.player + '0' as usize) as u8 as char,
-            false => 'X',
-        }
-    }
-
-    fn color(&self) -> Color {
-        player_to_color(self.player)
-    }
 }
 
 pub struct Water;
 
-impl Entity for Water {
-    fn char(&self) -> char {
-        '%'
-    }
-
-    fn color(&self) -> Color {
-        Color::DarkBlue
-    }
-}
+impl Entity for Water {}
 
-pub fn from_char(value: char) -> Option<Box<dyn Entity>> {
-    match value {
+/// Returns the entity the character of a map stands for, `None` for land, or an error if it stands for nothing.
+pub fn from_char(value: char) -> Result<Option<Box<dyn Entity>>, String> {
+    Ok(match value {
         // Ignore land entities to reduce memory usage
         '.' => None,
         // Max 10 players
@@ -186,6 +161,7 @@ pub fn from_char(value: char) -> Option<Box<dyn Entity>> {
             player: value as usize - 'a' as usize,
             alive: true,
             on_ant_hill: None,
+            carrying: false,
         })),
         // Max 10 players
         'A'..='J' => Some(Box::new(Ant {
@@ -198,6 +174,7 @@ pub fn from_char(value: char) -> Option<Box<dyn Entity>> {
                 player: value as usize - 'A' as usize,
                 alive: true,
             })),
+            carrying: false,
         })),
         '*' => Some(Box::new(Food)),
         // Max 10 players
@@ -206,22 +183,6 @@ pub fn from_char(value: char) -> Option<Box<dyn Entity>> {
             alive: true,
         })),
         '%' => Some(Box::new(Water)),
-        _ => panic!("Invalid character value: {}", value),
-    }
-}
-
-pub fn player_to_color(player: usize) -> Color {
-    match player {
-        0 => Color::Red,
-        1 => Color::Green,
-        2 => Color::Blue,
-        3 => Color::Yellow,
-        4 => Color::Magenta,
-        5 => Color::Cyan,
-        6 => Color::DarkRed,
-        7 => Color::DarkGreen,
-        8 => Color::DarkMagenta,
-        9 => Color::DarkYellow,
-        _ => panic!("Invalid player number"),
-    }
+        _ => return Err(format!("Invalid character value: {}", value)),
+    })
 }
@@ -1,14 +1,24 @@
-use crate::entities::{Ant, Entity, Food, Hill};
+use crate::encoding;
+use crate::entities::{Ant, Corpse, Entity, Food, Hill};
 use crate::map::Map;
-use crate::replay::{create_replay_logger, ReplayLogger};
+use crate::render::{default_player_name, RenderTheme};
+use crate::replay::{create_replay_logger, observe_events, Event, GameResult, ReplayLogger};
+use crossterm::style::Color;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
-use rand::distributions::{Distribution, Standard};
 use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 use rand::Rng;
 use rand::SeedableRng;
-use std::collections::{HashMap, HashSet};
+use rayon::prelude::*;
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
 use std::vec;
+use uuid::Builder;
+
+pub use crate::map::{Direction, DistanceMetric};
 
 /// The Ants game.
 /// Main entry point for running the game.
@@ -16,67 +26,210 @@ use std::vec;
 pub struct Game {
     map: Map,
     map_contents: String,
-    fov_radius2: usize,
+    fov_radius2: Vec<usize>,
+    default_fov_radius2: usize,
     attack_radius2: usize,
     food_radius2: usize,
     turn: usize,
-    scores: Vec<usize>,
+    scores: Vec<isize>,
     hive: Vec<usize>,
+    per_hill_hive: bool,
+    /// The food banked at each hill when the hive is split per hill, by the hill's player, row and column.
+    /// The hive of each player is always the sum of the food banked at their hills.
+    hill_hives: BTreeMap<(usize, usize, usize), usize>,
     turn_stats: Vec<TurnStats>,
+    statistics: Vec<PlayerStats>,
     food_per_turn: usize,
+    food_spawn_strategy: FoodSpawnStrategy,
+    min_food_ant_distance: usize,
+    hill_raze_mode: HillRazeMode,
+    starting_score: StartingScore,
+    contested_food: ContestedFood,
+    corpse_lifetime: usize,
+    dig_cost: Option<usize>,
+    /// The direction each ant was ordered to move in on the current turn and whether it moved, by ant id.
+    last_moves: BTreeMap<String, (Direction, bool)>,
+    idle_patience: usize,
+    idle_turns: BTreeMap<String, usize>,
+    active_ants: BTreeSet<String>,
+    /// The turn each corpse on the map was left on, by location.
+    corpses: BTreeMap<(usize, usize), usize>,
+    step_to_harvest: bool,
+    carry_food: bool,
+    signed_scores: bool,
+    skip_penalty: usize,
+    hill_defense: bool,
+    reveal_hill_owner: bool,
+    log_blocked_moves: bool,
+    track_explored: bool,
+    track_board_hash: bool,
+    explored: Vec<Vec<Vec<bool>>>,
+    skipped_players: BTreeSet<usize>,
+    eliminated_players: BTreeSet<usize>,
+    scored_hills: BTreeSet<(usize, usize)>,
+    placements: Vec<(usize, usize, Placement)>,
+    distance_metric: DistanceMetric,
+    phase_order: Vec<TurnPhase>,
+    line_of_sight: bool,
     started: bool,
     finished: bool,
     finished_reason: Option<FinishedReason>,
     winner: Option<usize>,
     cutoff_threshold: usize,
     turns_with_too_much_food: usize,
+    reachable_food_only: bool,
+    stalemate_patience: Option<usize>,
+    turns_in_stalemate: usize,
     points_for_razing_hill: usize,
     points_for_losing_hill: usize,
     max_turns: usize,
     max_colony_size: usize,
+    max_hive: Option<usize>,
+    max_food_per_ant_per_turn: usize,
+    starting_ants_per_hill: usize,
+    starting_food_per_hill: usize,
+    spawn_burst: usize,
+    render_theme: RenderTheme,
+    player_names: Vec<String>,
+    player_colors: Option<Vec<(u8, u8, u8)>>,
     replay_logger: Box<dyn ReplayLogger>,
+    /// The stream for map-wide random decisions: where food spawns, including the starting food around the hills.
     rng: StdRng,
+    /// The stream for each player's random decisions, where the index is the player number:
+    /// which hills spawn ants from the hive and where the extra starting and burst ants go around the hills.
+    /// Each stream is seeded from the game seed and the player number so that one player's draws never shift another's.
+    player_rngs: Vec<StdRng>,
+    /// The stream for the ids of the ants, seeded with `id_seed` so that the ids don't correlate with where food spawns.
+    id_rng: StdRng,
+    reward_hook: Option<RewardHook>,
+    /// The state the reward hook computes the next rewards from, only kept while there is a hook.
+    last_state: Option<GameState>,
+    last_rewards: Option<Vec<f64>>,
+    /// The events of the current turn, only collected while there is a reward hook.
+    turn_events: Option<Arc<Mutex<Vec<Event>>>>,
+    /// The first exception raised by a Python event observer or reward hook, raised by the Python call that triggered it.
+    callback_error: Arc<Mutex<Option<PyErr>>>,
 }
 
+/// A function that computes the reward of each player for a turn, from the state before the turn, the actions,
+/// the state after the turn and the events of the turn.
+pub type RewardHook =
+    Box<dyn Fn(&GameState, &[Action], &GameState, &[Event]) -> Vec<f64> + Send + Sync>;
+
 /// Represents the state of the game.
+#[derive(Clone, Debug, PartialEq)]
 #[pyclass(module = "ants_engine", get_all)]
 pub struct GameState {
     /// The current turn.
     pub turn: usize,
-    /// The scores for each player where the index is the player number.
-    pub scores: Vec<usize>,
+    /// The maximum number of turns before the game ends.
+    pub max_turns: usize,
+    /// The scores for each player where the index is the player number. Scores never go below 0 unless signed scores are enabled.
+    pub scores: Vec<isize>,
     /// The ants for each player where the index is the player number.
     pub ants: Vec<Vec<PlayerAnt>>,
-    /// The number of ants in the hive for each player where the index is the player number.
+    /// The amount of food stored in the hive for each player where the index is the player number.
+    /// Each unit of food is spent to spawn one ant on one of the player's hills.
+    /// This is full information, i.e. it includes the hive of every player.
     pub hive: Vec<usize>,
+    /// The total amount of food on the map.
+    pub total_food: usize,
     /// The turn stats for each player where the index is the player number.
     pub turn_stats: Vec<TurnStats>,
     /// Whether the game has finished.
     pub finished: bool,
     /// The reason the game finished. `None` if the game has not finished.
     pub finished_reason: Option<FinishedReason>,
-    /// The player that won the game. `None` if the game has not finished or if the game finished without a winner.
+    /// The player that won the game, the first in the ranking when it finished.
+    /// `None` if the game has not finished or if the first two players were tied, see `Game::winner`.
     pub winner: Option<usize>,
+    /// The cells that each player has ever seen, indexed by player, row and column.
+    /// Only tracked when `track_explored` is enabled, otherwise it's empty.
+    pub explored: Vec<Vec<Vec<bool>>>,
+    /// The reward of each player for the last turn, as computed by the reward hook. `None` if there is no reward hook.
+    pub rewards: Option<Vec<f64>>,
+    /// The hash of the board at the end of the turn, see `Game::board_hash`.
+    /// Only computed when `track_board_hash` is enabled, otherwise it's `None`.
+    pub board_hash: Option<u64>,
+    /// Every entity on the map regardless of the field of vision of any ant, see `Game::entities`.
+    /// Only populated in the state returned by `Game::debug_state`, `None` in the states given to agents.
+    pub board: Option<Vec<StateEntity>>,
 }
 
-/// Represents the direction an ant can move.
-#[derive(Clone, PartialEq)]
-#[pyclass(module = "ants_engine", eq, eq_int)]
-pub enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
+#[pymethods]
+impl GameState {
+    /// Returns the number of turns left before the turn limit is reached.
+    #[getter]
+    pub fn turns_remaining(&self) -> usize {
+        self.max_turns.saturating_sub(self.turn)
+    }
+
+    /// Returns the fraction of the maximum number of turns that has been played, from 0 to 1.
+    #[getter]
+    pub fn progress(&self) -> f64 {
+        if self.max_turns == 0 {
+            return 1.0;
+        }
+
+        (self.turn as f64 / self.max_turns as f64).min(1.0)
+    }
+
+    /// Returns the state in a compact binary encoding, e.g. to send it to remote agents over a socket.
+    /// The layout is documented in the `encoding` module and can be decoded with `from_bytes`.
+    pub fn to_bytes(&self) -> Cow<'static, [u8]> {
+        Cow::Owned(encoding::encode(self))
+    }
+
+    /// Decodes a state encoded with `to_bytes`, see `GameState::from_bytes`.
+    /// Raises a `ValueError` if the bytes are not a valid encoded state.
+    ///
+    /// # Arguments
+    /// * `bytes` - The encoded state.
+    #[staticmethod]
+    #[pyo3(name = "from_bytes")]
+    pub fn from_bytes_py(bytes: &[u8]) -> PyResult<GameState> {
+        GameState::from_bytes(bytes).map_err(PyValueError::new_err)
+    }
 
-impl Distribution<Direction> for Standard {
-    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Direction {
-        match rng.gen_range(0..4) {
-            0 => Direction::North,
-            1 => Direction::East,
-            2 => Direction::South,
-            _ => Direction::West,
+    /// Returns the ants of all players, and their fields of vision, as flat parallel lists.
+    pub fn as_arrays(&self) -> StateArrays {
+        let mut arrays = StateArrays {
+            fov_offsets: vec![0],
+            ..StateArrays::default()
+        };
+
+        for ant in self.ants.iter().flatten() {
+            arrays.ids.push(ant.id.clone());
+            arrays.rows.push(ant.row);
+            arrays.cols.push(ant.col);
+            arrays.players.push(ant.player);
+            arrays.alive.push(ant.alive);
+
+            for entity in &ant.field_of_vision {
+                arrays.fov_names.push(entity.name.clone());
+                arrays.fov_rows.push(entity.row);
+                arrays.fov_cols.push(entity.col);
+                arrays
+                    .fov_players
+                    .push(entity.player.map_or(-1, |player| player as isize));
+                arrays.fov_alive.push(entity.alive.unwrap_or(false));
+            }
+            arrays.fov_offsets.push(arrays.fov_names.len());
         }
+
+        arrays
+    }
+}
+
+impl GameState {
+    /// Decodes a state encoded with `to_bytes`.
+    /// Returns an error if the bytes are not a valid encoded state, e.g. if they were encoded with another version
+    /// or place something outside the map.
+    ///
+    /// # Arguments
+    /// * `bytes` - The encoded state.
+    pub fn from_bytes(bytes: &[u8]) -> Result<GameState, String> {
+        encoding::decode(bytes)
     }
 }
 
@@ -92,6 +245,155 @@ pub enum FinishedReason {
     TooMuchFood,
     /// The game ended because the maximum number of turns was reached.
     TurnLimitReached,
+    /// The game ended because, for too many turns, no ant could reach food nor an enemy ant or hill.
+    Stalemate,
+}
+
+/// Represents why the game state can't be updated, or why the game is not configured consistently.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GameError {
+    /// The game was updated before calling `start`.
+    NotStarted,
+    /// The game was updated after it finished, without calling `start` again.
+    AlreadyFinished,
+    /// The attack radius is larger than the field of vision of a player, so their ants can be attacked by enemies
+    /// they can't see. Only reported by `Game::validate`, since some rulesets allow it on purpose.
+    AttackBeyondVision {
+        player: usize,
+        attack_radius2: usize,
+        fov_radius2: usize,
+    },
+}
+
+impl std::fmt::Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameError::NotStarted => {
+                write!(f, "Game has not started! Call `start` to start the game.")
+            }
+            GameError::AlreadyFinished => {
+                write!(f, "Game is finished! Call `start` to start a new game.")
+            }
+            GameError::AttackBeyondVision {
+                player,
+                attack_radius2,
+                fov_radius2,
+            } => write!(
+                f,
+                "The attack radius squared {} is larger than the field of vision radius squared {} of player {}, so their ants can be attacked by enemies they can't see.",
+                attack_radius2, fov_radius2, player
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+// Python gets a regular exception instead of a panic that would kill the process
+impl From<GameError> for PyErr {
+    fn from(error: GameError) -> PyErr {
+        PyRuntimeError::new_err(error.to_string())
+    }
+}
+
+/// Represents what happens to food surrounded by ants of more than one player.
+#[derive(Clone, Debug, PartialEq)]
+#[pyclass(module = "ants_engine", eq, eq_int)]
+pub enum ContestedFood {
+    /// The food is destroyed without being harvested by anyone. This is the default.
+    Destroy,
+    /// The player with strictly more ants around the food harvests it, and only ties destroy it.
+    Majority,
+}
+
+/// Represents what happens to a hill after an enemy ant razes it.
+#[derive(Clone, Debug, PartialEq)]
+#[pyclass(module = "ants_engine", eq, eq_int)]
+pub enum HillRazeMode {
+    /// The hill stays on the map but it's no longer alive, so it stops spawning ants. This is the default.
+    Disable,
+    /// The hill is removed from the map, so the cell becomes land once the ant leaves it or dies.
+    Remove,
+    /// The hill keeps spawning ants for its owner and only the scores change. Each hill can only be razed once.
+    ScoreOnly,
+}
+
+/// Represents the strategy used to choose where food spawns.
+#[derive(Clone, Debug, PartialEq)]
+#[pyclass(module = "ants_engine", eq)]
+pub enum FoodSpawnStrategy {
+    /// Food spawns on random land cells. This is the default.
+    Random(),
+    /// Food spawns on random land cells and on all their images under the map's symmetries (rotational or mirror),
+    /// so that every player sees equivalent food. If the map is not symmetric, this behaves like `Random`.
+    SymmetricMirror(),
+    /// Food spawns on the given locations, in order, whenever they are empty land. Repeated locations are ignored.
+    Fixed(Vec<(usize, usize)>),
+    /// Food spawns on random land cells, preferring the ones that are farther away from every live hill by the number of moves,
+    /// to encourage exploring the map. The starting food around the hills is still random.
+    FarFromHills(),
+    /// Food spawns on random land cells spread evenly across the map, to avoid clustering it by chance.
+    /// The map is partitioned into a grid of square buckets, about one per food spawned on a turn, and each food goes to
+    /// a random cell of a different bucket, starting with the buckets that have the least food. The starting food
+    /// around the hills is still random.
+    Spread(),
+}
+
+/// Represents the score each player starts the game with.
+/// Maps can give some players a head start on top of it, with optional `bonus <player> <points>` lines.
+#[derive(Clone, Debug, PartialEq)]
+#[pyclass(module = "ants_engine", eq)]
+pub enum StartingScore {
+    /// Each player starts with 1 point per hill. This is the default.
+    HillCount(),
+    /// Every player starts with 0 points.
+    Zero(),
+    /// Every player starts with the given number of points.
+    Fixed(usize),
+}
+
+/// Represents a phase of a turn. Every turn runs all the phases, in the order set by `Game::phase_order`.
+#[derive(Clone, Debug, PartialEq)]
+#[pyclass(module = "ants_engine", eq, eq_int)]
+pub enum TurnPhase {
+    /// The ants move according to the actions.
+    Move,
+    /// The ants fight the enemies within their attack range.
+    Attack,
+    /// The enemy hills with a live ant on them are razed.
+    Raze,
+    /// The hives spawn new ants on their hills.
+    SpawnAnts,
+    /// The ants harvest the food around them.
+    Harvest,
+    /// New food spawns on the map.
+    SpawnFood,
+}
+
+#[pymethods]
+impl TurnPhase {
+    /// Returns the order used by the original game, which is the default.
+    #[staticmethod]
+    pub fn default_order() -> Vec<TurnPhase> {
+        vec![
+            TurnPhase::Move,
+            TurnPhase::Attack,
+            TurnPhase::Raze,
+            TurnPhase::SpawnAnts,
+            TurnPhase::Harvest,
+            TurnPhase::SpawnFood,
+        ]
+    }
+}
+
+/// Represents an entity placed on the map to set up a scenario, see `Game::place_ant`.
+#[derive(Clone)]
+enum Placement {
+    /// An ant of the given player.
+    Ant(usize),
+    Food,
+    /// A hill of the given player and whether it's alive.
+    Hill(usize, bool),
 }
 
 /// Represents an action an ant can take.
@@ -99,12 +401,19 @@ pub enum FinishedReason {
 /// The action is a tuple of the ant's row, column, and direction.
 /// If the direction is not a valid move, the ant will stay in place.
 /// Or if the provided location is not a valid ant, the action will be ignored.
+///
+/// Maps don't wrap around unless their header has a `wrap 1` line. On a bounded map, moving off any of the four edges
+/// is never a valid move: the ant stays in place, no `Move` event is logged and a move of several steps stops at the
+/// edge. When blocked moves are logged, the move is logged as a `BlockedMove` with the `OutOfBounds` reason.
+/// On a wrapping map, moving off an edge comes back on the opposite edge.
 #[derive(Clone)]
 #[pyclass(module = "ants_engine")]
 pub struct Action {
     row: usize,
     col: usize,
     direction: Direction,
+    steps: usize,
+    dig: bool,
 }
 
 #[pymethods]
@@ -117,16 +426,58 @@ impl Action {
     /// * `direction` - The direction the ant should move.
     #[new]
     pub fn new(row: usize, col: usize, direction: Direction) -> Action {
+        Action::new_with_steps(row, col, direction, 1)
+    }
+
+    /// Creates a new action that moves the ant up to `steps` cells in the same direction.
+    /// The ant stops at the first cell it can't move into, e.g. water, food or the edge of the map,
+    /// and collides with the first ant it runs into.
+    ///
+    /// # Arguments
+    /// * `row` - The row of the ant to move.
+    /// * `col` - The column of the ant to move.
+    /// * `direction` - The direction the ant should move.
+    /// * `steps` - The maximum number of cells to move.
+    #[staticmethod]
+    pub fn new_with_steps(row: usize, col: usize, direction: Direction, steps: usize) -> Action {
+        Action {
+            row,
+            col,
+            direction,
+            steps,
+            dig: false,
+        }
+    }
+
+    /// Creates a new action that digs the water cell next to the ant, in the given direction, turning it into land.
+    /// The ant stays in place. Digging must be enabled with `Game.dig_cost` and it's paid with food from the player's hive.
+    /// If the cell is not water or there isn't enough food in the hive, the action is ignored.
+    ///
+    /// # Arguments
+    /// * `row` - The row of the ant that digs.
+    /// * `col` - The column of the ant that digs.
+    /// * `direction` - The direction of the water cell to dig.
+    #[staticmethod]
+    pub fn dig(row: usize, col: usize, direction: Direction) -> Action {
         Action {
             row,
             col,
             direction,
+            steps: 0,
+            dig: true,
         }
     }
 }
 
+impl Action {
+    /// Returns the location of the ant the action is for.
+    pub(crate) fn location(&self) -> (usize, usize) {
+        (self.row, self.col)
+    }
+}
+
 /// Represents an entity in the game state.
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 #[pyclass(name = "Entity", module = "ants_engine", get_all)]
 pub struct StateEntity {
     /// The name of the entity.
@@ -138,11 +489,14 @@ pub struct StateEntity {
     /// The player who owns the entity, if applicable. For example, food does not belong to a player.
     pub player: Option<usize>,
     /// Whether the entity is alive, if applicable. For example, food does not have an alive state.
+    /// For a hill, `false` means it was razed, which is clearer to read from `razed`.
     pub alive: Option<bool>,
+    /// Whether the hill was razed by an enemy ant, only for hills. Unlike a dead ant, a razed hill can stay on the map.
+    pub razed: Option<bool>,
 }
 
 /// Represents an ant in the game state.
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 #[pyclass(name = "Ant", module = "ants_engine", get_all)]
 pub struct PlayerAnt {
     /// The unique identifier for the ant.
@@ -157,10 +511,56 @@ pub struct PlayerAnt {
     pub alive: bool,
     /// The field of vision for the ant as a list of entities the ant can see.
     pub field_of_vision: Vec<StateEntity>,
+    /// The coordinates of all the cells the ant can see, including empty ones and its own cell.
+    /// Unlike `field_of_vision`, this tells the cells seen as empty apart from the cells the ant has no information about.
+    pub visible_cells: Vec<(usize, usize)>,
+    /// The direction the ant was ordered to move in on the last turn, or `None` if it wasn't ordered to move, e.g. because it was just spawned.
+    pub last_direction: Option<Direction>,
+    /// Whether the ant actually moved on the last turn, i.e. at least one step of its move was not blocked.
+    pub last_move_succeeded: bool,
+    /// The current score of the player who owns the ant, the same as in `GameState::scores`.
+    pub owner_score: isize,
+    /// The amount of food in the hive of the player who owns the ant, the same as in `GameState::hive`.
+    pub owner_hive: usize,
+    /// Whether the ant is carrying food back to a hill, only when ants carry food.
+    pub carrying: bool,
+    /// The number of turns in a row the ant has been idle, only counted when ants have an idle patience.
+    pub idle_turns: usize,
+}
+
+/// Represents the ants of a game state as flat, parallel lists, where the index is the ant number.
+/// Ants are ordered player by player, as in `GameState::ants`.
+/// This allows building tensors with vectorized operations instead of reading each ant one attribute at a time.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[pyclass(name = "StateArrays", module = "ants_engine", get_all)]
+pub struct StateArrays {
+    /// The id of each ant.
+    pub ids: Vec<String>,
+    /// The row of each ant.
+    pub rows: Vec<usize>,
+    /// The column of each ant.
+    pub cols: Vec<usize>,
+    /// The player who owns each ant.
+    pub players: Vec<usize>,
+    /// Whether each ant is alive.
+    pub alive: Vec<bool>,
+    /// Where the field of vision of each ant starts and ends in the `fov_*` lists, with one more entry than there are ants.
+    /// The field of vision of ant `i` is from `fov_offsets[i]` up to, but not including, `fov_offsets[i + 1]`.
+    pub fov_offsets: Vec<usize>,
+    /// The name of each entity seen.
+    pub fov_names: Vec<String>,
+    /// The row of each entity seen.
+    pub fov_rows: Vec<usize>,
+    /// The column of each entity seen.
+    pub fov_cols: Vec<usize>,
+    /// The player who owns each entity seen, or `-1` if it doesn't belong to a player, e.g. food.
+    pub fov_players: Vec<isize>,
+    /// Whether each entity seen is alive, `false` if it doesn't have an alive state, e.g. food.
+    pub fov_alive: Vec<bool>,
 }
 
 /// Represents the statistics for a turn for a player.
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 #[pyclass(name = "TurnStats", module = "ants_engine", get_all)]
 pub struct TurnStats {
     /// The turn number.
@@ -179,6 +579,92 @@ pub struct TurnStats {
     pub hills_lost: usize,
 }
 
+/// Represents the cumulative statistics of a player over the whole game.
+#[derive(Clone, serde::Serialize)]
+#[pyclass(name = "PlayerStats", module = "ants_engine", get_all)]
+pub struct PlayerStats {
+    /// The number of food harvested.
+    pub food_harvested: usize,
+    /// The number of ants spawned, including the starting ants.
+    pub ants_spawned: usize,
+    /// The number of ants killed.
+    pub ants_killed: usize,
+    /// The number of hills razed.
+    pub hills_razed: usize,
+    /// The number of ants lost, either in battle or from collisions.
+    pub ants_lost: usize,
+    /// The number of hills lost.
+    pub hills_lost: usize,
+}
+
+impl PlayerStats {
+    /// Creates a new player stats object.
+    pub fn new() -> PlayerStats {
+        PlayerStats {
+            food_harvested: 0,
+            ants_spawned: 0,
+            ants_killed: 0,
+            hills_razed: 0,
+            ants_lost: 0,
+            hills_lost: 0,
+        }
+    }
+
+    /// Adds food harvested.
+    ///
+    /// # Arguments
+    /// * `food` - The amount of food harvested.
+    pub fn add_food_harvested(&mut self, food: usize) {
+        self.food_harvested += food;
+    }
+
+    /// Adds ants spawned.
+    ///
+    /// # Arguments
+    /// * `ants` - The amount of ants spawned.
+    pub fn add_ants_spawned(&mut self, ants: usize) {
+        self.ants_spawned += ants;
+    }
+
+    /// Adds ants killed.
+    ///
+    /// # Arguments
+    /// * `ants` - The amount of ants killed.
+    pub fn add_ants_killed(&mut self, ants: usize) {
+        self.ants_killed += ants;
+    }
+
+    /// Adds hills razed.
+    ///
+    /// # Arguments
+    /// * `hills` - The amount of hills razed.
+    pub fn add_hills_razed(&mut self, hills: usize) {
+        self.hills_razed += hills;
+    }
+
+    /// Adds ants lost.
+    ///
+    /// # Arguments
+    /// * `ants` - The amount of ants lost.
+    pub fn add_ants_lost(&mut self, ants: usize) {
+        self.ants_lost += ants;
+    }
+
+    /// Adds hills lost.
+    ///
+    /// # Arguments
+    /// * `hills` - The amount of hills lost.
+    pub fn add_hills_lost(&mut self, hills: usize) {
+        self.hills_lost += hills;
+    }
+}
+
+impl Default for PlayerStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TurnStats {
     /// Creates a new turn stats object.
     ///
@@ -247,21 +733,28 @@ impl TurnStats {
 
 #[pymethods]
 impl Game {
-    /// Creates a new game.
+    /// Creates a new game, optionally with the names and colors of the players. See `Game::new` for the other arguments.
     ///
     /// # Arguments
-    /// * `map_contents` - The map as a string.
-    /// * `fov_radius2` - The radius **squared** of the field of vision for each ant.
-    /// * `attack_radius2` - The radius **squared** of the attack range for each ant.
-    /// * `food_radius2` - The radius **squared** of the range around ants to harvest food.
-    /// * `food_rate` - The amount of food to spawn *per player* on each round.
-    /// * `max_turns` - The maximum number of turns before the game ends.
-    /// * `seed` - The seed for the random number generator.
-    /// * `max_colony_size` - The maximum number of live ants that a player can have at any time.
-    /// * `replay_filename` - The filename to save the replay of the game to. If `None`, no replay will be saved.
+    /// * `player_names` - The name of each player, used by `draw`, `render` and the replay. Defaults to `Player N`.
+    /// * `player_colors` - The RGB color of each player, used by `draw` and the replay. Defaults to the theme's palette.
+    /// * `strict` - Whether to raise an error if the game is not configured consistently, see `validate`.
+    ///   Defaults to `false`, since some rulesets are inconsistent on purpose.
+    /// * `idle_patience` - The number of turns in a row an ant can stay idle before it has to eat, see `set_idle_patience`.
+    ///   Defaults to `0`, i.e. ants can stay idle forever.
+    /// * `starting_ants_per_hill` - The number of ants each hill starts the game with, see `set_starting_ants_per_hill`.
+    ///   Defaults to `1`.
+    /// * `hill_defense` - Whether ants standing on their own live hill can't be killed in battle, see `set_hill_defense`.
+    ///   Defaults to `false`.
+    /// * `starting_food_per_hill` - The amount of food spawned around each hill at the start of the game, see `set_starting_food_per_hill`.
+    ///   Defaults to `3`.
+    ///
+    /// Raises a `ValueError` if the map can't be played, see `Game::try_new`,
+    /// or if there isn't exactly one name or color for each player.
     #[new]
-    #[pyo3(signature = (map_contents, fov_radius2, attack_radius2, food_radius2, food_rate, max_turns, max_colony_size, seed, replay_filename=None))]
-    pub fn new(
+    #[pyo3(signature = (map_contents, fov_radius2, attack_radius2, food_radius2, food_rate, max_turns, max_colony_size, seed, replay_filename=None, player_names=None, player_colors=None, strict=false, idle_patience=0, starting_ants_per_hill=1, hill_defense=false, starting_food_per_hill=3))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_py(
         map_contents: &str,
         fov_radius2: usize,
         attack_radius2: usize,
@@ -271,42 +764,56 @@ impl Game {
         max_colony_size: usize,
         seed: u64,
         replay_filename: Option<String>,
-    ) -> Game {
-        let map = Map::parse(map_contents);
-        let players = map.players();
-        let width = map.width();
-        let height = map.height();
-
-        Game {
-            map,
-            map_contents: map_contents.to_string(),
+        player_names: Option<Vec<String>>,
+        player_colors: Option<Vec<(u8, u8, u8)>>,
+        strict: bool,
+        idle_patience: usize,
+        starting_ants_per_hill: usize,
+        hill_defense: bool,
+        starting_food_per_hill: usize,
+    ) -> PyResult<Game> {
+        let mut game = Game::try_new(
+            map_contents,
             fov_radius2,
             attack_radius2,
             food_radius2,
-            turn: 0,
-            scores: vec![0; players],
-            hive: vec![0; players],
-            turn_stats: vec![TurnStats::new(0); players],
-            food_per_turn: food_rate * players,
-            started: false,
-            finished: false,
-            finished_reason: None,
-            winner: None,
-            cutoff_threshold: 150,
-            turns_with_too_much_food: 0,
-            points_for_razing_hill: 2,
-            points_for_losing_hill: 1,
+            food_rate,
             max_turns,
             max_colony_size,
-            replay_logger: create_replay_logger(
-                replay_filename,
-                players,
-                width,
-                height,
-                map_contents.to_string(),
-            ),
-            rng: StdRng::seed_from_u64(seed),
+            seed,
+            replay_filename,
+        )
+        .map_err(PyValueError::new_err)?;
+        game.try_set_players(player_names, player_colors)
+            .map_err(PyValueError::new_err)?;
+        game.set_idle_patience(idle_patience);
+        game.set_starting_ants_per_hill(starting_ants_per_hill);
+        game.set_hill_defense(hill_defense);
+        game.set_starting_food_per_hill(starting_food_per_hill);
+        if strict {
+            game.validate()?;
+        }
+
+        Ok(game)
+    }
+
+    /// Checks that the game is configured consistently, returning an error otherwise. In Python, the error is raised as a `RuntimeError`.
+    /// The game itself is permissive, so this check is opt-in, e.g. for tournament setups.
+    ///
+    /// Currently, the attack radius must not be larger than the field of vision of any player,
+    /// since their ants could otherwise be attacked by enemies they can't see.
+    pub fn validate(&self) -> Result<(), GameError> {
+        for (player, fov_radius2) in self.fov_radius2.iter().enumerate() {
+            if self.attack_radius2 > *fov_radius2 {
+                return Err(GameError::AttackBeyondVision {
+                    player,
+                    attack_radius2: self.attack_radius2,
+                    fov_radius2: *fov_radius2,
+                });
+            }
         }
+
+        Ok(())
     }
 
     /// Returns the width of the map.
@@ -324,1683 +831,8249 @@ impl Game {
         self.map.players()
     }
 
-    /// Starts the game.
-    ///
-    /// Must be called once before updating the game state.
-    pub fn start(&mut self) -> GameState {
-        self.turn = 0;
-        self.started = true;
-        self.finished = false;
-        self.finished_reason = None;
-        self.winner = None;
-        self.turns_with_too_much_food = 0;
-        self.hive = vec![0; self.map.players()];
-        self.map = Map::parse(&self.map_contents);
-        self.replay_logger.clear();
+    /// Returns the total amount of food on the map.
+    pub fn food_on_map(&self) -> usize {
+        self.map.food().len()
+    }
 
-        self.compute_initial_scores();
-        self.spawn_food_around_hills();
-        self.spawn_ants_all_hills();
+    /// Returns the cumulative statistics since the game started for each player where the index is the player number.
+    pub fn statistics(&self) -> Vec<PlayerStats> {
+        self.statistics.clone()
+    }
 
-        self.replay_logger.log_turn(
-            self.turn,
-            self.live_ants_per_player_count(),
-            self.hive.clone(),
-            self.scores.clone(),
+    /// Returns the players sorted by their current score, from first to last place.
+    /// Ties are broken by the number of live ants, then by the number of live hills and finally by the player number.
+    pub fn ranking(&self) -> Vec<usize> {
+        self.current_standings()
+            .into_iter()
+            .map(|(player, _, _, _)| player)
+            .collect()
+    }
+
+    /// Returns the final standings as if the game ended now, as `(player, score, live ants, live hills)` from first to last place.
+    /// The players are sorted like in `ranking`.
+    pub fn current_standings(&self) -> Vec<(usize, isize, usize, usize)> {
+        let ants_per_player = self.live_ants_per_player_count();
+        let hills_per_player = self.live_ant_hills_per_player();
+        let mut standings: Vec<(usize, isize, usize, usize)> = (0..self.map.players())
+            .map(|player| {
+                (
+                    player,
+                    self.scores[player],
+                    ants_per_player[player],
+                    hills_per_player[player].len(),
+                )
+            })
+            .collect();
+        // Sort in descending order of score, ants and hills, the sort is stable so players that are fully tied keep their order
+        standings.sort_by(
+            |(_, a_score, a_ants, a_hills), (_, b_score, b_ants, b_hills)| {
+                (b_score, b_ants, b_hills).cmp(&(a_score, a_ants, a_hills))
+            },
         );
 
-        // Compute the intial game state
-        self.game_state()
+        standings
     }
 
-    /// Updates the game state.
-    ///
-    /// # Arguments
-    /// * `actions` - The actions to take for each ant.
-    pub fn update(&mut self, actions: Vec<Action>) -> GameState {
-        if !self.started {
-            panic!("Game has not started! Call `start` to start the game.");
-        }
+    /// Returns the winner of the game, the same as the `winner` of the game state, i.e. the first player in the `ranking`
+    /// when the game finished, whatever the reason. `None` if the game has not finished or if the first two players
+    /// were tied on score, live ants and live hills.
+    pub fn winner(&self) -> Option<usize> {
+        self.winner
+    }
 
-        if self.finished {
-            panic!("Game is finished! Call `start` to start a new game.");
-        }
+    /// Returns the radius **squared** of the field of vision the game was created with.
+    /// Players can see with a different radius, see `fov_radius2_per_player`.
+    #[getter]
+    pub fn fov_radius2(&self) -> usize {
+        self.default_fov_radius2
+    }
 
-        self.turn += 1;
-        self.turn_stats = vec![TurnStats::new(self.turn); self.map.players()];
+    /// Returns the radius **squared** of the attack range of each ant.
+    #[getter]
+    pub fn attack_radius2(&self) -> usize {
+        self.attack_radius2
+    }
 
-        self.move_ants(actions);
-        self.attack();
-        self.raze_hills();
-        self.spawn_ants_from_hive();
-        self.harvest_food();
-        // Opted for spawning food randomly across the map instead of doing the symmetric spawning that the original Ants game used.
-        // The reason is that random food makes the game more challenging as it could lead to scenarios where agents aren't near any food.
-        // This will require better learning and handling of complex world states.
-        // Which we hope will ultimately lead to more robust agents.
-        self.spawn_food_randomly();
+    /// Returns the radius **squared** of the range around ants to harvest food.
+    #[getter]
+    pub fn food_radius2(&self) -> usize {
+        self.food_radius2
+    }
 
-        self.check_for_endgame();
+    /// Returns the amount of food to spawn *per player* on each round.
+    #[getter]
+    pub fn food_rate(&self) -> usize {
+        self.food_per_turn / self.map.players()
+    }
 
-        // Compute the game state before removing dead ants so that the dead ants are included in the state
-        let state = self.game_state();
-        self.remove_dead_ants();
+    /// Returns the maximum number of turns before the game ends.
+    #[getter]
+    pub fn max_turns(&self) -> usize {
+        self.max_turns
+    }
 
-        self.replay_logger.log_turn(
-            self.turn,
-            self.live_ants_per_player_count(),
-            self.hive.clone(),
-            self.scores.clone(),
-        );
+    /// Returns the name of each player, `Player N` unless they were named when creating the game.
+    #[getter]
+    pub fn player_names(&self) -> Vec<String> {
+        self.player_names.clone()
+    }
 
-        // If the game finished, log the end game and save the replay
-        if self.finished {
-            self.replay_logger.log_end_game(
-                format!("{:?}", self.finished_reason.as_ref().unwrap()),
-                self.winner,
-            );
-            self.replay_logger.save();
-        }
-
-        state
+    /// Returns the RGB color of each player, or `None` if they use the theme's palette.
+    #[getter]
+    pub fn player_colors(&self) -> Option<Vec<(u8, u8, u8)>> {
+        self.player_colors.clone()
     }
 
-    /// Draws the game to the console.
-    pub fn draw(&self) {
-        let ants = self.live_ants_per_player_count();
-        self.map.draw(self.turn, &self.scores, &ants, &self.hive);
+    /// Returns the current turn number, the same as in the last game state.
+    #[getter]
+    pub fn turn(&self) -> usize {
+        self.turn
     }
-}
 
-impl Game {
-    fn compute_initial_scores(&mut self) {
-        // Each agent starts with 1 point per hill
-        let ants_hills_per_player = self.live_ant_hills_per_player();
+    /// Returns whether the game has finished, the same as in the last game state.
+    #[getter]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
 
-        for (player, hills) in ants_hills_per_player.iter().enumerate() {
-            self.scores[player] = hills.len();
-        }
+    /// Returns the reason the game finished. `None` if the game has not finished.
+    #[getter]
+    pub fn finished_reason(&self) -> Option<FinishedReason> {
+        self.finished_reason.clone()
     }
 
-    fn spawn_food_around_hills(&mut self) {
-        let ant_hills = self.live_ant_hills();
+    /// Returns the radius **squared** of the field of vision for the ants of each player where the index is the player number.
+    #[getter]
+    pub fn fov_radius2_per_player(&self) -> Vec<usize> {
+        self.fov_radius2.clone()
+    }
 
-        // For each ant hill, collect up to 3 random land cells around it
-        let lands: Vec<(usize, usize)> = ant_hills
-            .iter()
-            .flat_map(|(_, row, col)| {
-                self.map
-                    .land_around(*row, *col)
-                    .choose_multiple(&mut self.rng, 3)
-                    .cloned()
-                    .collect::<Vec<(usize, usize)>>()
-            })
-            .collect();
+    /// Sets a different radius **squared** of the field of vision for the ants of each player.
+    ///
+    /// This only affects what the ants see, the attack and food radii are the same for all players.
+    ///
+    /// # Arguments
+    /// * `value` - The radius **squared** for each player where the index is the player number.
+    #[setter]
+    pub fn set_fov_radius2_per_player(&mut self, value: Vec<usize>) {
+        if value.len() != self.map.players() {
+            panic!(
+                "Expected a field of vision radius for each of the {} players, got {}.",
+                self.map.players(),
+                value.len()
+            );
+        }
 
-        // Spawn food on the random land cells
-        self.spawn_food(lands);
+        self.fov_radius2 = value;
     }
 
-    fn spawn_food_randomly(&mut self) {
-        // Make sure to only spawn food if there is less food than the food per turn
-        let current_food = self.map.food().len();
-
-        if current_food >= self.food_per_turn {
-            return;
-        }
+    /// Returns how distances are measured for the field of vision, attack and food ranges.
+    #[getter]
+    pub fn distance_metric(&self) -> DistanceMetric {
+        self.distance_metric.clone()
+    }
 
-        let food_to_spawn = self.food_per_turn - current_food;
-        let land = self.map.land();
-        let food_locations = land
-            .choose_multiple(&mut self.rng, food_to_spawn)
-            .cloned()
-            .collect();
+    /// Sets how distances are measured for the field of vision, attack and food ranges.
+    ///
+    /// # Arguments
+    /// * `value` - The distance metric. Defaults to `DistanceMetric::Euclidean2`.
+    #[setter]
+    pub fn set_distance_metric(&mut self, value: DistanceMetric) {
+        self.distance_metric = value;
+    }
 
-        self.spawn_food(food_locations);
+    /// Returns the order in which the phases of each turn run.
+    #[getter]
+    pub fn phase_order(&self) -> Vec<TurnPhase> {
+        self.phase_order.clone()
     }
 
-    fn spawn_food(&mut self, locations: Vec<(usize, usize)>) {
-        for (row, col) in locations {
-            self.map.set(row, col, Box::new(Food));
-            self.replay_logger.log_spawn_food(self.turn, (row, col));
+    /// Sets the order in which the phases of each turn run, e.g. to raze hills before the ants fight.
+    ///
+    /// # Arguments
+    /// * `value` - The phases in order, each phase must appear exactly once. Defaults to `TurnPhase::default_order()`,
+    ///   i.e. move, attack, raze, spawn ants, harvest and spawn food.
+    #[setter]
+    pub fn set_phase_order(&mut self, value: Vec<TurnPhase>) {
+        let is_valid = value.len() == TurnPhase::default_order().len()
+            && TurnPhase::default_order()
+                .iter()
+                .all(|phase| value.iter().filter(|other| *other == phase).count() == 1);
+        if !is_valid {
+            panic!(
+                "The phase order must contain each phase exactly once, got {:?}.",
+                value
+            );
         }
+
+        self.phase_order = value;
     }
 
-    fn spawn_ants_all_hills(&mut self) {
-        let ant_hills = self.live_ant_hills();
-        self.spawn_ants(ant_hills);
+    /// Returns whether water blocks the field of vision of the ants.
+    #[getter]
+    pub fn line_of_sight(&self) -> bool {
+        self.line_of_sight
     }
 
-    fn spawn_ants_from_hive(&mut self) {
-        let players = self.map.players();
-        let hills_by_player = self.live_ant_hills_per_player();
-        let ants_per_player = self.live_ants_per_player_count();
+    /// Sets whether water blocks the field of vision of the ants, in which case they can't see the cells behind water.
+    ///
+    /// This only affects what the ants see, attacks and harvesting still reach over water.
+    ///
+    /// # Arguments
+    /// * `value` - Whether water blocks vision. Defaults to `false`, i.e. ants see through water.
+    #[setter]
+    pub fn set_line_of_sight(&mut self, value: bool) {
+        self.line_of_sight = value;
+    }
 
-        for (player, hills) in hills_by_player.iter().enumerate().take(players) {
-            let available_food = self.hive[player];
+    /// Returns the strategy used to choose where food spawns.
+    #[getter]
+    pub fn food_spawn_strategy(&self) -> FoodSpawnStrategy {
+        self.food_spawn_strategy.clone()
+    }
 
-            if available_food == 0 {
-                continue;
-            }
+    /// Sets the strategy used to choose where food spawns.
+    ///
+    /// # Arguments
+    /// * `value` - The food spawn strategy. Defaults to `FoodSpawnStrategy::Random`.
+    #[setter]
+    pub fn set_food_spawn_strategy(&mut self, value: FoodSpawnStrategy) {
+        self.food_spawn_strategy = value;
+    }
 
-            if ants_per_player[player] >= self.max_colony_size {
-                continue;
-            }
+    /// Returns the minimum distance between randomly spawned food and any ant.
+    #[getter]
+    pub fn min_food_ant_distance(&self) -> usize {
+        self.min_food_ant_distance
+    }
 
-            // Randomly choose hills, up to the available food, to spawn ants on
-            // We do this withouth repetition to avoid spawning multiple ants on the same hill
-            let ant_hills = hills.choose_multiple(&mut self.rng, available_food);
+    /// Sets the minimum distance between randomly spawned food and any live ant, measured with the distance metric,
+    /// so that food doesn't appear right next to an ant to be harvested for free.
+    ///
+    /// If there aren't enough cells that far from the ants, the distance is relaxed one cell at a time for the rest of the food.
+    /// Fixed food locations and the symmetric images of `SymmetricMirror` are not affected, since all of them are spawned.
+    ///
+    /// # Arguments
+    /// * `value` - The minimum distance, in cells. Defaults to `0`, i.e. food can spawn anywhere.
+    #[setter]
+    pub fn set_min_food_ant_distance(&mut self, value: usize) {
+        self.min_food_ant_distance = value;
+    }
 
-            // Update the hive with the remaining food
-            self.hive[player] -= ant_hills.len();
-            // And update the turn stats
-            self.turn_stats[player].add_ants_spawned(ant_hills.len());
+    /// Returns what happens to a hill after an enemy ant razes it.
+    #[getter]
+    pub fn hill_raze_mode(&self) -> HillRazeMode {
+        self.hill_raze_mode.clone()
+    }
 
-            // Spawn ants on the chosen hills
-            self.spawn_ants(ant_hills.cloned().collect());
-        }
+    /// Sets what happens to a hill after an enemy ant razes it.
+    ///
+    /// # Arguments
+    /// * `value` - The hill raze mode. Defaults to `HillRazeMode::Disable`.
+    #[setter]
+    pub fn set_hill_raze_mode(&mut self, value: HillRazeMode) {
+        self.hill_raze_mode = value;
     }
 
-    fn spawn_ants(&mut self, ant_hills: Vec<(usize, usize, usize)>) {
-        for (player, row, col) in ant_hills {
-            let ant = Ant::from_ant_hill(player, Box::new(Hill::new(player, true)));
-            let id = ant.id().to_string();
-            self.map.set(row, col, Box::new(ant));
-            self.replay_logger
-                .log_spawn_ant(self.turn, id, player, (row, col));
-        }
+    /// Returns the score each player starts the game with.
+    #[getter]
+    pub fn starting_score(&self) -> StartingScore {
+        self.starting_score.clone()
     }
 
-    fn remove_dead_ants(&mut self) {
-        let dead_ants = self
-            .map
-            .ants()
-            .into_iter()
-            .filter(|(ant, _, _)| !ant.alive().unwrap())
-            .map(|(ant, row, col)| (ant.id().to_string(), row, col))
-            .collect::<Vec<(String, usize, usize)>>();
+    /// Sets the score each player starts the game with.
+    ///
+    /// # Arguments
+    /// * `value` - The starting score. Defaults to `StartingScore::HillCount`.
+    #[setter]
+    pub fn set_starting_score(&mut self, value: StartingScore) {
+        self.starting_score = value;
+    }
 
-        for (id, row, col) in dead_ants {
-            // If the ant was on a hill, replace the location with the hill, otherwise remove the ant
-            if let Some(hill) = self.map.get(row, col).unwrap().on_ant_hill() {
-                self.map.set(
-                    row,
-                    col,
-                    Box::new(Hill::new(hill.player().unwrap(), hill.alive().unwrap())),
-                );
-            } else {
-                self.map.remove(row, col);
-            }
+    /// Returns what happens to food surrounded by ants of more than one player.
+    #[getter]
+    pub fn contested_food(&self) -> ContestedFood {
+        self.contested_food.clone()
+    }
 
-            self.replay_logger.log_remove_ant(self.turn, id);
-        }
+    /// Sets what happens to food surrounded by ants of more than one player.
+    ///
+    /// # Arguments
+    /// * `value` - The contested food mode. Defaults to `ContestedFood::Destroy`.
+    #[setter]
+    pub fn set_contested_food(&mut self, value: ContestedFood) {
+        self.contested_food = value;
     }
 
-    fn move_ants(&mut self, actions: Vec<Action>) {
-        for action in actions {
-            let (to_row, to_col) = match action.direction {
-                Direction::North => (action.row.saturating_sub(1), action.col),
-                Direction::East => (action.row, action.col + 1),
-                Direction::South => (action.row + 1, action.col),
-                Direction::West => (action.row, action.col.saturating_sub(1)),
-            };
+    /// Returns the number of turns a dead ant stays on the map as a corpse. `0` if dead ants are removed right away.
+    #[getter]
+    pub fn corpse_lifetime(&self) -> usize {
+        self.corpse_lifetime
+    }
 
-            let id = self
-                .map
-                .get(action.row, action.col)
-                .unwrap()
-                .id()
-                .to_string();
+    /// Sets the number of turns a dead ant stays on the map as a `Corpse`, visible in the field of vision of nearby ants.
+    /// Corpses don't block movement, an ant that walks over a corpse removes it.
+    /// Ants that die on a hill don't leave a corpse, and corpses are not logged in the replay.
+    ///
+    /// # Arguments
+    /// * `value` - The number of turns after the ant died. Defaults to `0`, i.e. no corpses.
+    #[setter]
+    pub fn set_corpse_lifetime(&mut self, value: usize) {
+        self.corpse_lifetime = value;
+    }
 
-            let did_move = self
-                .map
-                .move_entity((action.row, action.col), (to_row, to_col));
-
-            if did_move {
-                self.replay_logger.log_move_ant(
-                    self.turn,
-                    id,
-                    (action.row, action.col),
-                    (to_row, to_col),
-                );
-            }
-        }
+    /// Returns the amount of hive food it costs to dig a water cell. `None` if digging is disabled.
+    #[getter]
+    pub fn dig_cost(&self) -> Option<usize> {
+        self.dig_cost
     }
 
-    fn attack(&mut self) {
-        // Pre-calculate the number of enemies for each live ant as a map of ant `id` to the Vec of enemies
-        let ants = self.live_ants();
-        let enemies: HashMap<String, Vec<(&dyn Entity, usize, usize)>> = ants
-            .iter()
-            .map(|(ant, row, col)| {
-                let fov = self.map.field_of_vision((*row, *col), self.attack_radius2);
-                let enemies = self.enemies(fov, ant.player().unwrap());
-                (ant.id().to_string(), enemies)
-            })
-            .collect();
+    /// Sets the amount of hive food it costs to dig a water cell, turning it into land, with `Action.dig`.
+    /// Digs run in the same phase and order as the moves, and are logged as `Remove` events of the water.
+    ///
+    /// # Arguments
+    /// * `value` - The amount of food per water cell. `None`, the default, means digging is disabled.
+    #[setter]
+    pub fn set_dig_cost(&mut self, value: Option<usize>) {
+        self.dig_cost = value;
+    }
 
-        // Determine which ants to kill
-        let mut to_kill = Vec::new();
-        let mut attack_logs = Vec::new();
+    /// Returns whether ants can move onto food to harvest it.
+    #[getter]
+    pub fn step_to_harvest(&self) -> bool {
+        self.step_to_harvest
+    }
 
-        for (ant, row, col) in ants {
-            let ant_enemies = enemies.get(ant.id()).unwrap();
-            let focus = ant_enemies.len();
+    /// Sets whether ants can move onto food to harvest it, in which case the food goes to the hive of the ant that moved and the ant ends on its cell.
+    /// Food around ants is still harvested at the end of the turn either way.
+    ///
+    /// # Arguments
+    /// * `value` - Whether ants can move onto food. Defaults to `false`, i.e. food blocks movement.
+    #[setter]
+    pub fn set_step_to_harvest(&mut self, value: bool) {
+        self.step_to_harvest = value;
+    }
 
-            if focus == 0 {
-                continue;
-            }
+    /// Returns whether ants carry food back to their hills instead of harvesting the food around them.
+    #[getter]
+    pub fn carry_food(&self) -> bool {
+        self.carry_food
+    }
 
-            // Find the enemy with the most attention power, i.e. the enemy with the least other ants focused on it
-            let min_enemy_focus = ant_enemies
-                .iter()
-                .map(|(enemy, _, _)| enemies.get(enemy.id()).unwrap().len())
-                .min()
-                .unwrap();
+    /// Sets whether ants carry food back to their hills instead of harvesting the food around them.
+    /// An ant that moves onto food picks it up and ends on its cell, and the food is only stored in the hive once
+    /// the ant stands on one of its own live hills at the harvest phase. Ants carry one food at a time, so food
+    /// blocks the ants that are already carrying, and the food is lost if the ant dies before delivering it.
+    ///
+    /// # Arguments
+    /// * `value` - Whether ants carry food. Defaults to `false`, i.e. the food around ants is harvested.
+    #[setter]
+    pub fn set_carry_food(&mut self, value: bool) {
+        self.carry_food = value;
+    }
 
-            // Ant dies if its focused on more or equal enemies than its enemy with the most attention power
-            if focus >= min_enemy_focus {
-                to_kill.push((ant.player().unwrap(), row, col));
-
-                // Collect attack log from each enemy to the ant
-                for (ant_enemy, enemy_row, enemy_col) in ant_enemies {
-                    attack_logs.push((
-                        ant_enemy.player().unwrap(),
-                        (*enemy_row, *enemy_col),
-                        (row, col),
-                    ));
-                }
-            }
-        }
+    /// Returns whether scores can go below 0.
+    #[getter]
+    pub fn signed_scores(&self) -> bool {
+        self.signed_scores
+    }
 
-        // After all battles are resolved, kill the ants
-        for (player, row, col) in to_kill {
-            self.map.get_mut(row, col).unwrap().set_alive(false);
-            self.turn_stats[player].add_ants_lost(1);
-        }
+    /// Sets whether scores can go below 0, e.g. when a player with no points loses a hill.
+    /// Otherwise, scores are clamped at 0.
+    ///
+    /// # Arguments
+    /// * `value` - Whether scores can go below 0. Defaults to `false`.
+    #[setter]
+    pub fn set_signed_scores(&mut self, value: bool) {
+        self.signed_scores = value;
+    }
 
-        // Log all attack events
-        for (enemy_player, enemy_pos, ant_pos) in attack_logs {
-            self.replay_logger.log_attack(self.turn, enemy_pos, ant_pos);
-            self.turn_stats[enemy_player].add_ants_killed(1);
-        }
+    /// Returns the points a player loses every time they skip a turn.
+    #[getter]
+    pub fn skip_penalty(&self) -> usize {
+        self.skip_penalty
     }
 
-    fn raze_hills(&mut self) {
-        let ants = self.live_ants();
-        let hills_to_raze: Vec<(usize, usize, usize, usize)> = ants
-            .into_iter()
-            .filter_map(|(ant, row, col)| {
-                // If the ant is on an ant hill that is not its own, the hill should be razed
-                if ant.on_ant_hill().is_some()
-                    && ant.player().unwrap()
-                        != ant.on_ant_hill().as_ref().unwrap().player().unwrap()
-                {
-                    let hill_owner = ant.on_ant_hill().as_ref().unwrap().player().unwrap();
-                    let player = ant.player().unwrap();
-                    Some((hill_owner, player, row, col))
-                } else {
-                    None
-                }
-            })
-            .collect();
+    /// Sets the points a player loses every time they skip a turn, see `skip_player_turn`.
+    ///
+    /// # Arguments
+    /// * `value` - The points to lose. Defaults to 0, i.e. no penalty.
+    #[setter]
+    pub fn set_skip_penalty(&mut self, value: usize) {
+        self.skip_penalty = value;
+    }
 
-        for (hill_owner, player, row, col) in hills_to_raze {
-            // Add the points for razing the hill to the player's score
-            self.scores[player] += self.points_for_razing_hill;
-            // Subtract the points for losing the hill from the hill owner's score
-            self.scores[hill_owner] -= self.points_for_losing_hill;
-            // Update the turn stats for both players
-            self.turn_stats[player].add_hills_razed(1);
-            self.turn_stats[hill_owner].add_hills_lost(1);
-            // Update the hill to be razed
-            self.map
-                .get_mut(row, col)
-                .unwrap()
-                .set_on_ant_hill(Box::new(Hill::new(hill_owner, false)));
-            self.replay_logger.log_remove_hill(self.turn, (row, col));
-        }
+    /// Returns the number of turns in a row an ant can stay idle before it has to eat. `0` if ants can stay idle forever.
+    #[getter]
+    pub fn idle_patience(&self) -> usize {
+        self.idle_patience
     }
 
-    fn harvest_food(&mut self) {
-        let food = self.map.food();
-        let mut ants_that_harvested_food: HashSet<(usize, usize)> = HashSet::new();
+    /// Sets the number of turns in a row an ant can stay idle before it has to eat, to discourage passive play.
+    ///
+    /// An ant is idle on a turn when it doesn't move, harvest, pick up or deliver food, or attack an enemy, e.g. because
+    /// it got no action or its move was blocked. Ants that were just spawned and ants of players that skipped the turn are not idle.
+    /// Once an ant has been idle for this many turns, it eats one food from its player's hive and its count starts over,
+    /// or it starves to death if the hive is empty.
+    ///
+    /// # Arguments
+    /// * `value` - The number of turns. Defaults to `0`, i.e. ants can stay idle forever.
+    #[setter]
+    pub fn set_idle_patience(&mut self, value: usize) {
+        self.idle_patience = value;
+    }
 
-        for (row, col) in food {
-            let ants_around_food: Vec<(usize, usize, usize)> = self
-                .map
-                .field_of_vision((row, col), self.food_radius2)
-                .into_iter()
-                .filter(|(entity, _, _)| entity.name() == "Ant")
-                .map(|(entity, row, col)| (row, col, entity.player().unwrap()))
-                .collect();
+    /// Returns whether ants standing on their own live hill can't be killed in battle.
+    #[getter]
+    pub fn hill_defense(&self) -> bool {
+        self.hill_defense
+    }
 
-            if ants_around_food.is_empty() {
-                continue;
-            }
+    /// Sets whether ants standing on their own live hill can't be killed in battle.
+    /// They still attack as usual, so the enemies fighting them can die.
+    ///
+    /// # Arguments
+    /// * `value` - Whether hills protect the ants on them. Defaults to `false`.
+    #[setter]
+    pub fn set_hill_defense(&mut self, value: bool) {
+        self.hill_defense = value;
+    }
 
-            // Check to see if there is only one player around the food
-            let unique_player_ants_around_food: HashSet<usize> = ants_around_food
-                .iter()
-                .map(|(_, _, player)| *player)
-                .collect();
+    /// Returns whether ants see who owns the enemy hills in their field of vision.
+    #[getter]
+    pub fn reveal_hill_owner(&self) -> bool {
+        self.reveal_hill_owner
+    }
 
-            // If there is only one player around the food, they consume it into their hive
-            // Otherwise, it's simply removed from the map without being consumed by anyone
-            if unique_player_ants_around_food.len() == 1 {
-                let mut can_harvest = false;
+    /// Sets whether ants see who owns the enemy hills in their field of vision.
+    /// When hidden, enemy hills are seen with no player, while a player's own hills are always seen as theirs.
+    ///
+    /// # Arguments
+    /// * `value` - Whether to reveal the owner of enemy hills. Defaults to `true`.
+    #[setter]
+    pub fn set_reveal_hill_owner(&mut self, value: bool) {
+        self.reveal_hill_owner = value;
+    }
 
-                // But first, check if the ants around the food already harvested this turn
-                for (row, col, player) in &ants_around_food {
-                    if ants_that_harvested_food.contains(&(*row, *col)) {
-                        continue;
-                    }
+    /// Returns whether moves that fail are logged, with the reason they failed.
+    #[getter]
+    pub fn log_blocked_moves(&self) -> bool {
+        self.log_blocked_moves
+    }
 
-                    // This ant can harvest the food
-                    self.hive[*player] += 1;
-                    self.turn_stats[*player].add_food_harvested(1);
-                    ants_that_harvested_food.insert((*row, *col));
-                    can_harvest = true;
-                    break;
-                }
+    /// Sets whether moves that fail are logged as `BlockedMove` events, with the reason they failed.
+    /// This helps debugging what agents tried to do versus what happened, at the cost of larger replays.
+    ///
+    /// # Arguments
+    /// * `value` - Whether to log blocked moves. Defaults to `false`.
+    #[setter]
+    pub fn set_log_blocked_moves(&mut self, value: bool) {
+        self.log_blocked_moves = value;
+    }
 
-                // No ants around the food could harvest it but since they all belong to
-                // the same player, we don't remove the food
-                if !can_harvest {
-                    continue;
-                }
-            }
+    /// Returns whether the cells that each player has ever seen are tracked.
+    #[getter]
+    pub fn track_explored(&self) -> bool {
+        self.track_explored
+    }
 
-            self.map.remove(row, col);
-            self.replay_logger.log_remove_food(self.turn, (row, col));
+    /// Sets whether to track the cells that each player has ever seen, i.e. that were ever within the field of vision of any of their ants.
+    /// They are included in the game state as `explored`, which lets agents build a persistent map.
+    ///
+    /// # Arguments
+    /// * `value` - Whether to track the explored cells. Defaults to `false` to avoid the memory cost.
+    ///   When enabled mid-game, only the cells seen from then on are explored.
+    #[setter]
+    pub fn set_track_explored(&mut self, value: bool) {
+        self.track_explored = value;
+        if !value {
+            self.explored = vec![];
         }
     }
 
-    fn live_ant_hills_per_player(&self) -> Vec<Vec<(usize, usize, usize)>> {
-        let players = self.map.players();
-        self.live_ant_hills()
-            .into_iter()
-            // Group hills by player
-            .fold(vec![vec![]; players], |mut acc, hill| {
-                acc[hill.0].push(hill);
-                acc
-            })
+    /// Returns whether the hash of the board is computed every turn.
+    #[getter]
+    pub fn track_board_hash(&self) -> bool {
+        self.track_board_hash
     }
 
-    fn live_ant_hills(&self) -> Vec<(usize, usize, usize)> {
-        self.map
-            .ant_hills()
-            .into_iter()
-            .filter(|(hill, _, _)| hill.alive().unwrap())
-            .map(|(hill, row, col)| (hill.player().unwrap(), row, col))
-            .collect()
+    /// Sets whether to compute the hash of the board every turn, see `board_hash`.
+    /// It's included in the game state as `board_hash` and in every turn of the replay, which lets two runs of the same game
+    /// be compared turn by turn to detect when they diverge.
+    ///
+    /// # Arguments
+    /// * `value` - Whether to compute the hash of the board. Defaults to `false` to avoid the cost of hashing every cell.
+    #[setter]
+    pub fn set_track_board_hash(&mut self, value: bool) {
+        self.track_board_hash = value;
     }
 
-    fn live_ants_per_player_count(&self) -> Vec<usize> {
-        let players = self.map.players();
-        self.live_ants()
-            .into_iter()
-            .fold(vec![vec![]; players], |mut acc, (ant, _, _)| {
-                acc[ant.player().unwrap()].push(ant);
-                acc
-            })
-            .iter()
-            .map(|ants| ants.len())
-            .collect::<Vec<usize>>()
+    /// Places an ant of the given player on the map to set up a scenario, see `Game::place_ant`.
+    /// Raises a `ValueError` if the ant can't be placed.
+    ///
+    /// # Arguments
+    /// * `player` - The player that owns the ant.
+    /// * `row` - The row of the cell.
+    /// * `col` - The column of the cell.
+    #[pyo3(name = "place_ant")]
+    pub fn place_ant_py(&mut self, player: usize, row: usize, col: usize) -> PyResult<()> {
+        self.place_ant(player, row, col)
+            .map_err(PyValueError::new_err)
     }
 
-    fn live_ants(&self) -> Vec<(&dyn Entity, usize, usize)> {
-        self.map
-            .ants()
-            .into_iter()
-            .filter(|(ant, _, _)| ant.alive().unwrap())
-            .collect()
+    /// Places food on the map to set up a scenario, see `Game::place_food`.
+    /// Raises a `ValueError` if the food can't be placed.
+    ///
+    /// # Arguments
+    /// * `row` - The row of the cell.
+    /// * `col` - The column of the cell.
+    #[pyo3(name = "place_food")]
+    pub fn place_food_py(&mut self, row: usize, col: usize) -> PyResult<()> {
+        self.place_food(row, col).map_err(PyValueError::new_err)
     }
 
-    fn enemies<'a>(
-        &'a self,
-        field_of_vision: Vec<(&'a dyn Entity, usize, usize)>,
+    /// Places a hill of the given player on the map to set up a scenario, see `Game::place_hill`.
+    /// Raises a `ValueError` if the hill can't be placed.
+    ///
+    /// # Arguments
+    /// * `player` - The player that owns the hill.
+    /// * `row` - The row of the cell.
+    /// * `col` - The column of the cell.
+    /// * `alive` - Whether the hill is alive, i.e. not razed.
+    #[pyo3(name = "place_hill")]
+    pub fn place_hill_py(
+        &mut self,
         player: usize,
-    ) -> Vec<(&'a dyn Entity, usize, usize)> {
-        field_of_vision
-            .into_iter()
-            .filter(|(entity, _, _)| {
-                entity.name() == "Ant"
-                    && entity.alive().is_some()
-                    && entity.alive().unwrap()
-                    && entity.player().is_some()
-                    && entity.player().unwrap() != player
-            })
-            .collect()
+        row: usize,
+        col: usize,
+        alive: bool,
+    ) -> PyResult<()> {
+        self.place_hill(player, row, col, alive)
+            .map_err(PyValueError::new_err)
     }
 
-    fn game_state(&self) -> GameState {
-        let players = self.map.players();
-        let ants = self
-            .live_ants()
+    /// Marks the player as having failed to respond in time, e.g. because they ran out of their time budget.
+    /// On the next update, all the actions for the ants of this player are ignored so their ants hold their position,
+    /// and the player loses the points set by `skip_penalty`. The rest of the turn is played as usual.
+    ///
+    /// # Arguments
+    /// * `player` - The player that skips the next turn.
+    pub fn skip_player_turn(&mut self, player: usize) {
+        if player >= self.map.players() {
+            panic!(
+                "Invalid player {}, the game only has {} players.",
+                player,
+                self.map.players()
+            );
+        }
+
+        self.skipped_players.insert(player);
+    }
+
+    /// Eliminates the player from the game, e.g. because they resigned or timed out too many times.
+    /// All of their ants are killed and removed from the map, and their hills become land so no more ants are spawned for them.
+    /// The player is still listed in the game state, with no ants, but no longer counts as a remaining player for the endgame.
+    ///
+    /// # Arguments
+    /// * `player` - The player to eliminate.
+    pub fn eliminate_player(&mut self, player: usize) {
+        if player >= self.map.players() {
+            panic!(
+                "Invalid player {}, the game only has {} players.",
+                player,
+                self.map.players()
+            );
+        }
+
+        self.eliminated_players.insert(player);
+        self.skipped_players.remove(&player);
+
+        // Kill all the player's ants and remove them right away, restoring any hill they were standing on
+        for (_, row, col) in self
+            .live_ant_locations()
             .into_iter()
-            .map(|(ant, row, col)| PlayerAnt {
-                id: ant.id().to_string(),
-                row,
-                col,
-                player: ant.player().unwrap(),
-                alive: ant.alive().unwrap(),
-                field_of_vision: self
-                    .map
-                    .field_of_vision((row, col), self.fov_radius2)
-                    .into_iter()
-                    .map(|(entity, row, col)| self.to_state_entity(entity, row, col))
-                    .collect(),
+            .filter(|(owner, _, _)| *owner == player)
+        {
+            self.map.get_mut(row, col).unwrap().set_alive(false);
+        }
+        self.remove_dead_ants();
+
+        // Neutralize the player's hills, including the ones with an enemy ant on them
+        let hills: Vec<(usize, usize)> = (0..self.map.height())
+            .flat_map(|row| (0..self.map.width()).map(move |col| (row, col)))
+            .filter(|(row, col)| {
+                self.map.get(*row, *col).is_some_and(|entity| {
+                    let hill = match entity.name() {
+                        "Hill" => Some(entity),
+                        _ => entity.on_ant_hill(),
+                    };
+                    hill.is_some_and(|hill| hill.player() == Some(player))
+                })
             })
-            // Group ants by player
-            .fold(vec![vec![]; players], |mut acc, ant| {
-                acc[ant.player].push(ant);
-                acc
-            });
+            .collect();
 
-        GameState {
-            turn: self.turn,
-            scores: self.scores.clone(),
-            ants,
-            hive: self.hive.clone(),
-            turn_stats: self.turn_stats.clone(),
-            finished: self.finished,
-            finished_reason: self.finished_reason.clone(),
-            winner: self.winner,
+        for (row, col) in hills {
+            let entity = self.map.get(row, col).unwrap();
+            if entity.name() == "Hill" {
+                self.map.remove(row, col);
+            } else {
+                let mut ant = Ant::new(
+                    entity.id().to_string(),
+                    entity.player().unwrap(),
+                    entity.alive().unwrap(),
+                    None,
+                );
+                ant.set_carrying(entity.carrying().unwrap());
+                self.map.set(row, col, Box::new(ant));
+            }
+            self.scored_hills.remove(&(row, col));
+            self.replay_logger.log_remove_hill(self.turn, (row, col));
         }
     }
 
-    fn to_state_entity(&self, entity: &dyn Entity, row: usize, col: usize) -> StateEntity {
-        StateEntity {
-            name: entity.name().to_string(),
-            row,
-            col,
-            player: entity.player(),
-            alive: entity.alive(),
-        }
+    /// Returns the maximum amount of food that a player can store in their hive. `None` if there is no limit.
+    #[getter]
+    pub fn max_hive(&self) -> Option<usize> {
+        self.max_hive
     }
 
-    fn check_for_endgame(&mut self) {
-        self.check_for_food_not_being_gathered();
+    /// Sets the maximum amount of food that a player can store in their hive.
+    ///
+    /// Food harvested while the hive is full is still removed from the map, but it's not stored.
+    ///
+    /// # Arguments
+    /// * `value` - The maximum amount of food per hive. `None`, the default, means there is no limit.
+    #[setter]
+    pub fn set_max_hive(&mut self, value: Option<usize>) {
+        self.max_hive = value;
+    }
 
-        if self.turns_with_too_much_food >= self.cutoff_threshold {
-            self.finished = true;
-            self.finished_reason = Some(FinishedReason::TooMuchFood);
-            self.winner = None;
+    /// Returns whether the hive of each player is split into a bucket per hill.
+    #[getter]
+    pub fn per_hill_hive(&self) -> bool {
+        self.per_hill_hive
+    }
 
-            return;
+    /// Sets whether the hive of each player is split into a bucket per hill, to model localized production.
+    ///
+    /// Harvested food is banked at the player's live hill nearest to the ant that harvested it, and it's not stored when
+    /// the player has no live hill left. Each hill only spawns ants with the food banked at it, while digging and
+    /// idle ants eat from the hills nearest to the ant. Food can be moved between hills with `transfer_hive_food`.
+    /// The hive in the game state is still the total food of each player, across all their hills.
+    /// When a hill is razed, the food banked at it is moved to the player's nearest live hill.
+    /// Panics if the game has started, since the food already in the hives is not banked at any hill.
+    ///
+    /// # Arguments
+    /// * `value` - Whether to split the hive per hill. Defaults to `false`, i.e. a single hive shared by all the hills.
+    #[setter]
+    pub fn set_per_hill_hive(&mut self, value: bool) {
+        if self.started {
+            panic!("The hive can only be split per hill before the game starts.");
         }
 
-        let remaining_players = self.remaining_players();
-        if remaining_players.len() == 1 {
-            self.finished = true;
-            self.finished_reason = Some(FinishedReason::LoneSurvivor);
-            self.winner = Some(*remaining_players.iter().next().unwrap());
+        self.per_hill_hive = value;
+    }
 
-            return;
+    /// Returns the food banked at the hill at the given location, see `per_hill_hive`.
+    /// It's `0` if there is no food banked there, e.g. because the hive is not split per hill.
+    ///
+    /// # Arguments
+    /// * `row` - The row of the hill.
+    /// * `col` - The column of the hill.
+    pub fn hill_hive(&self, row: usize, col: usize) -> usize {
+        self.hill_hives
+            .iter()
+            .filter(|((_, hill_row, hill_col), _)| (*hill_row, *hill_col) == (row, col))
+            .map(|(_, food)| *food)
+            .sum()
+    }
+
+    /// Moves food banked at one of the player's hills to another one of their live hills, see `per_hill_hive`,
+    /// and returns the amount of food moved, which is at most the food banked at the source hill.
+    /// Panics if the hive is not split per hill,
+    /// or if the destination is not a live hill of the player.
+    ///
+    /// # Arguments
+    /// * `player` - The player that owns both hills.
+    /// * `from_hill` - The row and column of the hill to take the food from.
+    /// * `to_hill` - The row and column of the hill to bank the food at.
+    /// * `amount` - The amount of food to move.
+    pub fn transfer_hive_food(
+        &mut self,
+        player: usize,
+        from_hill: (usize, usize),
+        to_hill: (usize, usize),
+        amount: usize,
+    ) -> usize {
+        if !self.per_hill_hive {
+            panic!("Food can only be transferred between hills when the hive is split per hill.");
         }
 
-        let (rank_stabilized, leader) = self.rank_stabilized();
-        if rank_stabilized {
-            self.finished = true;
-            self.finished_reason = Some(FinishedReason::RankStabilized);
-            self.winner = leader;
+        if !self
+            .live_hills(player)
+            .contains(&(player, to_hill.0, to_hill.1))
+        {
+            panic!(
+                "There is no live hill of player {} at ({}, {}) to transfer food to.",
+                player, to_hill.0, to_hill.1
+            );
+        }
 
-            return;
+        let banked = self
+            .hill_hives
+            .get_mut(&(player, from_hill.0, from_hill.1))
+            .filter(|_| from_hill != to_hill);
+        let Some(banked) = banked else {
+            return 0;
+        };
+
+        let moved = amount.min(*banked);
+        *banked -= moved;
+        *self
+            .hill_hives
+            .entry((player, to_hill.0, to_hill.1))
+            .or_default() += moved;
+
+        moved
+    }
+
+    /// Returns the maximum amount of food each ant can harvest on a turn.
+    #[getter]
+    pub fn max_food_per_ant_per_turn(&self) -> usize {
+        self.max_food_per_ant_per_turn
+    }
+
+    /// Sets the maximum amount of food each ant can harvest on a turn, e.g. when it's surrounded by food.
+    ///
+    /// # Arguments
+    /// * `value` - The maximum amount of food per ant per turn, at least `1`. Defaults to `1`.
+    #[setter]
+    pub fn set_max_food_per_ant_per_turn(&mut self, value: usize) {
+        if value == 0 {
+            panic!("The maximum food per ant per turn must be at least 1.");
         }
 
-        if self.turn >= self.max_turns {
-            self.finished = true;
-            self.finished_reason = Some(FinishedReason::TurnLimitReached);
-            self.winner = None;
+        self.max_food_per_ant_per_turn = value;
+    }
+
+    /// Returns the number of turns in a stalemate after which the game ends. `None` if stalemates are not detected.
+    #[getter]
+    pub fn stalemate_patience(&self) -> Option<usize> {
+        self.stalemate_patience
+    }
+
+    /// Sets the number of turns in a stalemate after which the game ends, won by the player first in the ranking if any.
+    ///
+    /// A turn is in a stalemate when no ant can walk to any food, and no ant can walk to an enemy ant or to an enemy live hill,
+    /// e.g. because the ants are walled off from each other by water.
+    ///
+    /// # Arguments
+    /// * `value` - The number of turns. `None`, the default, means stalemates are not detected.
+    #[setter]
+    pub fn set_stalemate_patience(&mut self, value: Option<usize>) {
+        self.stalemate_patience = value;
+    }
+
+    /// Returns whether only the food some ant can reach counts when checking if the food is not being gathered.
+    #[getter]
+    pub fn reachable_food_only(&self) -> bool {
+        self.reachable_food_only
+    }
+
+    /// Sets whether only the food some ant can reach counts when checking if the food is not being gathered.
+    ///
+    /// The game ends with `FinishedReason::TooMuchFood` when the food is most of the food and ants on the map for too long.
+    /// With this option, food walled off from all the ants, e.g. by water, is left out so it can't end the game on its own.
+    ///
+    /// # Arguments
+    /// * `value` - Whether to only count reachable food. Defaults to `false`.
+    #[setter]
+    pub fn set_reachable_food_only(&mut self, value: bool) {
+        self.reachable_food_only = value;
+    }
+
+    /// Returns the number of ants each hill starts the game with.
+    #[getter]
+    pub fn starting_ants_per_hill(&self) -> usize {
+        self.starting_ants_per_hill
+    }
+
+    /// Sets the number of ants each hill starts the game with.
+    ///
+    /// The first ant is spawned on the hill and the rest on the land around it, widening the search if needed.
+    /// If there isn't enough land, as many ants as fit are spawned.
+    ///
+    /// # Arguments
+    /// * `value` - The number of starting ants per hill. Defaults to `1`.
+    #[setter]
+    pub fn set_starting_ants_per_hill(&mut self, value: usize) {
+        self.starting_ants_per_hill = value;
+    }
+
+    /// Returns the amount of food spawned around each hill at the start of the game.
+    #[getter]
+    pub fn starting_food_per_hill(&self) -> usize {
+        self.starting_food_per_hill
+    }
+
+    /// Sets the amount of food spawned around each hill at the start of the game.
+    ///
+    /// Food is only spawned on the land right next to the hill, so if there isn't enough land, as much food as fits is spawned.
+    ///
+    /// # Arguments
+    /// * `value` - The amount of starting food per hill. Defaults to `3`.
+    #[setter]
+    pub fn set_starting_food_per_hill(&mut self, value: usize) {
+        self.starting_food_per_hill = value;
+    }
+
+    /// Returns the maximum number of ants each hill can spawn from the hive on a turn.
+    #[getter]
+    pub fn spawn_burst(&self) -> usize {
+        self.spawn_burst
+    }
+
+    /// Sets the maximum number of ants each hill can spawn from the hive on a turn.
+    ///
+    /// The first ant is spawned on the hill and the rest on the land right next to it, as long as there is food in the hive.
+    ///
+    /// # Arguments
+    /// * `value` - The maximum number of ants per hill per turn, at least `1`. Defaults to `1`.
+    #[setter]
+    pub fn set_spawn_burst(&mut self, value: usize) {
+        if value == 0 {
+            panic!("The spawn burst must be at least 1.");
         }
+
+        self.spawn_burst = value;
     }
 
-    fn check_for_food_not_being_gathered(&mut self) {
-        let total_food = self.map.food().len();
-        let total_ants = self.map.ants().len();
-        let food_pct = total_food as f64 / (total_food + total_ants) as f64;
+    /// Starts the game after reseeding it, see `Game::start_with_seed`.
+    /// Raises the exception of an event observer or the reward hook if one raised while starting.
+    ///
+    /// # Arguments
+    /// * `seed` - The new seed for the random number generator. Each player gets its own stream derived from it.
+    #[pyo3(name = "start_with_seed")]
+    pub fn start_with_seed_py(&mut self, seed: u64) -> PyResult<GameState> {
+        let state = self.start_with_seed(seed);
+        self.raise_callback_error(state)
+    }
 
-        // If the food is 85% or more of the count of ants and food then the food is not being gathered properly
-        if food_pct >= 0.85 {
-            self.turns_with_too_much_food += 1;
-        } else {
-            // Reset the count if the food is being gathered properly
-            self.turns_with_too_much_food = 0;
+    /// Starts the game, see `Game::start`.
+    /// Raises the exception of an event observer or the reward hook if one raised while starting.
+    #[pyo3(name = "start")]
+    pub fn start_py(&mut self) -> PyResult<GameState> {
+        let state = self.start();
+        self.raise_callback_error(state)
+    }
+
+    /// Updates the game state, see `Game::update`.
+    /// Raises the exception of an event observer or the reward hook if one raised during the turn.
+    ///
+    /// # Arguments
+    /// * `actions` - The actions to take for each ant.
+    #[pyo3(name = "update")]
+    pub fn update_py(&mut self, actions: Vec<Action>) -> PyResult<GameState> {
+        let state = self.update(actions);
+        self.raise_callback_error(state)
+    }
+
+    /// Updates the game state, see `Game::try_update`.
+    /// Raises the exception of an event observer or the reward hook if one raised during the turn.
+    ///
+    /// # Arguments
+    /// * `actions` - The actions to take for each ant.
+    #[pyo3(name = "try_update")]
+    pub fn try_update_py(&mut self, actions: Vec<Action>) -> PyResult<GameState> {
+        let state = self.try_update(actions)?;
+        self.raise_callback_error(state)
+    }
+
+    /// Fast-forwards the game by updating it with each turn's actions in order, e.g. to reach a recorded turn.
+    /// Stops early if the game finishes, in which case the remaining actions are ignored.
+    ///
+    /// # Arguments
+    /// * `actions_per_turn` - The actions to take for each ant, for each turn.
+    #[pyo3(name = "fast_forward")]
+    pub fn fast_forward_py(&mut self, actions_per_turn: Vec<Vec<Action>>) -> PyResult<GameState> {
+        let state = self.fast_forward(&actions_per_turn);
+        self.raise_callback_error(state)
+    }
+
+    /// Returns the directions of the shortest path between two cells, or `None` if the destination can't be reached.
+    ///
+    /// # Arguments
+    /// * `from` - The `(row, col)` to start from.
+    /// * `to` - The `(row, col)` to reach.
+    /// * `avoid_entities` - Whether food and ants block the path. Water always does.
+    #[pyo3(signature = (from, to, avoid_entities=false))]
+    pub fn path(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+        avoid_entities: bool,
+    ) -> Option<Vec<Direction>> {
+        self.map.shortest_path(from, to, avoid_entities)
+    }
+
+    /// Returns the number of moves of the shortest path between two cells, or `None` if the destination can't be reached.
+    ///
+    /// # Arguments
+    /// * `from` - The `(row, col)` to start from.
+    /// * `to` - The `(row, col)` to reach.
+    /// * `avoid_entities` - Whether food and ants block the path. Water always does.
+    #[pyo3(signature = (from, to, avoid_entities=false))]
+    pub fn distance(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+        avoid_entities: bool,
+    ) -> Option<usize> {
+        self.map.distance(from, to, avoid_entities)
+    }
+
+    /// Returns the location of the food closest to the given cell by the number of moves, or `None` if no food can be reached.
+    ///
+    /// # Arguments
+    /// * `row` - The row of the cell, usually the location of an ant.
+    /// * `col` - The column of the cell, usually the location of an ant.
+    /// * `within_fov` - Whether to only consider food within the field of vision of the ant in the cell, so that agents don't use information they can't see.
+    #[pyo3(signature = (row, col, within_fov=false))]
+    pub fn nearest_food(&self, row: usize, col: usize, within_fov: bool) -> Option<(usize, usize)> {
+        if !within_fov {
+            return self.map.nearest_food((row, col), |_| true);
         }
+
+        // Without an ant, there is no field of vision to restrict the search to
+        let player = self.map.get(row, col).and_then(|entity| entity.player())?;
+        self.map.nearest_food((row, col), |(food_row, food_col)| {
+            let (rows, cols) = self.map.abs_diff((row, col), (food_row, food_col));
+            self.distance_metric
+                .within(rows, cols, self.fov_radius2[player])
+                && (!self.line_of_sight
+                    || self.map.in_line_of_sight((row, col), (food_row, food_col)))
+        })
+    }
+
+    /// Returns the connected region of each cell, as a grid of region ids, where water belongs to no region.
+    /// Two cells are in the same region if an ant can walk from one to the other, ignoring food and other ants.
+    pub fn regions(&self) -> Vec<Vec<Option<usize>>> {
+        self.map.regions()
+    }
+
+    /// Returns the id of the connected region of the given cell, or `None` if the cell is water.
+    ///
+    /// # Arguments
+    /// * `row` - The row of the cell.
+    /// * `col` - The column of the cell.
+    pub fn region_of(&self, row: usize, col: usize) -> Option<usize> {
+        self.map.region_of(row, col)
+    }
+
+    /// Returns the number of cells controlled by each player, where the index is the player number.
+    /// A cell is controlled by the player with the closest live hill by the number of moves, cells equally close to different players are contested and not counted.
+    pub fn territory_sizes(&self) -> Vec<usize> {
+        self.map.territory().1
+    }
+
+    /// Registers a Python callable that is called with the turn and the event for every event in the game,
+    /// as they happen and in the same order as in the replay.
+    /// If the callable raises an exception, the call that played the turn raises it too.
+    ///
+    /// # Arguments
+    /// * `observer` - The callable to call with the turn and the event.
+    #[pyo3(name = "add_event_observer")]
+    pub fn add_event_observer_py(&mut self, observer: PyObject) {
+        let errors = self.callback_error.clone();
+        self.add_event_observer(move |turn, event| {
+            Python::with_gil(|py| {
+                // The turn is played to the end before the exception is raised, so that the game stays consistent
+                if let Err(error) = observer.call1(py, (turn, event.clone())) {
+                    errors.lock().unwrap().get_or_insert(error);
+                }
+            })
+        });
+    }
+
+    /// Sets a Python callable that computes the reward of each player at the end of every turn.
+    /// It's called with the state before the turn, the actions, the state after the turn and the events of the turn,
+    /// and must return a list with the reward of each player.
+    /// If it's set after calling `start`, the first rewards are computed from the state at the time it's set.
+    /// If the callable raises an exception, the call that played the turn raises it too.
+    ///
+    /// # Arguments
+    /// * `hook` - The callable that computes the reward of each player.
+    #[pyo3(name = "set_reward_hook")]
+    pub fn set_reward_hook_py(&mut self, hook: PyObject) {
+        let errors = self.callback_error.clone();
+        self.set_reward_hook(move |last_state, actions, state, events| {
+            Python::with_gil(|py| {
+                hook.call1(
+                    py,
+                    (
+                        last_state.clone(),
+                        actions.to_vec(),
+                        state.clone(),
+                        events.to_vec(),
+                    ),
+                )
+                .and_then(|rewards| rewards.extract::<Vec<f64>>(py))
+                .unwrap_or_else(|error| {
+                    errors.lock().unwrap().get_or_insert(error);
+                    vec![]
+                })
+            })
+        });
+    }
+
+    /// Returns the reward of each player for the last turn, as computed by the reward hook.
+    /// `None` if there is no reward hook or no turn has been played yet.
+    #[getter]
+    pub fn last_rewards(&self) -> Option<Vec<f64>> {
+        self.last_rewards.clone()
     }
 
-    fn remaining_players(&self) -> HashSet<usize> {
+    /// Returns the live ant with the given id, or `None` if there is no such ant on the map.
+    /// Ant ids are stable across turns, so agents can use them to keep track of each ant.
+    ///
+    /// # Arguments
+    /// * `id` - The id of the ant.
+    pub fn ant_by_id(&self, id: &str) -> Option<PlayerAnt> {
         self.live_ants()
             .into_iter()
-            .map(|(ant, _, _)| ant.player().unwrap())
-            .collect::<HashSet<usize>>()
+            .find(|(ant, _, _)| ant.id() == id)
+            .map(|(ant, row, col)| self.to_player_ant(ant, row, col))
     }
 
-    fn rank_stabilized(&self) -> (bool, Option<usize>) {
-        let live_ant_hills_per_player = self.live_ant_hills_per_player();
-        let current_scores = &self.scores;
+    /// Returns the directions in which the ant at the given cell can move, in the order North, East, South and West.
+    /// Moves off the map or onto water, food or a dead ant are not legal. Moving onto a live ant is, even though both ants die.
+    /// Empty if there is no live ant at the cell.
+    ///
+    /// # Arguments
+    /// * `row` - The row of the ant.
+    /// * `col` - The column of the ant.
+    pub fn legal_moves(&self, row: usize, col: usize) -> Vec<Direction> {
+        Direction::all()
+            .into_iter()
+            .filter(|direction| {
+                self.map
+                    .blocked_move_reason((row, col), self.map.step((row, col), direction))
+                    .is_none()
+            })
+            .collect()
+    }
 
-        // If all players are tied, the rank isn't stabilized yet
-        if current_scores
-            .iter()
-            .all(|score| *score == current_scores[0])
-        {
-            return (false, None);
-        }
+    /// Returns every entity on the map, one per occupied cell in board order, regardless of the field of vision of any ant.
+    /// This is full information meant for spectators such as custom renderers, so it shouldn't be given to competing agents.
+    pub fn entities(&self) -> Vec<StateEntity> {
+        self.map
+            .entities()
+            .into_iter()
+            .map(|(entity, row, col)| self.to_state_entity(entity, row, col))
+            .collect()
+    }
 
-        // Get the player that is in the lead
-        let (leader, leader_score) = current_scores
-            .iter()
-            .enumerate()
-            .max_by_key(|(_, score)| *score)
-            .unwrap();
+    /// Returns a hash of the board, i.e. of the position and state of every entity on the map.
+    /// It doesn't depend on the order the entities are visited in nor on their random ids, so two runs with the same
+    /// map, seed and actions have the same hash on every turn, and a different hash means the runs diverged.
+    pub fn board_hash(&self) -> u64 {
+        // Summing the hashes of the entities makes the result independent of their order
+        self.map
+            .entities()
+            .into_iter()
+            .fold(0u64, |hash, (entity, row, col)| {
+                hash.wrapping_add(entity_hash(entity, row, col))
+            })
+    }
 
-        // For each other player, compute their score as if they were to raze all other hills
-        for player in 0..self.map.players() {
-            if player == leader {
-                continue;
-            }
+    /// Returns the current state with the whole board revealed in `GameState::board`, regardless of the field of vision of any ant.
+    /// This is full information meant to debug agents, e.g. to compare what they observe with the actual board,
+    /// so it shouldn't be given to competing agents. The states returned by `start` and `update` never reveal the board.
+    pub fn debug_state(&self) -> GameState {
+        let mut state = self.game_state(&self.live_ant_locations());
+        state.rewards = self.last_rewards.clone();
+        state.board = Some(self.entities());
 
-            let mut scores = current_scores.clone();
-            for (other_player, hills) in live_ant_hills_per_player.iter().enumerate() {
-                if other_player == player {
-                    continue;
-                }
+        state
+    }
+
+    /// Draws the game to the console.
+    pub fn draw(&self) {
+        let ants = self.live_ants_per_player_count();
+        self.map.draw(
+            self.turn,
+            &self.scores,
+            &ants,
+            &self.hive,
+            &self.player_names,
+            &self.render_theme,
+        );
+    }
+
+    /// Renders the game to a plain string, i.e. the same visual as `draw` but without colors or terminal commands.
+    pub fn render(&self) -> String {
+        let ants = self.live_ants_per_player_count();
+        self.map.render_to_string(
+            self.turn,
+            &self.scores,
+            &ants,
+            &self.hive,
+            &self.player_names,
+            &self.render_theme,
+        )
+    }
+}
+
+impl Game {
+    /// Creates a new game.
+    ///
+    /// # Arguments
+    /// * `map_contents` - The map as a string.
+    /// * `fov_radius2` - The radius **squared** of the field of vision for each ant.
+    /// * `attack_radius2` - The radius **squared** of the attack range for each ant.
+    /// * `food_radius2` - The radius **squared** of the range around ants to harvest food. `0` means the ant must be right next to the food, i.e. north, east, south or west of it.
+    /// * `food_rate` - The amount of food to spawn *per player* on each round.
+    /// * `max_turns` - The maximum number of turns before the game ends.
+    /// * `seed` - The seed for the random number generator. Each player gets its own stream derived from it.
+    /// * `max_colony_size` - The maximum number of live ants that a player can have at any time.
+    /// * `replay_filename` - The filename to save the replay of the game to. If `None`, no replay will be saved.
+    ///
+    /// Panics if the map can't be played, see `try_new`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        map_contents: &str,
+        fov_radius2: usize,
+        attack_radius2: usize,
+        food_radius2: usize,
+        food_rate: usize,
+        max_turns: usize,
+        max_colony_size: usize,
+        seed: u64,
+        replay_filename: Option<String>,
+    ) -> Game {
+        Game::try_new(
+            map_contents,
+            fov_radius2,
+            attack_radius2,
+            food_radius2,
+            food_rate,
+            max_turns,
+            max_colony_size,
+            seed,
+            replay_filename,
+        )
+        .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a new game like `new`, returning a descriptive error if the map can't be played instead of panicking:
+    /// it must be well formed, have at least 1 player, every ant and hill must belong to a declared player
+    /// and every player must have at least 1 hill. See `new` for the arguments.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new(
+        map_contents: &str,
+        fov_radius2: usize,
+        attack_radius2: usize,
+        food_radius2: usize,
+        food_rate: usize,
+        max_turns: usize,
+        max_colony_size: usize,
+        seed: u64,
+        replay_filename: Option<String>,
+    ) -> Result<Game, String> {
+        let map = Map::parse(map_contents)?;
+        check_playable(&map)?;
+
+        Ok(Game::from_map(
+            map,
+            map_contents,
+            fov_radius2,
+            attack_radius2,
+            food_radius2,
+            food_rate,
+            max_turns,
+            max_colony_size,
+            seed,
+            replay_filename,
+        ))
+    }
+
+    /// Creates a new game like `new`, without checking whether the map can be played, so that each phase of a turn
+    /// can be tested on the smallest map that shows it, e.g. ants fighting without any hill.
+    #[cfg(test)]
+    #[allow(clippy::too_many_arguments)]
+    fn new_unchecked(
+        map_contents: &str,
+        fov_radius2: usize,
+        attack_radius2: usize,
+        food_radius2: usize,
+        food_rate: usize,
+        max_turns: usize,
+        max_colony_size: usize,
+        seed: u64,
+        replay_filename: Option<String>,
+    ) -> Game {
+        Game::from_map(
+            Map::parse(map_contents).unwrap(),
+            map_contents,
+            fov_radius2,
+            attack_radius2,
+            food_radius2,
+            food_rate,
+            max_turns,
+            max_colony_size,
+            seed,
+            replay_filename,
+        )
+    }
+
+    /// Creates a new game on the already parsed map, without checking whether it can be played.
+    #[allow(clippy::too_many_arguments)]
+    fn from_map(
+        map: Map,
+        map_contents: &str,
+        fov_radius2: usize,
+        attack_radius2: usize,
+        food_radius2: usize,
+        food_rate: usize,
+        max_turns: usize,
+        max_colony_size: usize,
+        seed: u64,
+        replay_filename: Option<String>,
+    ) -> Game {
+        let players = map.players();
+        let width = map.width();
+        let height = map.height();
+
+        Game {
+            map,
+            map_contents: map_contents.to_string(),
+            fov_radius2: vec![fov_radius2; players],
+            default_fov_radius2: fov_radius2,
+            attack_radius2,
+            food_radius2,
+            turn: 0,
+            scores: vec![0; players],
+            hive: vec![0; players],
+            per_hill_hive: false,
+            hill_hives: BTreeMap::new(),
+            turn_stats: vec![TurnStats::new(0); players],
+            statistics: vec![PlayerStats::new(); players],
+            food_per_turn: food_rate * players,
+            food_spawn_strategy: FoodSpawnStrategy::Random(),
+            min_food_ant_distance: 0,
+            hill_raze_mode: HillRazeMode::Disable,
+            starting_score: StartingScore::HillCount(),
+            contested_food: ContestedFood::Destroy,
+            corpse_lifetime: 0,
+            dig_cost: None,
+            last_moves: BTreeMap::new(),
+            idle_patience: 0,
+            idle_turns: BTreeMap::new(),
+            active_ants: BTreeSet::new(),
+            corpses: BTreeMap::new(),
+            step_to_harvest: false,
+            carry_food: false,
+            signed_scores: false,
+            skip_penalty: 0,
+            hill_defense: false,
+            reveal_hill_owner: true,
+            log_blocked_moves: false,
+            track_explored: false,
+            track_board_hash: false,
+            explored: vec![],
+            skipped_players: BTreeSet::new(),
+            eliminated_players: BTreeSet::new(),
+            scored_hills: BTreeSet::new(),
+            placements: vec![],
+            distance_metric: DistanceMetric::Euclidean2,
+            phase_order: TurnPhase::default_order(),
+            line_of_sight: false,
+            started: false,
+            finished: false,
+            finished_reason: None,
+            winner: None,
+            cutoff_threshold: 150,
+            turns_with_too_much_food: 0,
+            reachable_food_only: false,
+            stalemate_patience: None,
+            turns_in_stalemate: 0,
+            points_for_razing_hill: 2,
+            points_for_losing_hill: 1,
+            max_turns,
+            max_colony_size,
+            max_hive: None,
+            max_food_per_ant_per_turn: 1,
+            starting_ants_per_hill: 1,
+            starting_food_per_hill: 3,
+            spawn_burst: 1,
+            render_theme: RenderTheme::default(),
+            replay_logger: create_replay_logger(
+                replay_filename,
+                players,
+                width,
+                height,
+                map_contents.to_string(),
+            ),
+            rng: StdRng::seed_from_u64(seed),
+            player_rngs: (0..players)
+                .map(|player| StdRng::seed_from_u64(player_seed(seed, player)))
+                .collect(),
+            // Ant ids come from their own stream so that generating them doesn't change the rest of the game
+            id_rng: StdRng::seed_from_u64(id_seed(seed)),
+            reward_hook: None,
+            last_state: None,
+            last_rewards: None,
+            turn_events: None,
+            callback_error: Arc::new(Mutex::new(None)),
+            player_names: (0..players).map(default_player_name).collect(),
+            player_colors: None,
+        }
+    }
+
+    /// Replaces the stream for map-wide random decisions with the given generator, e.g. to pin the exact food
+    /// placements of a golden test by sharing the generator state as a fixture.
+    /// Only where food spawns depends on it, the players' streams and the ant ids are still derived from the seed.
+    /// It's kept when the game is started again, unless it's reseeded with `start_with_seed`.
+    ///
+    /// # Arguments
+    /// * `rng` - The generator, in the exact state the first random decision is drawn from.
+    pub fn with_rng(mut self, rng: StdRng) -> Game {
+        self.rng = rng;
+        self
+    }
+
+    /// Checks that the map can be played, returning a descriptive error otherwise, the same as `try_new`.
+    ///
+    /// # Arguments
+    /// * `map_contents` - The map as a string.
+    pub fn check_map(map_contents: &str) -> Result<(), String> {
+        check_playable(&Map::parse(map_contents)?)
+    }
+
+    /// Sets the names and colors of the players, e.g. to tell agents apart in dashboards.
+    /// The names are used by `draw`, `render` and the replay, and the colors by `draw` and the replay.
+    ///
+    /// Panics if there isn't exactly one name or color for each player, see `try_set_players`.
+    ///
+    /// # Arguments
+    /// * `names` - The name of each player. `None` keeps the current names, `Player N` by default.
+    /// * `colors` - The RGB color of each player. `None` keeps the current colors, the theme's palette by default.
+    pub fn set_players(&mut self, names: Option<Vec<String>>, colors: Option<Vec<(u8, u8, u8)>>) {
+        self.try_set_players(names, colors)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Sets the names and colors of the players, returning an error instead of panicking
+    /// if there isn't exactly one name or color for each player, in which case nothing is set.
+    ///
+    /// # Arguments
+    /// * `names` - The name of each player. `None` keeps the current names, `Player N` by default.
+    /// * `colors` - The RGB color of each player. `None` keeps the current colors, the theme's palette by default.
+    pub fn try_set_players(
+        &mut self,
+        names: Option<Vec<String>>,
+        colors: Option<Vec<(u8, u8, u8)>>,
+    ) -> Result<(), String> {
+        let players = self.map.players();
+
+        if let Some(names) = &names {
+            if names.len() != players {
+                return Err(format!(
+                    "Expected a name for each of the {} players, got {}.",
+                    players,
+                    names.len()
+                ));
+            }
+        }
+
+        if let Some(colors) = &colors {
+            if colors.len() != players {
+                return Err(format!(
+                    "Expected a color for each of the {} players, got {}.",
+                    players,
+                    colors.len()
+                ));
+            }
+        }
+
+        if let Some(names) = names {
+            self.player_names = names;
+        }
+
+        if let Some(colors) = colors {
+            self.render_theme.player_colors = colors
+                .iter()
+                .map(|(r, g, b)| Color::Rgb {
+                    r: *r,
+                    g: *g,
+                    b: *b,
+                })
+                .collect();
+            self.player_colors = Some(colors);
+        }
+
+        self.replay_logger
+            .log_players(&self.player_names, self.player_colors.as_deref());
+
+        Ok(())
+    }
+
+    /// Starts the game after reseeding it, as if it had been created with the given seed.
+    ///
+    /// This allows running many differently seeded games with the same `Game`, instead of creating a new one each time.
+    ///
+    /// # Arguments
+    /// * `seed` - The new seed for the random number generator. Each player gets its own stream derived from it.
+    pub fn start_with_seed(&mut self, seed: u64) -> GameState {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.player_rngs = (0..self.map.players())
+            .map(|player| StdRng::seed_from_u64(player_seed(seed, player)))
+            .collect();
+        self.id_rng = StdRng::seed_from_u64(id_seed(seed));
+
+        self.start()
+    }
+
+    /// Starts the game.
+    ///
+    /// Must be called once before updating the game state.
+    pub fn start(&mut self) -> GameState {
+        self.turn = 0;
+        self.started = true;
+        self.finished = false;
+        self.finished_reason = None;
+        self.winner = None;
+        self.turns_with_too_much_food = 0;
+        self.turns_in_stalemate = 0;
+        self.corpses.clear();
+        self.last_moves.clear();
+        self.idle_turns.clear();
+        self.hive = vec![0; self.map.players()];
+        self.hill_hives.clear();
+        self.statistics = vec![PlayerStats::new(); self.map.players()];
+        self.scored_hills.clear();
+        self.skipped_players.clear();
+        self.eliminated_players.clear();
+        self.explored = match self.track_explored {
+            true => {
+                vec![vec![vec![false; self.map.width()]; self.map.height()]; self.map.players()]
+            }
+            false => vec![],
+        };
+        // The map was already parsed when the game was created
+        self.map = Map::parse(&self.map_contents).unwrap();
+        for (row, col, placement) in self.placements.clone() {
+            self.put(row, col, &placement);
+        }
+        self.assign_ant_ids();
+        self.replay_logger.clear();
+
+        // Placed entities are not part of the map contents, so the replay needs to spawn them
+        for (row, col, placement) in self.placements.clone() {
+            match placement {
+                Placement::Ant(player) => {
+                    let id = self.map.get(row, col).unwrap().id().to_string();
+                    self.replay_logger
+                        .log_spawn_ant(self.turn, id, player, (row, col));
+                }
+                Placement::Food => self.replay_logger.log_spawn_food(self.turn, (row, col)),
+                Placement::Hill(player, alive) => {
+                    self.replay_logger
+                        .log_spawn_hill(self.turn, player, (row, col));
+                    if !alive {
+                        self.replay_logger.log_remove_hill(self.turn, (row, col));
+                    }
+                }
+            }
+        }
+
+        self.compute_initial_scores();
+        self.spawn_food_around_hills();
+        self.spawn_ants_all_hills();
+
+        // Compute the intial game state
+        let live_ants = self.live_ant_locations();
+        self.explore(&live_ants);
+        let state = self.game_state(&live_ants);
+
+        self.log_turn(&live_ants, state.board_hash);
+        self.last_rewards = None;
+        if self.reward_hook.is_some() {
+            self.last_state = Some(state.clone());
+        }
+
+        state
+    }
+
+    /// Updates the game state.
+    ///
+    /// # Arguments
+    /// * `actions` - The actions to take for each ant.
+    pub fn update(&mut self, actions: Vec<Action>) -> GameState {
+        self.update_from_slice(&actions)
+    }
+
+    /// Updates the game state, returning an error instead of panicking if the game has not started or is already finished.
+    /// In Python, the error is raised as a `RuntimeError`.
+    ///
+    /// # Arguments
+    /// * `actions` - The actions to take for each ant.
+    pub fn try_update(&mut self, actions: Vec<Action>) -> Result<GameState, GameError> {
+        self.try_update_from_slice(&actions)
+    }
+
+    /// Updates the game state without taking ownership of the actions, so that callers can reuse the same buffer every turn.
+    ///
+    /// # Arguments
+    /// * `actions` - The actions to take for each ant.
+    pub fn update_from_slice(&mut self, actions: &[Action]) -> GameState {
+        self.try_update_from_slice(actions)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Fast-forwards the game by updating it with each turn's actions in order and returns the state after the last
+    /// turn played. Stops early if the game finishes, and if there are no turns to play, returns the current state.
+    ///
+    /// Panics if the game has not started.
+    ///
+    /// # Arguments
+    /// * `actions_per_turn` - The actions to take for each ant, for each turn.
+    pub fn fast_forward(&mut self, actions_per_turn: &[Vec<Action>]) -> GameState {
+        if !self.started {
+            panic!("{}", GameError::NotStarted);
+        }
+
+        let mut state = self.game_state(&self.live_ant_locations());
+        for actions in actions_per_turn {
+            if self.finished {
+                break;
+            }
+
+            state = self.update_from_slice(actions);
+        }
+
+        state
+    }
+
+    /// Updates the game state without taking ownership of the actions, returning an error instead of panicking
+    /// if the game has not started or is already finished.
+    ///
+    /// # Arguments
+    /// * `actions` - The actions to take for each ant.
+    pub fn try_update_from_slice(&mut self, actions: &[Action]) -> Result<GameState, GameError> {
+        if !self.started {
+            return Err(GameError::NotStarted);
+        }
+
+        if self.finished {
+            return Err(GameError::AlreadyFinished);
+        }
+
+        if let Some(events) = &self.turn_events {
+            events.lock().unwrap().clear();
+        }
+
+        self.turn += 1;
+        self.turn_stats = vec![TurnStats::new(self.turn); self.map.players()];
+        // Only what the ants do from now on makes them active this turn, not e.g. being spawned when the game started
+        self.active_ants.clear();
+
+        self.penalize_skipped_players();
+        self.remove_old_corpses();
+
+        // The live ants are only computed again after a phase that moves, kills or spawns ants,
+        // so that they can be shared by the phases that only read them
+        let mut live_ants = None;
+        for phase in self.phase_order.clone() {
+            match phase {
+                TurnPhase::Move => {
+                    self.move_ants(actions);
+                    live_ants = None;
+                }
+                TurnPhase::Attack => {
+                    self.attack();
+                    live_ants = None;
+                }
+                TurnPhase::Raze => {
+                    let live_ants = live_ants.get_or_insert_with(|| self.live_ant_locations());
+                    self.raze_hills(live_ants);
+                }
+                TurnPhase::SpawnAnts => {
+                    let ants_per_player = self.ants_per_player(
+                        live_ants.get_or_insert_with(|| self.live_ant_locations()),
+                    );
+                    self.spawn_ants_from_hive(&ants_per_player);
+                    live_ants = None;
+                }
+                TurnPhase::Harvest if self.carry_food => self.deliver_food(),
+                TurnPhase::Harvest => self.harvest_food(),
+                // By default, food is spawned randomly across the map instead of doing the symmetric spawning that the original Ants game used.
+                // The reason is that random food makes the game more challenging as it could lead to scenarios where agents aren't near any food.
+                // This will require better learning and handling of complex world states.
+                // Which we hope will ultimately lead to more robust agents.
+                // The original symmetric spawning is still available through `FoodSpawnStrategy::SymmetricMirror`.
+                TurnPhase::SpawnFood => self.spawn_food_randomly(),
+            }
+        }
+        self.starve_idle_ants();
+        // Skipping only applies to a single turn
+        self.skipped_players.clear();
+
+        // New ants were spawned, but from now on the live ants don't change until the next turn
+        let live_ants = self.live_ant_locations();
+        self.check_for_endgame(&live_ants);
+
+        // Compute the game state before removing dead ants so that the dead ants are included in the state
+        self.explore(&live_ants);
+        let state = self.game_state(&live_ants);
+        self.remove_dead_ants();
+
+        self.log_turn(&live_ants, state.board_hash);
+
+        // If the game finished, log the end game and save the replay
+        if self.finished {
+            self.replay_logger.log_end_game(GameResult {
+                reason: format!("{:?}", self.finished_reason.as_ref().unwrap()),
+                winner: self.winner,
+                scores: self.scores.clone(),
+                ants: self.ants_per_player(&live_ants),
+                hive: self.hive.clone(),
+                ranking: self.ranking(),
+                statistics: self.statistics.clone(),
+            });
+            self.replay_logger.save();
+        }
+
+        let mut state = state;
+        self.compute_rewards(actions, &mut state);
+
+        Ok(state)
+    }
+
+    /// Sets a hook that computes the reward of each player at the end of every turn, e.g. to train agents with
+    /// custom rewards without diffing the states. It's called with the state before the turn, the actions,
+    /// the state after the turn and the events of the turn, and its result is returned as `GameState::rewards`.
+    /// If it's set after calling `start`, the first rewards are computed from the state at the time it's set.
+    ///
+    /// # Arguments
+    /// * `hook` - The function that computes the reward of each player.
+    pub fn set_reward_hook<F>(&mut self, hook: F)
+    where
+        F: Fn(&GameState, &[Action], &GameState, &[Event]) -> Vec<f64> + Send + Sync + 'static,
+    {
+        // The events of the turn are collected by an observer, which is only registered once
+        if self.turn_events.is_none() {
+            let events = Arc::new(Mutex::new(vec![]));
+            let collected = events.clone();
+            self.add_event_observer(move |_, event| collected.lock().unwrap().push(event.clone()));
+            self.turn_events = Some(events);
+        }
+
+        self.reward_hook = Some(Box::new(hook));
+        // Otherwise `start` keeps the state the first turn starts from
+        if self.started && self.last_state.is_none() {
+            self.last_state = Some(self.game_state(&self.live_ant_locations()));
+        }
+    }
+
+    /// Returns the result of a Python call, or the exception a Python callback raised during it.
+    fn raise_callback_error<T>(&self, result: T) -> PyResult<T> {
+        match self.callback_error.lock().unwrap().take() {
+            Some(error) => Err(error),
+            None => Ok(result),
+        }
+    }
+
+    fn compute_rewards(&mut self, actions: &[Action], state: &mut GameState) {
+        let (Some(hook), Some(last_state)) = (&self.reward_hook, &self.last_state) else {
+            return;
+        };
+
+        let events = self
+            .turn_events
+            .as_ref()
+            .map(|events| std::mem::take(&mut *events.lock().unwrap()))
+            .unwrap_or_default();
+        let rewards = hook(last_state, actions, state, &events);
+
+        state.rewards = Some(rewards.clone());
+        self.last_rewards = Some(rewards);
+        self.last_state = Some(state.clone());
+    }
+
+    /// Registers an observer that is called with the turn and the event for every event in the game,
+    /// as they happen and in the same order as in the replay, e.g. to compute custom metrics or shape rewards.
+    /// Observers are called whether or not a replay is saved.
+    ///
+    /// # Arguments
+    /// * `observer` - The function to call with the turn and the event.
+    pub fn add_event_observer<F>(&mut self, observer: F)
+    where
+        F: Fn(usize, &Event) + Send + Sync + 'static,
+    {
+        // The observer wraps the current logger so that every event goes to both
+        let logger = std::mem::replace(
+            &mut self.replay_logger,
+            create_replay_logger(None, 0, 0, 0, String::new()),
+        );
+        self.replay_logger = observe_events(logger, Box::new(observer));
+    }
+
+    /// Starts the game and plays it to the end, asking the agent for the actions of every turn.
+    /// Returns the final game state.
+    ///
+    /// # Arguments
+    /// * `agent` - Returns the actions of all players given the current game state.
+    pub fn run_to_completion<F>(&mut self, mut agent: F) -> GameState
+    where
+        F: FnMut(&GameState) -> Vec<Action>,
+    {
+        let mut state = self.start();
+        while !state.finished {
+            let actions = agent(&state);
+            state = self.update_from_slice(&actions);
+        }
+
+        state
+    }
+
+    /// Sets the symbols and colors used by `draw` and `render`. Defaults to `RenderTheme::default()`.
+    ///
+    /// # Arguments
+    /// * `theme` - The theme to render the game with.
+    pub fn set_render_theme(&mut self, theme: RenderTheme) {
+        self.render_theme = theme;
+    }
+
+    fn compute_initial_scores(&mut self) {
+        // By default, each agent starts with 1 point per hill
+        let ants_hills_per_player = self.live_ant_hills_per_player();
+
+        for (player, hills) in ants_hills_per_player.iter().enumerate() {
+            self.scores[player] = match self.starting_score {
+                StartingScore::HillCount() => hills.len() as isize,
+                StartingScore::Zero() => 0,
+                StartingScore::Fixed(points) => points as isize,
+            };
+            // Maps can declare a bonus for some players on top of the starting score
+            self.scores[player] += self.map.start_bonus()[player] as isize;
+        }
+    }
+
+    fn spawn_food_around_hills(&mut self) {
+        let ant_hills = self.live_ant_hills();
+        let mut lands: Vec<(usize, usize)> = Vec::new();
+        let food_per_hill = self.starting_food_per_hill;
+
+        // For each ant hill, collect up to `food_per_hill` land cells around it
+        for (_, row, col) in ant_hills {
+            let land_around = self.map.land_around(row, col);
+
+            match self.food_spawn_strategy {
+                // With fixed food, the starting food must not depend on the random number generator either
+                FoodSpawnStrategy::Fixed(_) => {
+                    lands.extend(land_around.into_iter().take(food_per_hill))
+                }
+                FoodSpawnStrategy::SymmetricMirror() => {
+                    // If the food around this hill was already spawned as the image of another hill's food, skip it
+                    if land_around.iter().any(|land| lands.contains(land)) {
+                        continue;
+                    }
+
+                    for (i, j) in land_around.choose_multiple(&mut self.rng, food_per_hill) {
+                        for image in self.map.symmetric_images(*i, *j) {
+                            if !lands.contains(&image) && self.map.get(image.0, image.1).is_none() {
+                                lands.push(image);
+                            }
+                        }
+                    }
+                }
+                FoodSpawnStrategy::Random()
+                | FoodSpawnStrategy::FarFromHills()
+                | FoodSpawnStrategy::Spread() => lands.extend(
+                    land_around
+                        .choose_multiple(&mut self.rng, food_per_hill)
+                        .cloned(),
+                ),
+            }
+        }
+
+        // Spawn food on the chosen land cells
+        let count = lands.len();
+        self.spawn_food(lands, count);
+    }
+
+    fn spawn_food_randomly(&mut self) {
+        // Make sure to only spawn food if there is less food than the food per turn
+        let current_food = self.map.food().len();
+
+        if current_food >= self.food_per_turn {
+            return;
+        }
+
+        let food_to_spawn = self.food_per_turn - current_food;
+        // All the candidates are kept in order of preference so that occupied cells fall back to the next one
+        let candidates = match &self.food_spawn_strategy {
+            FoodSpawnStrategy::Random() => {
+                let mut land = self.map.land();
+                land.shuffle(&mut self.rng);
+                land
+            }
+            FoodSpawnStrategy::SymmetricMirror() => self.symmetric_food_locations(food_to_spawn),
+            FoodSpawnStrategy::FarFromHills() => self.far_from_hills_food_locations(),
+            FoodSpawnStrategy::Spread() => self.spread_food_locations(food_to_spawn),
+            FoodSpawnStrategy::Fixed(locations) => {
+                // A location listed more than once only counts the first time
+                let mut seen = BTreeSet::new();
+                locations
+                    .iter()
+                    .filter(|(row, col)| *row < self.map.height() && *col < self.map.width())
+                    .filter(|location| seen.insert(**location))
+                    .cloned()
+                    .collect()
+            }
+        };
+
+        // Symmetric images are spawned all at once, so they can go slightly over the food to spawn
+        let count = match self.food_spawn_strategy {
+            FoodSpawnStrategy::SymmetricMirror() => candidates.len(),
+            _ => food_to_spawn,
+        };
+        let candidates = match self.food_spawn_strategy {
+            FoodSpawnStrategy::SymmetricMirror() | FoodSpawnStrategy::Fixed(_) => candidates,
+            _ => self.away_from_ants(candidates),
+        };
+        self.spawn_food(candidates, count);
+    }
+
+    /// Reorders the food candidates so that the cells at least `min_food_ant_distance` from every live ant come first,
+    /// followed by the cells one cell closer and so on, keeping the order of the strategy within each distance.
+    fn away_from_ants(&self, mut candidates: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+        if self.min_food_ant_distance == 0 {
+            return candidates;
+        }
+
+        let ants = self.live_ant_locations();
+        // The sort is stable, so the order of the strategy is kept among the cells at the same distance
+        candidates.sort_by_key(|cell| {
+            let nearest = ants
+                .iter()
+                .map(|(_, row, col)| self.distance2(*cell, (*row, *col)))
+                .min()
+                .unwrap_or(usize::MAX);
+            // The largest distance up to the minimum the cell is at, every cell is at least at distance 0
+            let distance = (0..=self.min_food_ant_distance)
+                .rev()
+                .find(|distance| nearest >= distance.pow(2))
+                .unwrap();
+            Reverse(distance)
+        });
+
+        candidates
+    }
+
+    fn symmetric_food_locations(&mut self, food_to_spawn: usize) -> Vec<(usize, usize)> {
+        let mut land = self.map.land();
+        land.shuffle(&mut self.rng);
+
+        let mut locations: Vec<(usize, usize)> = Vec::new();
+        for (row, col) in land {
+            if locations.len() >= food_to_spawn {
+                break;
+            }
+
+            // Food is spawned on all the symmetric images of the cell at once so that every player sees equivalent food
+            // This means that slightly more food than requested can be spawned on a turn
+            let images = self.map.symmetric_images(row, col);
+
+            // Only spawn food if all images are free, otherwise the food would favor some players
+            if images
+                .iter()
+                .any(|(i, j)| locations.contains(&(*i, *j)) || self.map.get(*i, *j).is_some())
+            {
+                continue;
+            }
+
+            locations.extend(images);
+        }
+
+        locations
+    }
+
+    fn far_from_hills_food_locations(&mut self) -> Vec<(usize, usize)> {
+        let distances = self.map.hill_distances();
+        // Cells that no hill can reach are as far as it gets, so they weigh as much as the farthest reachable cell
+        let max_distance = distances
+            .iter()
+            .flatten()
+            .flatten()
+            .max()
+            .copied()
+            .unwrap_or(1);
+
+        // Weighted shuffle where each cell draws a key of `u^(1 / weight)` and the largest keys go first
+        // This keeps every cell as a fallback while cells with a higher weight are more likely to come first
+        let mut keyed: Vec<(f64, (usize, usize))> = self
+            .map
+            .land()
+            .into_iter()
+            .map(|(row, col)| {
+                let weight = distances[row][col].unwrap_or(max_distance).max(1) as f64;
+                (self.rng.gen::<f64>().powf(1.0 / weight), (row, col))
+            })
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+        keyed.into_iter().map(|(_, cell)| cell).collect()
+    }
+
+    fn spread_food_locations(&mut self, food_to_spawn: usize) -> Vec<(usize, usize)> {
+        // Square buckets sized so that there is about one bucket per food to spawn
+        let area = self.map.width() * self.map.height();
+        let size = ((area as f64 / food_to_spawn as f64).sqrt().ceil() as usize).max(1);
+        let bucket = |(row, col): (usize, usize)| (row / size, col / size);
+
+        let mut land_per_bucket: BTreeMap<(usize, usize), Vec<(usize, usize)>> = BTreeMap::new();
+        for cell in self.map.land() {
+            land_per_bucket.entry(bucket(cell)).or_default().push(cell);
+        }
+        let mut food_per_bucket: HashMap<(usize, usize), usize> = HashMap::new();
+        for cell in self.map.food() {
+            *food_per_bucket.entry(bucket(cell)).or_default() += 1;
+        }
+
+        // Buckets are visited in random order, but the ones with the least food come first
+        let mut buckets: Vec<Vec<(usize, usize)>> = land_per_bucket.into_values().collect();
+        buckets.shuffle(&mut self.rng);
+        for land in buckets.iter_mut() {
+            land.shuffle(&mut self.rng);
+        }
+        buckets.sort_by_key(|land| food_per_bucket.get(&bucket(land[0])).copied().unwrap_or(0));
+
+        // Take one cell of each bucket in turn, so that every bucket is used once before any is used again
+        let mut locations = Vec::new();
+        let longest = buckets.iter().map(Vec::len).max().unwrap_or(0);
+        for i in 0..longest {
+            locations.extend(buckets.iter().filter_map(|land| land.get(i)));
+        }
+
+        locations
+    }
+
+    /// Spawns food on the first `count` candidates that are empty and returns how much food was spawned.
+    fn spawn_food(&mut self, candidates: Vec<(usize, usize)>, count: usize) -> usize {
+        let mut spawned = 0;
+
+        for (row, col) in candidates {
+            if spawned >= count {
+                break;
+            }
+
+            // Food never overwrites another entity, so occupied cells are skipped in favor of the next candidate
+            if self.map.try_set(row, col, Box::new(Food)) {
+                self.replay_logger.log_spawn_food(self.turn, (row, col));
+                spawned += 1;
+            }
+        }
+
+        spawned
+    }
+
+    /// Places an ant of the given player on the map to set up a scenario.
+    /// Placed entities are kept every time the game is started.
+    ///
+    /// Returns an error if the game has started, the player is invalid, or the cell is out of bounds or occupied.
+    ///
+    /// # Arguments
+    /// * `player` - The player that owns the ant.
+    /// * `row` - The row of the cell.
+    /// * `col` - The column of the cell.
+    pub fn place_ant(&mut self, player: usize, row: usize, col: usize) -> Result<(), String> {
+        self.place(row, col, Placement::Ant(player))
+    }
+
+    /// Places food on the map to set up a scenario.
+    /// Placed entities are kept every time the game is started.
+    ///
+    /// Returns an error if the game has started, or the cell is out of bounds or occupied.
+    ///
+    /// # Arguments
+    /// * `row` - The row of the cell.
+    /// * `col` - The column of the cell.
+    pub fn place_food(&mut self, row: usize, col: usize) -> Result<(), String> {
+        self.place(row, col, Placement::Food)
+    }
+
+    /// Places a hill of the given player on the map to set up a scenario.
+    /// Placed entities are kept every time the game is started.
+    ///
+    /// Returns an error if the game has started, the player is invalid, or the cell is out of bounds or occupied.
+    ///
+    /// # Arguments
+    /// * `player` - The player that owns the hill.
+    /// * `row` - The row of the cell.
+    /// * `col` - The column of the cell.
+    /// * `alive` - Whether the hill is alive, i.e. not razed.
+    pub fn place_hill(
+        &mut self,
+        player: usize,
+        row: usize,
+        col: usize,
+        alive: bool,
+    ) -> Result<(), String> {
+        self.place(row, col, Placement::Hill(player, alive))
+    }
+
+    fn place(&mut self, row: usize, col: usize, placement: Placement) -> Result<(), String> {
+        if self.started {
+            return Err("Entities can only be placed before the game starts.".to_string());
+        }
+
+        if let Placement::Ant(player) | Placement::Hill(player, _) = placement {
+            if player >= self.map.players() {
+                return Err(format!(
+                    "Invalid player {}, the game only has {} players.",
+                    player,
+                    self.map.players()
+                ));
+            }
+        }
+
+        if row >= self.map.height() || col >= self.map.width() {
+            return Err(format!(
+                "The cell ({}, {}) is out of bounds, the map has {} rows and {} columns.",
+                row,
+                col,
+                self.map.height(),
+                self.map.width()
+            ));
+        }
+
+        if let Some(entity) = self.map.get(row, col) {
+            return Err(format!(
+                "The cell ({}, {}) is already occupied by {}.",
+                row,
+                col,
+                entity.name()
+            ));
+        }
+
+        self.put(row, col, &placement);
+        self.placements.push((row, col, placement));
+
+        Ok(())
+    }
+
+    fn put(&mut self, row: usize, col: usize, placement: &Placement) {
+        let entity: Box<dyn Entity> = match placement {
+            Placement::Ant(player) => Box::new(Ant::new(self.next_ant_id(), *player, true, None)),
+            Placement::Food => Box::new(Food),
+            Placement::Hill(player, alive) => Box::new(Hill::new(*player, *alive)),
+        };
+        self.map.set(row, col, entity);
+    }
+
+    /// Returns a new ant id, which is a uuid generated from the seeded id stream so that games are reproducible.
+    fn next_ant_id(&mut self) -> String {
+        Builder::from_random_bytes(self.id_rng.gen())
+            .into_uuid()
+            .to_string()
+    }
+
+    /// Replaces the ids of the ants placed on the map with ids from the seeded id stream.
+    fn assign_ant_ids(&mut self) {
+        let ants: Vec<(usize, usize, usize, Option<bool>)> = self
+            .map
+            .ants()
+            .into_iter()
+            .map(|(ant, row, col)| {
+                (
+                    ant.player().unwrap(),
+                    row,
+                    col,
+                    ant.on_ant_hill().map(|hill| hill.alive().unwrap()),
+                )
+            })
+            .collect();
+
+        for (player, row, col, hill) in ants {
+            let hill = hill.map(|alive| Box::new(Hill::new(player, alive)) as Box<dyn Entity>);
+            let ant = Ant::new(self.next_ant_id(), player, true, hill);
+            self.map.set(row, col, Box::new(ant));
+        }
+    }
+
+    fn spawn_ants_all_hills(&mut self) {
+        let ant_hills = self.live_ant_hills();
+        self.spawn_ants(ant_hills.clone());
+
+        // The remaining starting ants are spawned around each hill
+        let extra_ants = self.starting_ants_per_hill.saturating_sub(1);
+        if extra_ants == 0 {
+            return;
+        }
+
+        for (player, row, col) in ant_hills {
+            self.spawn_ants_around(player, row, col, extra_ants);
+        }
+    }
+
+    fn spawn_ants_around(&mut self, player: usize, row: usize, col: usize, count: usize) {
+        let mut spawned = 0;
+        let max_distance = self.map.width().max(self.map.height());
+
+        // Look for land in rings of increasing distance around the location until all ants are spawned
+        for distance in 1..=max_distance {
+            if spawned >= count {
+                break;
+            }
+
+            let lands: Vec<(usize, usize)> = self
+                .map
+                .land_in_ring(row, col, distance)
+                .choose_multiple(&mut self.player_rngs[player], count - spawned)
+                .cloned()
+                .collect();
+
+            spawned += self.spawn_ants_on_land(player, lands);
+        }
+    }
+
+    /// Spawns an ant for the player on each of the given land cells that is still empty and returns how many were spawned.
+    fn spawn_ants_on_land(&mut self, player: usize, lands: Vec<(usize, usize)>) -> usize {
+        let mut spawned = 0;
+
+        for (row, col) in lands {
+            let ant = Ant::new(self.next_ant_id(), player, true, None);
+            let id = ant.id().to_string();
+            if self.map.try_set(row, col, Box::new(ant)) {
+                self.mark_active(&id);
+                self.replay_logger
+                    .log_spawn_ant(self.turn, id, player, (row, col));
+                self.statistics[player].add_ants_spawned(1);
+                spawned += 1;
+            }
+        }
+
+        spawned
+    }
+
+    fn spawn_ants_from_hive(&mut self, ants_per_player: &[usize]) {
+        let players = self.map.players();
+        let hills_by_player = self.live_ant_hills_per_player();
+
+        for (player, hills) in hills_by_player.iter().enumerate().take(players) {
+            if self.eliminated_players.contains(&player) {
+                continue;
+            }
+
+            let available_food = self.hive[player];
+
+            if available_food == 0 {
+                continue;
+            }
+
+            if ants_per_player[player] >= self.max_colony_size {
+                continue;
+            }
+
+            // With a hive per hill, every hill spawns ants with its own food, without bursting past the maximum colony size
+            if self.per_hill_hive {
+                let room = self.max_colony_size - ants_per_player[player];
+                let mut spawned = 0;
+                for (player, row, col) in hills.clone() {
+                    if spawned >= room {
+                        break;
+                    }
+
+                    let Some(food) = self.hill_hives.get(&(player, row, col)).copied() else {
+                        continue;
+                    };
+
+                    let spawned_on_hill =
+                        self.spawn_ants_on_hill(player, row, col, food.min(room - spawned));
+                    self.hill_hives
+                        .insert((player, row, col), food - spawned_on_hill);
+                    spawned += spawned_on_hill;
+                }
+
+                self.hive[player] -= spawned;
+                self.turn_stats[player].add_ants_spawned(spawned);
+                continue;
+            }
+
+            // Randomly choose hills, up to the available food, to spawn ants on
+            // We do this withouth repetition to avoid spawning multiple ants on the same hill
+            // Hills with an ant on them, alive or dead, are not among them, so their ant is never overwritten
+            let ant_hills: Vec<(usize, usize, usize)> = hills
+                .choose_multiple(&mut self.player_rngs[player], available_food)
+                .cloned()
+                .collect();
+
+            // Spawn ants on the chosen hills, without bursting past the maximum colony size
+            // Without a burst, every chosen hill spawns its ant as in the original game, even past the maximum colony size
+            let budget = match self.spawn_burst {
+                1 => available_food,
+                _ => available_food.min(self.max_colony_size - ants_per_player[player]),
+            };
+            let mut spawned = 0;
+            for (player, row, col) in ant_hills {
+                if spawned >= budget {
+                    break;
+                }
+
+                spawned += self.spawn_ants_on_hill(player, row, col, budget - spawned);
+            }
+
+            // Update the hive with the remaining food, only paying for the ants that were actually spawned
+            self.hive[player] -= spawned;
+            // And update the turn stats
+            self.turn_stats[player].add_ants_spawned(spawned);
+        }
+    }
+
+    /// Spawns an ant on the hill and, with a burst, more ants on the land around it, paying one food per ant.
+    /// Returns how many ants were spawned, at most the given food, which callers cap by the room left in the colony.
+    fn spawn_ants_on_hill(&mut self, player: usize, row: usize, col: usize, food: usize) -> usize {
+        if food == 0 || self.spawn_ants(vec![(player, row, col)]) == 0 {
+            return 0;
+        }
+
+        // With a burst, the rest of the hill's ants are spawned on the land around it while there is food left
+        let burst = (self.spawn_burst - 1).min(food - 1);
+        if burst == 0 {
+            return 1;
+        }
+
+        let lands = self
+            .map
+            .land_around(row, col)
+            .choose_multiple(&mut self.player_rngs[player], burst)
+            .cloned()
+            .collect();
+        1 + self.spawn_ants_on_land(player, lands)
+    }
+
+    fn spawn_ants(&mut self, ant_hills: Vec<(usize, usize, usize)>) -> usize {
+        let mut spawned = 0;
+
+        for (player, row, col) in ant_hills {
+            // Ants can only be spawned on a hill that has nothing on it, otherwise they would overwrite another entity
+            if self
+                .map
+                .get(row, col)
+                .is_none_or(|entity| entity.name() != "Hill")
+            {
+                continue;
+            }
+
+            let id = self.next_ant_id();
+            let ant = Ant::new(
+                id.clone(),
+                player,
+                true,
+                Some(Box::new(Hill::new(player, true))),
+            );
+            self.map.set(row, col, Box::new(ant));
+            self.mark_active(&id);
+            self.replay_logger
+                .log_spawn_ant(self.turn, id, player, (row, col));
+            self.statistics[player].add_ants_spawned(1);
+            spawned += 1;
+        }
+
+        spawned
+    }
+
+    /// Records that the ant did something this turn, so it's not idle, see `set_idle_patience`.
+    fn mark_active(&mut self, id: &str) {
+        if self.idle_patience > 0 {
+            self.active_ants.insert(id.to_string());
+        }
+    }
+
+    fn starve_idle_ants(&mut self) {
+        if self.idle_patience == 0 {
+            return;
+        }
+
+        let live_ants: Vec<(String, usize, usize, usize)> = self
+            .live_ants()
+            .into_iter()
+            .map(|(ant, row, col)| (ant.id().to_string(), ant.player().unwrap(), row, col))
+            .collect();
+
+        // Only the live ants are kept, so the counts of the ants that died are dropped
+        let mut idle_turns = BTreeMap::new();
+        for (id, player, row, col) in live_ants {
+            let mut idle = self.idle_turns.get(&id).copied().unwrap_or(0);
+            if self.active_ants.contains(&id) {
+                idle = 0;
+            } else if !self.skipped_players.contains(&player) {
+                idle += 1;
+            }
+
+            // The ant eats from the hive to keep going, or starves if there is nothing left
+            if idle >= self.idle_patience {
+                if self.hive[player] > 0 {
+                    self.take_from_hive(player, (row, col), 1);
+                    idle = 0;
+                } else {
+                    self.map.get_mut(row, col).unwrap().set_alive(false);
+                    self.turn_stats[player].add_ants_lost(1);
+                    continue;
+                }
+            }
+
+            idle_turns.insert(id, idle);
+        }
+
+        self.idle_turns = idle_turns;
+    }
+
+    fn remove_dead_ants(&mut self) {
+        let dead_ants = self
+            .map
+            .ants()
+            .into_iter()
+            .filter(|(ant, _, _)| !ant.alive().unwrap())
+            .map(|(ant, row, col)| (ant.id().to_string(), ant.player().unwrap(), row, col))
+            .collect::<Vec<(String, usize, usize, usize)>>();
+
+        for (id, player, row, col) in dead_ants {
+            // Every dead ant ends up here, whether it died in battle or from a collision
+            self.statistics[player].add_ants_lost(1);
+
+            // If the ant was on a hill, replace the location with the hill, otherwise remove the ant
+            if let Some(hill) = self.map.get(row, col).unwrap().on_ant_hill() {
+                self.map.set(
+                    row,
+                    col,
+                    Box::new(Hill::new(hill.player().unwrap(), hill.alive().unwrap())),
+                );
+            } else if self.corpse_lifetime > 0 {
+                self.map.set(row, col, Box::new(Corpse::new(player)));
+                self.corpses.insert((row, col), self.turn);
+            } else {
+                self.map.remove(row, col);
+            }
+
+            self.replay_logger.log_remove_ant(self.turn, id, (row, col));
+        }
+    }
+
+    fn remove_old_corpses(&mut self) {
+        let lifetime = self.corpse_lifetime;
+        let turn = self.turn;
+        let expired = self
+            .corpses
+            .iter()
+            .filter(|(_, died)| turn - **died > lifetime)
+            .map(|(location, _)| *location)
+            .collect::<Vec<(usize, usize)>>();
+
+        for (row, col) in expired {
+            self.corpses.remove(&(row, col));
+
+            // The corpse could have been walked over by an ant since then
+            if self
+                .map
+                .get(row, col)
+                .is_some_and(|entity| entity.name() == "Corpse")
+            {
+                self.map.remove(row, col);
+            }
+        }
+    }
+
+    fn penalize_skipped_players(&mut self) {
+        for player in self.skipped_players.clone() {
+            self.scores[player] = self.subtract_points(self.scores[player], self.skip_penalty);
+        }
+    }
+
+    fn move_ants(&mut self, actions: &[Action]) {
+        self.last_moves.clear();
+
+        for action in actions {
+            // The ants of players that skipped this turn hold their position
+            let player = self
+                .map
+                .get(action.row, action.col)
+                .and_then(|entity| entity.player());
+            if player.is_some_and(|player| self.skipped_players.contains(&player)) {
+                continue;
+            }
+
+            if action.dig {
+                self.dig_water(action);
+                continue;
+            }
+
+            let id = self
+                .map
+                .get(action.row, action.col)
+                .unwrap()
+                .id()
+                .to_string();
+
+            // Each step is a regular single cell move, which is logged on its own
+            let mut from = (action.row, action.col);
+            let mut moved = false;
+            for _ in 0..action.steps {
+                let to = self.map.step(from, &action.direction);
+
+                if self.carry_food {
+                    self.pick_up_food(from, to);
+                } else if self.step_to_harvest {
+                    self.harvest_food_at_destination(from, to);
+                }
+
+                if !self.map.move_entity(from, to) {
+                    if self.log_blocked_moves {
+                        let reason = self.map.blocked_move_reason(from, to).unwrap();
+                        self.replay_logger.log_blocked_move(
+                            self.turn,
+                            id.clone(),
+                            from,
+                            to,
+                            reason,
+                        );
+                    }
+                    break;
+                }
+                self.replay_logger
+                    .log_move_ant(self.turn, id.clone(), from, to);
+                moved = true;
+
+                // After a collision the ant stays where it was, dead, so it can't move any further
+                if self
+                    .map
+                    .get(to.0, to.1)
+                    .is_none_or(|entity| entity.id() != id)
+                {
+                    break;
+                }
+                from = to;
+            }
+
+            if moved {
+                self.mark_active(&id);
+            }
+            self.last_moves
+                .insert(id, (action.direction.clone(), moved));
+        }
+    }
+
+    fn dig_water(&mut self, action: &Action) {
+        let Some(cost) = self.dig_cost else {
+            return;
+        };
+
+        let player = self
+            .map
+            .get(action.row, action.col)
+            .filter(|entity| entity.name() == "Ant" && entity.alive().unwrap())
+            .and_then(|ant| ant.player());
+        let Some(player) = player else {
+            return;
+        };
+
+        // Stepping off the top or left edge of a bounded map stays on the ant, which is never water
+        let (row, col) = self.map.step((action.row, action.col), &action.direction);
+        let is_water = row < self.map.height()
+            && col < self.map.width()
+            && self
+                .map
+                .get(row, col)
+                .is_some_and(|entity| entity.name() == "Water");
+        if !is_water || self.hive[player] < cost {
+            return;
+        }
+
+        self.take_from_hive(player, (action.row, action.col), cost);
+        self.map.remove(row, col);
+        self.replay_logger.log_remove_water(self.turn, (row, col));
+    }
+
+    fn harvest_food_at_destination(&mut self, from: (usize, usize), to: (usize, usize)) {
+        if from == to {
+            return;
+        }
+
+        let is_food = self
+            .map
+            .get(to.0, to.1)
+            .is_some_and(|entity| entity.name() == "Food");
+        let harvester = self
+            .map
+            .get(from.0, from.1)
+            .filter(|entity| entity.name() == "Ant" && entity.alive().unwrap())
+            .map(|ant| (ant.player().unwrap(), ant.id().to_string()));
+
+        if let (true, Some((player, id))) = (is_food, harvester) {
+            // Clear the food so that the ant can move onto its cell
+            self.map.remove(to.0, to.1);
+            self.mark_active(&id);
+            self.replay_logger
+                .log_harvest_food(self.turn, to, player, id);
+            self.store_harvested_food(player, from);
+        }
+    }
+
+    fn pick_up_food(&mut self, from: (usize, usize), to: (usize, usize)) {
+        if from == to {
+            return;
+        }
+
+        let is_food = self
+            .map
+            .get(to.0, to.1)
+            .is_some_and(|entity| entity.name() == "Food");
+        let carrier = self
+            .map
+            .get(from.0, from.1)
+            .filter(|entity| {
+                entity.name() == "Ant" && entity.alive().unwrap() && !entity.carrying().unwrap()
+            })
+            .map(|ant| ant.id().to_string());
+
+        if let (true, Some(id)) = (is_food, carrier) {
+            // Clear the food so that the ant can move onto its cell, it's only stored once the ant delivers it
+            self.map.remove(to.0, to.1);
+            self.mark_active(&id);
+            self.replay_logger.log_remove_food(self.turn, to, Some(id));
+            self.map.get_mut(from.0, from.1).unwrap().set_carrying(true);
+        }
+    }
+
+    fn deliver_food(&mut self) {
+        let deliveries: Vec<(String, usize, usize, usize)> = self
+            .live_ants()
+            .into_iter()
+            .filter(|(ant, _, _)| {
+                ant.carrying().unwrap()
+                    && ant
+                        .on_ant_hill()
+                        .is_some_and(|hill| hill.player() == ant.player() && hill.alive().unwrap())
+            })
+            .map(|(ant, row, col)| (ant.id().to_string(), ant.player().unwrap(), row, col))
+            .collect();
+
+        for (id, player, row, col) in deliveries {
+            self.mark_active(&id);
+            self.map.get_mut(row, col).unwrap().set_carrying(false);
+            self.store_harvested_food(player, (row, col));
+        }
+    }
+
+    fn attack(&mut self) {
+        let (to_kill, attack_logs) = self.resolve_battles(true);
+
+        // After all battles are resolved, kill the ants
+        for (player, row, col) in to_kill {
+            self.map.get_mut(row, col).unwrap().set_alive(false);
+            self.turn_stats[player].add_ants_lost(1);
+        }
+
+        // Log all attack events
+        for (enemy_player, enemy_id, enemy_pos, ant_id, ant_pos) in attack_logs {
+            self.mark_active(&enemy_id);
+            self.replay_logger
+                .log_attack(self.turn, enemy_id, enemy_pos, ant_id, ant_pos);
+            self.turn_stats[enemy_player].add_ants_killed(1);
+            self.statistics[enemy_player].add_ants_killed(1);
+        }
+    }
+
+    /// Returns the ants to kill as `(player, row, col)` and the attacks that killed them as
+    /// `(enemy player, enemy id, enemy location, ant id, ant location)`.
+    /// Battles only read the board, so they can be resolved in parallel and the result is the same as resolving them serially.
+    #[allow(clippy::type_complexity)]
+    fn resolve_battles(
+        &self,
+        parallel: bool,
+    ) -> (
+        Vec<(usize, usize, usize)>,
+        Vec<(usize, String, (usize, usize), String, (usize, usize))>,
+    ) {
+        // Pre-calculate the number of enemies for each live ant as a map of ant location to the Vec of enemies
+        // Keying by location, rather than by id, keeps any iteration over the map in board order
+        let ants = self.live_ants();
+        let find_enemies = |(ant, row, col): &(&dyn Entity, usize, usize)| {
+            let fov = self.map.field_of_vision(
+                (*row, *col),
+                self.attack_radius2,
+                &self.distance_metric,
+                false,
+            );
+            let enemies = self.enemies(fov, ant.player().unwrap());
+            ((*row, *col), enemies)
+        };
+        let enemies: BTreeMap<(usize, usize), Vec<(&dyn Entity, usize, usize)>> = match parallel {
+            true => ants.par_iter().map(find_enemies).collect(),
+            false => ants.iter().map(find_enemies).collect(),
+        };
+
+        // Determine which ants to kill
+        let battle = |(ant, row, col): &(&dyn Entity, usize, usize)| {
+            let ant_enemies = enemies.get(&(*row, *col)).unwrap();
+            let focus = ant_enemies.len();
+
+            if focus == 0 {
+                return None;
+            }
+
+            // Find the enemy with the most attention power, i.e. the enemy with the least other ants focused on it
+            let min_enemy_focus = ant_enemies
+                .iter()
+                .map(|(_, enemy_row, enemy_col)| {
+                    enemies.get(&(*enemy_row, *enemy_col)).unwrap().len()
+                })
+                .min()
+                .unwrap();
+
+            // Ant dies if its focused on more or equal enemies than its enemy with the most attention power
+            if focus < min_enemy_focus {
+                return None;
+            }
+
+            // Unless it's defending its own live hill
+            let is_defending_hill = ant
+                .on_ant_hill()
+                .is_some_and(|hill| hill.player() == ant.player() && hill.alive().unwrap());
+            if self.hill_defense && is_defending_hill {
+                return None;
+            }
+
+            // Collect attack log from each enemy to the ant
+            let attack_logs: Vec<(usize, String, (usize, usize), String, (usize, usize))> =
+                ant_enemies
+                    .iter()
+                    .map(|(ant_enemy, enemy_row, enemy_col)| {
+                        (
+                            ant_enemy.player().unwrap(),
+                            ant_enemy.id().to_string(),
+                            (*enemy_row, *enemy_col),
+                            ant.id().to_string(),
+                            (*row, *col),
+                        )
+                    })
+                    .collect();
+
+            Some(((ant.player().unwrap(), *row, *col), attack_logs))
+        };
+        // Collecting keeps the order of the ants either way, so the outcome doesn't depend on the scheduling
+        let battles: Vec<_> = match parallel {
+            true => ants.par_iter().filter_map(battle).collect(),
+            false => ants.iter().filter_map(battle).collect(),
+        };
+
+        let mut to_kill = Vec::with_capacity(battles.len());
+        let mut attack_logs = Vec::new();
+        for (ant, logs) in battles {
+            to_kill.push(ant);
+            attack_logs.extend(logs);
+        }
+
+        (to_kill, attack_logs)
+    }
+
+    fn raze_hills(&mut self, live_ants: &[(usize, usize, usize)]) {
+        let hills_to_raze: Vec<(usize, usize, usize, usize)> = live_ants
+            .iter()
+            .map(|(_, row, col)| (self.map.get(*row, *col).unwrap(), *row, *col))
+            .filter_map(|(ant, row, col)| {
+                // If the ant is on a live ant hill that is not its own, the hill should be razed
+                // Unless it was already razed while keeping it alive
+                if ant.on_ant_hill().is_some()
+                    && ant.on_ant_hill().unwrap().alive().unwrap()
+                    && !self.scored_hills.contains(&(row, col))
+                    && ant.player().unwrap()
+                        != ant.on_ant_hill().as_ref().unwrap().player().unwrap()
+                {
+                    let hill_owner = ant.on_ant_hill().as_ref().unwrap().player().unwrap();
+                    let player = ant.player().unwrap();
+                    Some((hill_owner, player, row, col))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        for (hill_owner, player, row, col) in hills_to_raze {
+            // Add the points for razing the hill to the player's score
+            self.scores[player] += self.points_for_razing_hill as isize;
+            // Subtract the points for losing the hill from the hill owner's score
+            self.scores[hill_owner] =
+                self.subtract_points(self.scores[hill_owner], self.points_for_losing_hill);
+            // Update the turn stats for both players
+            self.turn_stats[player].add_hills_razed(1);
+            self.turn_stats[hill_owner].add_hills_lost(1);
+            self.statistics[player].add_hills_razed(1);
+            self.statistics[hill_owner].add_hills_lost(1);
+            // Update the hill to be razed
+            match self.hill_raze_mode {
+                HillRazeMode::Disable => {
+                    self.map
+                        .get_mut(row, col)
+                        .unwrap()
+                        .set_on_ant_hill(Box::new(Hill::new(hill_owner, false)));
+                }
+                HillRazeMode::Remove => {
+                    // Replace the ant with the same ant without the hill under it
+                    // Both `remove_dead_ants` and moving the ant then leave land behind and hives can no longer spawn on it
+                    let entity = self.map.get(row, col).unwrap();
+                    let mut ant = Ant::new(
+                        entity.id().to_string(),
+                        player,
+                        entity.alive().unwrap(),
+                        None,
+                    );
+                    ant.set_carrying(entity.carrying().unwrap());
+                    self.map.set(row, col, Box::new(ant));
+                }
+                HillRazeMode::ScoreOnly => {
+                    self.scored_hills.insert((row, col));
+                }
+            }
+            self.replay_logger.log_remove_hill(self.turn, (row, col));
+            self.rebank_razed_hill_food(hill_owner, (row, col));
+        }
+    }
+
+    /// Moves the food banked at a razed hill to the player's nearest live hill, so that it can still spawn ants.
+    /// It stays at the razed hill if the player has no live hill left, where digging and idle ants can still eat it.
+    fn rebank_razed_hill_food(&mut self, player: usize, location: (usize, usize)) {
+        // A hill razed while keeping it alive still spawns ants
+        if !self.per_hill_hive || self.hill_raze_mode == HillRazeMode::ScoreOnly {
+            return;
+        }
+
+        let Some(hill) = self.nearest_live_hill(player, location) else {
+            return;
+        };
+        if let Some(food) = self.hill_hives.remove(&(player, location.0, location.1)) {
+            *self.hill_hives.entry(hill).or_default() += food;
+        }
+    }
+
+    /// Stores one food harvested by the player's ant at the given location.
+    fn store_harvested_food(&mut self, player: usize, location: (usize, usize)) {
+        // The food counts as harvested, but it's only stored if the hive isn't full
+        if self
+            .max_hive
+            .is_none_or(|max_hive| self.hive[player] < max_hive)
+        {
+            if !self.per_hill_hive {
+                self.hive[player] += 1;
+            } else if let Some(hill) = self.nearest_live_hill(player, location) {
+                // Without a live hill, there is nowhere to bank the food
+                *self.hill_hives.entry(hill).or_default() += 1;
+                self.hive[player] += 1;
+            }
+        }
+        self.turn_stats[player].add_food_harvested(1);
+        self.statistics[player].add_food_harvested(1);
+    }
+
+    /// Takes the given amount of food from the player's hive, which must have at least that much.
+    /// With a hive per hill, the food is taken from the hills nearest to the given location first.
+    fn take_from_hive(&mut self, player: usize, location: (usize, usize), amount: usize) {
+        self.hive[player] -= amount;
+        if !self.per_hill_hive {
+            return;
+        }
+
+        let mut hills: Vec<(usize, usize, usize)> = self
+            .hill_hives
+            .keys()
+            .filter(|(owner, _, _)| *owner == player)
+            .copied()
+            .collect();
+        hills.sort_by_key(|(_, row, col)| self.distance2(location, (*row, *col)));
+
+        let mut left = amount;
+        for hill in hills {
+            let banked = self.hill_hives.get_mut(&hill).unwrap();
+            let taken = left.min(*banked);
+            *banked -= taken;
+            left -= taken;
+        }
+    }
+
+    /// Returns the player's live hill nearest to the given location, the topmost and then leftmost one on ties.
+    fn nearest_live_hill(
+        &self,
+        player: usize,
+        location: (usize, usize),
+    ) -> Option<(usize, usize, usize)> {
+        self.live_hills(player)
+            .into_iter()
+            .min_by_key(|(_, row, col)| (self.distance2(location, (*row, *col)), *row, *col))
+    }
+
+    /// Returns the player's live hills, including the ones with an ant on them.
+    fn live_hills(&self, player: usize) -> Vec<(usize, usize, usize)> {
+        self.map
+            .entities()
+            .into_iter()
+            .filter(|(entity, _, _)| {
+                let hill = match entity.name() {
+                    "Hill" => Some(*entity),
+                    _ => entity.on_ant_hill(),
+                };
+                hill.is_some_and(|hill| hill.player() == Some(player) && hill.alive().unwrap())
+            })
+            .map(|(_, row, col)| (player, row, col))
+            .collect()
+    }
+
+    fn distance2(&self, from: (usize, usize), to: (usize, usize)) -> usize {
+        let (rows, cols) = self.map.abs_diff(from, to);
+        self.distance_metric.distance2(rows, cols)
+    }
+
+    /// Returns the radius **squared** and the distance metric used to find the ants that can harvest food.
+    /// Ants can never stand on food, so a radius of `0` would never match anything and instead means the ant must be
+    /// right next to the food, regardless of the metric used for the rest of the game.
+    fn harvest_range(&self) -> (usize, DistanceMetric) {
+        match self.food_radius2 {
+            0 => (1, DistanceMetric::Manhattan),
+            radius2 => (radius2, self.distance_metric.clone()),
+        }
+    }
+
+    fn harvest_food(&mut self) {
+        let food = self.map.food();
+        let (food_radius2, metric) = self.harvest_range();
+        let mut food_harvested_per_ant: HashMap<(usize, usize), usize> = HashMap::new();
+
+        for (row, col) in food {
+            let mut ants_around_food: Vec<(usize, usize, usize, String)> = self
+                .map
+                .field_of_vision((row, col), food_radius2, &metric, false)
+                .into_iter()
+                .filter(|(entity, _, _)| entity.name() == "Ant")
+                .map(|(entity, row, col)| {
+                    (row, col, entity.player().unwrap(), entity.id().to_string())
+                })
+                .collect();
+
+            if ants_around_food.is_empty() {
+                continue;
+            }
+
+            // When several ants could harvest the food, the one with the lowest `(row, col)` gets the credit,
+            // i.e. the topmost ant and, among those, the leftmost one
+            ants_around_food.sort_by_key(|(row, col, _, _)| (*row, *col));
+
+            // Count the ants of each player around the food
+            let mut ants_per_player: BTreeMap<usize, usize> = BTreeMap::new();
+            for (_, _, player, _) in &ants_around_food {
+                *ants_per_player.entry(*player).or_default() += 1;
+            }
+
+            // If there is only one player around the food, they consume it into their hive
+            // With majority contested food, the player with strictly more ants around it consumes it instead
+            // Otherwise, it's simply removed from the map without being consumed by anyone
+            let harvesting_player = match (ants_per_player.len(), &self.contested_food) {
+                (1, _) => ants_per_player.keys().next().copied(),
+                (_, ContestedFood::Majority) => {
+                    let most_ants = ants_per_player.values().max().copied().unwrap();
+                    let mut majority = ants_per_player
+                        .iter()
+                        .filter(|(_, ants)| **ants == most_ants)
+                        .map(|(player, _)| *player);
+
+                    match (majority.next(), majority.next()) {
+                        (Some(player), None) => Some(player),
+                        _ => None,
+                    }
+                }
+                (_, ContestedFood::Destroy) => None,
+            };
+
+            let mut harvester = None;
+            if let Some(harvesting_player) = harvesting_player {
+                // But first, check if the ants around the food already harvested as much as they can this turn
+                for (row, col, player, id) in &ants_around_food {
+                    let harvested = food_harvested_per_ant.entry((*row, *col)).or_default();
+                    if *player != harvesting_player || *harvested >= self.max_food_per_ant_per_turn
+                    {
+                        continue;
+                    }
+
+                    self.store_harvested_food(*player, (*row, *col));
+                    *harvested += 1;
+                    harvester = Some(id.clone());
+                    self.mark_active(id);
+                    break;
+                }
+
+                // No ants of the harvesting player could harvest the food,
+                // but since they have the food surrounded, we don't remove it
+                if harvester.is_none() {
+                    continue;
+                }
+            }
+
+            self.map.remove(row, col);
+            match (harvesting_player, harvester) {
+                (Some(player), Some(id)) => {
+                    self.replay_logger
+                        .log_harvest_food(self.turn, (row, col), player, id)
+                }
+                // Contested food is destroyed without being stored in any hive
+                _ => self
+                    .replay_logger
+                    .log_remove_food(self.turn, (row, col), None),
+            }
+        }
+    }
+
+    fn live_ant_hills_per_player(&self) -> Vec<Vec<(usize, usize, usize)>> {
+        let players = self.map.players();
+        self.live_ant_hills()
+            .into_iter()
+            // Group hills by player
+            .fold(vec![vec![]; players], |mut acc, hill| {
+                acc[hill.0].push(hill);
+                acc
+            })
+    }
+
+    fn live_ant_hills(&self) -> Vec<(usize, usize, usize)> {
+        self.map
+            .ant_hills()
+            .into_iter()
+            .filter(|(hill, _, _)| hill.alive().unwrap())
+            .map(|(hill, row, col)| (hill.player().unwrap(), row, col))
+            .collect()
+    }
+
+    fn live_ants_per_player_count(&self) -> Vec<usize> {
+        self.ants_per_player(&self.live_ant_locations())
+    }
+
+    fn log_turn(&mut self, live_ants: &[(usize, usize, usize)], board_hash: Option<u64>) {
+        // Counting the ants is only worth it when the logger keeps them
+        let ants = match self.replay_logger.records_turns() {
+            true => self.ants_per_player(live_ants),
+            false => vec![],
+        };
+        self.replay_logger
+            .log_turn(self.turn, &ants, &self.hive, &self.scores, board_hash);
+    }
+
+    fn ants_per_player(&self, live_ants: &[(usize, usize, usize)]) -> Vec<usize> {
+        live_ants
+            .iter()
+            .fold(vec![0; self.map.players()], |mut acc, (player, _, _)| {
+                acc[*player] += 1;
+                acc
+            })
+    }
+
+    /// Returns the player and location of each live ant as `(player, row, col)`.
+    /// Unlike `live_ants`, it doesn't borrow the map so it can be computed once and shared by the phases of a turn.
+    fn live_ant_locations(&self) -> Vec<(usize, usize, usize)> {
+        self.live_ants()
+            .into_iter()
+            .map(|(ant, row, col)| (ant.player().unwrap(), row, col))
+            .collect()
+    }
+
+    fn live_ants(&self) -> Vec<(&dyn Entity, usize, usize)> {
+        self.map
+            .ants()
+            .into_iter()
+            .filter(|(ant, _, _)| ant.alive().unwrap())
+            .collect()
+    }
+
+    fn enemies<'a>(
+        &'a self,
+        field_of_vision: Vec<(&'a dyn Entity, usize, usize)>,
+        player: usize,
+    ) -> Vec<(&'a dyn Entity, usize, usize)> {
+        field_of_vision
+            .into_iter()
+            .filter(|(entity, _, _)| {
+                entity.name() == "Ant"
+                    && entity.alive().is_some()
+                    && entity.alive().unwrap()
+                    && entity.player().is_some()
+                    && entity.player().unwrap() != player
+            })
+            .collect()
+    }
+
+    /// Marks the cells within the field of vision of the live ants as explored by their player, if tracking is enabled.
+    fn explore(&mut self, live_ants: &[(usize, usize, usize)]) {
+        if !self.track_explored {
+            return;
+        }
+
+        // Tracking can be enabled after the game started
+        if self.explored.is_empty() {
+            self.explored =
+                vec![vec![vec![false; self.map.width()]; self.map.height()]; self.map.players()];
+        }
+
+        for (player, row, col) in live_ants {
+            for (i, j) in self.map.cells_within(
+                (*row, *col),
+                self.fov_radius2[*player],
+                &self.distance_metric,
+                self.line_of_sight,
+            ) {
+                self.explored[*player][i][j] = true;
+            }
+        }
+    }
+
+    fn game_state(&self, live_ants: &[(usize, usize, usize)]) -> GameState {
+        let players = self.map.players();
+        let ants = live_ants
+            .iter()
+            .map(|(_, row, col)| self.to_player_ant(self.map.get(*row, *col).unwrap(), *row, *col))
+            // Group ants by player
+            .fold(vec![vec![]; players], |mut acc, ant| {
+                acc[ant.player].push(ant);
+                acc
+            });
+
+        GameState {
+            turn: self.turn,
+            max_turns: self.max_turns,
+            scores: self.scores.clone(),
+            ants,
+            hive: self.hive.clone(),
+            total_food: self.food_on_map(),
+            turn_stats: self.turn_stats.clone(),
+            finished: self.finished,
+            finished_reason: self.finished_reason.clone(),
+            winner: self.winner,
+            explored: self.explored.clone(),
+            rewards: None,
+            board_hash: self.track_board_hash.then(|| self.board_hash()),
+            board: None,
+        }
+    }
+
+    fn to_player_ant(&self, ant: &dyn Entity, row: usize, col: usize) -> PlayerAnt {
+        let last_move = self.last_moves.get(ant.id());
+        PlayerAnt {
+            id: ant.id().to_string(),
+            row,
+            col,
+            player: ant.player().unwrap(),
+            alive: ant.alive().unwrap(),
+            field_of_vision: self
+                .map
+                .field_of_vision(
+                    (row, col),
+                    self.fov_radius2[ant.player().unwrap()],
+                    &self.distance_metric,
+                    self.line_of_sight,
+                )
+                .into_iter()
+                .map(|(entity, row, col)| {
+                    self.to_visible_entity(entity, row, col, ant.player().unwrap())
+                })
+                .collect(),
+            visible_cells: self.map.cells_within(
+                (row, col),
+                self.fov_radius2[ant.player().unwrap()],
+                &self.distance_metric,
+                self.line_of_sight,
+            ),
+            last_direction: last_move.map(|(direction, _)| direction.clone()),
+            last_move_succeeded: last_move.is_some_and(|(_, moved)| *moved),
+            owner_score: self.scores[ant.player().unwrap()],
+            owner_hive: self.hive[ant.player().unwrap()],
+            carrying: ant.carrying().unwrap(),
+            idle_turns: self.idle_turns.get(ant.id()).copied().unwrap_or(0),
+        }
+    }
+
+    fn to_state_entity(&self, entity: &dyn Entity, row: usize, col: usize) -> StateEntity {
+        StateEntity {
+            name: entity.name().to_string(),
+            row,
+            col,
+            player: entity.player(),
+            alive: entity.alive(),
+            razed: match entity.name() {
+                "Hill" => entity.alive().map(|alive| !alive),
+                _ => None,
+            },
+        }
+    }
+
+    /// Returns the entity as seen by the given player, which hides the owner of enemy hills unless they are revealed.
+    fn to_visible_entity(
+        &self,
+        entity: &dyn Entity,
+        row: usize,
+        col: usize,
+        viewer: usize,
+    ) -> StateEntity {
+        let mut state_entity = self.to_state_entity(entity, row, col);
+        if !self.reveal_hill_owner
+            && state_entity.name == "Hill"
+            && state_entity.player != Some(viewer)
+        {
+            state_entity.player = None;
+        }
+
+        state_entity
+    }
+
+    fn check_for_endgame(&mut self, live_ants: &[(usize, usize, usize)]) {
+        self.check_for_food_not_being_gathered(live_ants);
+        self.check_for_stalemate(live_ants);
+
+        if self.turns_with_too_much_food >= self.cutoff_threshold {
+            self.finished = true;
+            self.finished_reason = Some(FinishedReason::TooMuchFood);
+            self.winner = self.leader();
+
+            return;
+        }
+
+        let remaining_players = self.remaining_players(live_ants);
+        if remaining_players.len() == 1 {
+            self.finished = true;
+            self.finished_reason = Some(FinishedReason::LoneSurvivor);
+            self.winner = self.leader();
+
+            return;
+        }
+
+        if self.rank_stabilized() {
+            self.finished = true;
+            self.finished_reason = Some(FinishedReason::RankStabilized);
+            self.winner = self.leader();
+
+            return;
+        }
+
+        if self
+            .stalemate_patience
+            .is_some_and(|patience| self.turns_in_stalemate >= patience)
+        {
+            self.finished = true;
+            self.finished_reason = Some(FinishedReason::Stalemate);
+            self.winner = self.leader();
+
+            return;
+        }
+
+        if self.turn >= self.max_turns {
+            self.finished = true;
+            self.finished_reason = Some(FinishedReason::TurnLimitReached);
+            self.winner = self.leader();
+        }
+    }
+
+    /// Returns the player first in the `ranking`, or `None` if they are tied with the second player
+    /// on score, live ants and live hills, i.e. when there is no unambiguous leader.
+    fn leader(&self) -> Option<usize> {
+        match self.current_standings().as_slice() {
+            [(_, score, ants, hills), (_, second_score, second_ants, second_hills), ..]
+                if (score, ants, hills) == (second_score, second_ants, second_hills) =>
+            {
+                None
+            }
+            [(leader, _, _, _), ..] => Some(*leader),
+            [] => None,
+        }
+    }
+
+    fn check_for_food_not_being_gathered(&mut self, live_ants: &[(usize, usize, usize)]) {
+        let total_food = match self.reachable_food_only {
+            true => self.reachable_food(live_ants),
+            false => self.map.food().len(),
+        };
+        let total_ants = self.map.ants().len();
+        let food_pct = total_food as f64 / (total_food + total_ants) as f64;
+
+        // If the food is 85% or more of the count of ants and food then the food is not being gathered properly
+        if food_pct >= 0.85 {
+            self.turns_with_too_much_food += 1;
+        } else {
+            // Reset the count if the food is being gathered properly
+            self.turns_with_too_much_food = 0;
+        }
+    }
+
+    /// Returns the amount of food in the same region as some live ant, i.e. the food an ant can walk to.
+    fn reachable_food(&self, live_ants: &[(usize, usize, usize)]) -> usize {
+        let regions = self.map.regions();
+        let ant_regions: BTreeSet<usize> = live_ants
+            .iter()
+            .filter_map(|(_, row, col)| regions[*row][*col])
+            .collect();
+
+        self.map
+            .food()
+            .iter()
+            .filter(|(row, col)| {
+                regions[*row][*col].is_some_and(|region| ant_regions.contains(&region))
+            })
+            .count()
+    }
+
+    fn check_for_stalemate(&mut self, live_ants: &[(usize, usize, usize)]) {
+        // The regions are only computed when stalemates are detected, since it's a search over the whole map
+        if self.stalemate_patience.is_none() {
+            return;
+        }
+
+        if self.is_stalemate(live_ants) {
+            self.turns_in_stalemate += 1;
+        } else {
+            // Reset the count as soon as anyone can harvest or fight again
+            self.turns_in_stalemate = 0;
+        }
+    }
+
+    fn is_stalemate(&self, live_ants: &[(usize, usize, usize)]) -> bool {
+        let regions = self.map.regions();
+
+        // Collect the players with ants or live hills in each region
+        let mut players_per_region: BTreeMap<usize, BTreeSet<usize>> = BTreeMap::new();
+        for (player, row, col) in live_ants.iter().chain(self.live_ant_hills().iter()) {
+            if let Some(region) = regions[*row][*col] {
+                players_per_region
+                    .entry(region)
+                    .or_default()
+                    .insert(*player);
+            }
+        }
+
+        // Enemies in the same region can still reach each other to fight or raze hills
+        if players_per_region.values().any(|players| players.len() > 1) {
+            return false;
+        }
+
+        // Ants in the same region as some food can still harvest it
+        let ant_regions: BTreeSet<usize> = live_ants
+            .iter()
+            .filter_map(|(_, row, col)| regions[*row][*col])
+            .collect();
+
+        !self.map.food().iter().any(|(row, col)| {
+            regions[*row][*col].is_some_and(|region| ant_regions.contains(&region))
+        })
+    }
+
+    fn remaining_players(&self, live_ants: &[(usize, usize, usize)]) -> BTreeSet<usize> {
+        live_ants
+            .iter()
+            .map(|(player, _, _)| *player)
+            .filter(|player| !self.eliminated_players.contains(player))
+            .collect::<BTreeSet<usize>>()
+    }
+
+    fn subtract_points(&self, score: isize, points: usize) -> isize {
+        let score = score - points as isize;
+
+        // Unless signed scores are enabled, scores never go below 0
+        match self.signed_scores {
+            true => score,
+            false => score.max(0),
+        }
+    }
+
+    fn rank_stabilized(&self) -> bool {
+        let live_ant_hills_per_player = self.live_ant_hills_per_player();
+        let current_scores = &self.scores;
+        let standings = self.current_standings();
+
+        // If all players are tied, the rank isn't stabilized yet
+        if current_scores
+            .iter()
+            .all(|score| *score == current_scores[0])
+        {
+            return false;
+        }
+
+        // Get the player that is in the lead
+        let (leader, leader_score, _, _) = standings[0];
+
+        // For each other player, compute their score as if they were to raze all other hills
+        for player in 0..self.map.players() {
+            if player == leader {
+                continue;
+            }
+
+            let mut scores = current_scores.clone();
+            for (other_player, hills) in live_ant_hills_per_player.iter().enumerate() {
+                if other_player == player {
+                    continue;
+                }
 
                 // Add to the score as if the player razed all hills from the other player
-                scores[player] += hills.len() * self.points_for_razing_hill;
+                scores[player] += (hills.len() * self.points_for_razing_hill) as isize;
                 // Subtract from the score as if the other player lost all their hills
-                scores[other_player] -= hills.len() * self.points_for_losing_hill;
+                scores[other_player] = self.subtract_points(
+                    scores[other_player],
+                    hills.len() * self.points_for_losing_hill,
+                );
+            }
+
+            // If this player can surpass the leader, the rank isn't stabilized yet
+            if scores[player] > leader_score {
+                return false;
+            }
+        }
+
+        // If no player can surpass the leader, the rank is stabilized
+        true
+    }
+}
+
+/// Returns a stable hash of an entity at the given location, which only depends on its position and state.
+/// It's FNV-1a, which unlike the standard library hasher is guaranteed to be the same across builds and platforms,
+/// followed by a finalizer that spreads the bits so that the hashes of different entities don't cancel out when summed.
+fn entity_hash(entity: &dyn Entity, row: usize, col: usize) -> u64 {
+    let flag = |value: Option<bool>| value.map_or(2, |value| value as u8);
+    let hill = entity.on_ant_hill();
+
+    let mut bytes = entity.name().as_bytes().to_vec();
+    bytes.push(0);
+    bytes.extend((row as u64).to_le_bytes());
+    bytes.extend((col as u64).to_le_bytes());
+    bytes.extend(
+        entity
+            .player()
+            .map_or(u64::MAX, |player| player as u64)
+            .to_le_bytes(),
+    );
+    bytes.push(flag(entity.alive()));
+    bytes.push(flag(entity.carrying()));
+    bytes.extend(
+        hill.and_then(|hill| hill.player())
+            .map_or(u64::MAX, |player| player as u64)
+            .to_le_bytes(),
+    );
+    bytes.push(flag(hill.and_then(|hill| hill.alive())));
+
+    let mut hash = bytes.iter().fold(0xCBF2_9CE4_8422_2325u64, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01B3)
+    });
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+    hash ^= hash >> 33;
+    hash
+}
+
+/// Returns the seed of a player's random stream, derived from the game seed and the player number.
+/// The player number is spread with the golden ratio so that the streams differ from the game stream and from each other.
+pub(crate) fn player_seed(seed: u64, player: usize) -> u64 {
+    seed ^ (player as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+/// Returns the seed of the ant ids stream, derived from the game seed with a constant of its own
+/// so that it differs from the game stream and from every player stream.
+fn id_seed(seed: u64) -> u64 {
+    seed ^ 0xD1B5_4A32_D192_ED03
+}
+
+/// Checks that the map has at least 1 player and that every ant and hill belongs to a declared player.
+/// Checks that the parsed map can be played, see `Game::try_new`.
+fn check_playable(map: &Map) -> Result<(), String> {
+    let players = map.players();
+    if players == 0 {
+        return Err("The map must have at least 1 player.".to_string());
+    }
+
+    for (entity, row, col) in map.ants().into_iter().chain(map.ant_hills()) {
+        let player = entity.player().unwrap();
+        if player >= players {
+            return Err(format!(
+                "The {} at ({}, {}) belongs to player {}, but the map only has {} players.",
+                entity.name(),
+                row,
+                col,
+                player,
+                players
+            ));
+        }
+    }
+
+    // Without a hill, a player can't have any ants and the game would be decided from the first turn
+    match map.hills_per_player().iter().position(|hills| *hills == 0) {
+        Some(player) => Err(format!(
+            "Player {} has no hill. Every player must have at least 1 hill to start the game.",
+            player
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+
+    use super::*;
+    use crate::entities::Food;
+
+    #[test]
+    #[should_panic(expected = "The map must have at least 1 player.")]
+    fn when_creating_a_game_with_no_players_it_panics() {
+        let map = "\
+            rows 1
+            cols 2
+            players 0
+            m ..";
+        Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "The Hill at (0, 1) belongs to player 2, but the map only has 2 players."
+    )]
+    fn when_creating_a_game_with_entities_of_undeclared_players_it_panics() {
+        let map = "\
+            rows 1
+            cols 3
+            players 2
+            m 02.";
+        Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+    }
+
+    #[test]
+    fn when_checking_a_map_where_a_player_has_no_hill_an_error_is_returned() {
+        let map = "\
+            rows 1
+            cols 3
+            players 2
+            m 0.b";
+
+        assert_eq!(
+            Game::check_map(map),
+            Err(
+                "Player 1 has no hill. Every player must have at least 1 hill to start the game."
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            Game::check_map("rows 1\ncols 2\nplayers 0\nm .."),
+            Err("The map must have at least 1 player.".to_string())
+        );
+        assert_eq!(Game::check_map("rows 1\ncols 3\nplayers 2\nm 0.1"), Ok(()));
+    }
+
+    #[test]
+    fn when_trying_to_create_a_game_with_a_map_that_can_not_be_played_an_error_is_returned() {
+        let try_new = |map: &str| Game::try_new(map, 4, 5, 1, 5, 1500, 500, 0, None).err();
+
+        assert_eq!(
+            try_new("rows 1\ncols 3\nplayers 2\nm 0.b"),
+            Some(
+                "Player 1 has no hill. Every player must have at least 1 hill to start the game."
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            try_new("rows 1\ncols 3\nplayers 2\nm 0?1"),
+            Some("Invalid character value: ?".to_string())
+        );
+        assert_eq!(
+            try_new("rows 1\ncols 3\nplayers 2\nbonus 2 5\nm 0.1"),
+            Some("The bonus is for player 2, but the map only has 2 players.".to_string())
+        );
+        assert_eq!(try_new("rows 1\ncols 3\nplayers 2\nm 0.1"), None);
+    }
+
+    #[test]
+    fn when_placing_entities_before_starting_they_are_part_of_the_game() {
+        let map = "\
+            rows 2
+            cols 4
+            players 2
+            m %...
+            m ....";
+        let mut game = Game::new_unchecked(map, 4, 1, 1, 0, 1500, 500, 0, None);
+
+        game.place_hill(0, 0, 1, true).unwrap();
+        game.place_hill(1, 1, 3, true).unwrap();
+        game.place_hill(1, 0, 3, false).unwrap();
+        game.place_ant(1, 1, 0).unwrap();
+        game.place_food(0, 2).unwrap();
+        let state = game.start();
+
+        assert_eq!(state.ants[0].len(), 1);
+        assert_eq!(state.ants[1].len(), 2);
+        assert_eq!(
+            game.map.get(0, 1).unwrap().on_ant_hill().unwrap().player(),
+            Some(0)
+        );
+        assert!(!game.map.get(0, 3).unwrap().alive().unwrap());
+        assert_eq!(game.map.get(1, 0).unwrap().player(), Some(1));
+        assert_eq!(game.map.get(0, 2).unwrap().name(), "Food");
+
+        // They are kept when the game is started again
+        let state = game.start();
+        assert_eq!(state.ants[1].len(), 2);
+    }
+
+    #[test]
+    fn when_an_ant_steps_off_the_edge_of_a_wrapping_map_it_comes_back_on_the_opposite_edge() {
+        let map = "\
+            rows 3
+            cols 6
+            players 2
+            wrap 1
+            m 0.....
+            m a.....
+            m ...1..";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.set_starting_food_per_hill(0);
+        game.start();
+
+        let state = game.update(vec![Action::new(1, 0, Direction::West)]);
+
+        assert!(state.ants[0].iter().any(|ant| (ant.row, ant.col) == (1, 5)));
+        assert!(game.map.get(1, 0).is_none());
+        assert_eq!(game.map.get(1, 5).unwrap().player(), Some(0));
+        assert_eq!(
+            game.legal_moves(1, 5),
+            vec![
+                Direction::North,
+                Direction::East,
+                Direction::South,
+                Direction::West
+            ]
+        );
+    }
+
+    #[test]
+    fn when_placing_hills_before_starting_they_are_spawned_in_the_replay() {
+        let map = "\
+            rows 2
+            cols 4
+            players 2
+            m %...
+            m ....";
+        let filename = std::env::temp_dir()
+            .join(format!("ants_replay_{}.json", uuid::Uuid::new_v4()))
+            .to_str()
+            .unwrap()
+            .to_string();
+        let mut game = Game::new_unchecked(map, 4, 1, 1, 0, 1, 500, 0, Some(filename.clone()));
+        game.set_starting_food_per_hill(0);
+        game.place_hill(0, 0, 1, true).unwrap();
+        game.place_hill(1, 1, 3, true).unwrap();
+        game.place_hill(1, 0, 3, false).unwrap();
+        game.start();
+        let start = game.render();
+
+        // The game ends after the first turn since the turn limit is 1
+        game.update(vec![]);
+
+        let replay = std::fs::read_to_string(&filename).unwrap();
+        std::fs::remove_file(&filename).unwrap();
+
+        let frames = crate::render_frames(&replay).unwrap();
+        assert_eq!(frames[0], start);
+        assert_eq!(frames.last().unwrap(), &game.render());
+    }
+
+    #[test]
+    fn when_placing_an_entity_on_an_occupied_cell_an_error_is_returned() {
+        let map = "\
+            rows 1
+            cols 3
+            players 1
+            m %.0";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+
+        assert_eq!(
+            game.place_food(0, 0),
+            Err("The cell (0, 0) is already occupied by Water.".to_string())
+        );
+    }
+
+    #[test]
+    fn when_placing_an_entity_out_of_bounds_an_error_is_returned() {
+        let map = "\
+            rows 1
+            cols 3
+            players 1
+            m %.0";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+
+        assert_eq!(
+            game.place_ant(0, 1, 0),
+            Err("The cell (1, 0) is out of bounds, the map has 1 rows and 3 columns.".to_string())
+        );
+        assert_eq!(
+            game.place_hill(1, 0, 0, true),
+            Err("Invalid player 1, the game only has 1 players.".to_string())
+        );
+    }
+
+    #[test]
+    fn when_placing_an_entity_after_starting_an_error_is_returned() {
+        let map = "\
+            rows 1
+            cols 3
+            players 1
+            m ..0";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.start();
+
+        assert_eq!(
+            game.place_food(0, 0),
+            Err("Entities can only be placed before the game starts.".to_string())
+        );
+    }
+
+    #[test]
+    fn when_starting_a_game_the_map_is_reset() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+
+        game.map.set(0, 0, Box::new(Food));
+        game.start();
+
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Water");
+    }
+
+    #[test]
+    fn when_starting_a_game_ants_are_spawned_on_ant_hills() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+
+        game.start();
+
+        let ant = game.map.get(0, 1).unwrap();
+        assert_eq!(ant.name(), "Ant");
+        assert_eq!(ant.player().unwrap(), 1);
+        assert!(ant.alive().unwrap());
+        assert_eq!(ant.on_ant_hill().as_ref().unwrap().player().unwrap(), 1);
+
+        let ant = game.map.get(3, 2).unwrap();
+        assert_eq!(ant.name(), "Ant");
+        assert_eq!(ant.player().unwrap(), 0);
+        assert!(ant.alive().unwrap());
+        assert_eq!(ant.on_ant_hill().as_ref().unwrap().player().unwrap(), 0);
+    }
+
+    #[test]
+    fn when_starting_a_game_food_is_spawned_around_land_locations_for_each_ant_hill() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+
+        game.start();
+
+        // The map has 1 ant hill at (0, 1) for player 1 with 3 land cells around it
+        // So food should be spawned at (0, 2), (1, 1), and (1, 2)
+        assert_eq!(game.map.get(0, 2).as_ref().unwrap().name(), "Food");
+        assert_eq!(game.map.get(1, 1).as_ref().unwrap().name(), "Food");
+        assert_eq!(game.map.get(1, 2).as_ref().unwrap().name(), "Food");
+
+        // The map has 1 ant hill at (3, 2) for player 0 with 3 land cells around it
+        // So food should be spawned at (2, 1), (2, 2), and (3, 1)
+        assert_eq!(game.map.get(2, 1).as_ref().unwrap().name(), "Food");
+        assert_eq!(game.map.get(2, 2).as_ref().unwrap().name(), "Food");
+        assert_eq!(game.map.get(3, 1).as_ref().unwrap().name(), "Food");
+    }
+
+    #[test]
+    fn when_starting_a_game_with_a_different_starting_food_per_hill_that_much_food_is_spawned_around_each_hill(
+    ) {
+        let map = "\
+            rows 3
+            cols 7
+            players 2
+            m .......
+            m .0...1.
+            m .......";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+        game.set_starting_food_per_hill(5);
+
+        game.start();
+
+        let food = game.map.food();
+        assert_eq!(food.len(), 10);
+        assert_eq!(food.iter().filter(|(_, col)| *col <= 2).count(), 5);
+        assert_eq!(food.iter().filter(|(_, col)| *col >= 4).count(), 5);
+    }
+
+    #[test]
+    fn when_starting_a_game_with_more_starting_food_per_hill_than_land_around_only_the_food_that_fits_is_spawned(
+    ) {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+        game.set_starting_food_per_hill(4);
+
+        game.start();
+
+        // Each hill only has 3 land cells around it
+        assert_eq!(game.map.food().len(), 6);
+    }
+
+    #[test]
+    fn when_starting_a_game_the_correct_game_state_is_returned() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+
+        let state = game.start();
+
+        assert_eq!(state.turn, 0);
+        assert_eq!(state.max_turns, 1500);
+        assert_eq!(state.turns_remaining(), 1500);
+        assert_eq!(state.progress(), 0.0);
+        assert!(!state.finished);
+        assert!(state.finished_reason.is_none());
+
+        // 3 food cells are spawned around each of the 2 ant hills
+        assert_eq!(state.total_food, 6);
+        assert_eq!(game.food_on_map(), 6);
+
+        // The map has 2 players
+        assert_eq!(state.scores, vec![1, 1]);
+        assert_eq!(state.ants.len(), 2);
+
+        // The map has 1 ant hill at (3, 2) for player 0
+        assert_eq!(state.ants[0].len(), 1);
+        assert_eq!(state.ants[0][0].row, 3);
+        assert_eq!(state.ants[0][0].col, 2);
+        assert_eq!(state.ants[0][0].player, 0);
+        assert!(state.ants[0][0].alive);
+        // Given the fov radius of 2, the ant at (3, 2) should see 8 entities
+        assert_eq!(state.ants[0][0].field_of_vision.len(), 8);
+        // Let's check that it was able to see the water next to it at (3, 3)
+        assert!(state.ants[0][0]
+            .field_of_vision
+            .iter()
+            .any(|entity| entity.name == "Water" && entity.row == 3 && entity.col == 3));
+        // Let's also check that it was able to see the ant hill where it is standing at (3, 2)
+        assert!(state.ants[0][0]
+            .field_of_vision
+            .iter()
+            .any(|entity| entity.name == "Hill"
+                && entity.row == 3
+                && entity.col == 2
+                && entity.player.unwrap() == 0
+                && entity.alive.unwrap()));
+
+        // The map has 1 ant hill at (0, 1) for player 1
+        assert_eq!(state.ants[1].len(), 1);
+        assert_eq!(state.ants[1][0].row, 0);
+        assert_eq!(state.ants[1][0].col, 1);
+        assert_eq!(state.ants[1][0].player, 1);
+        assert!(state.ants[1][0].alive);
+        // Given the fov radius of 2, the ant at (0, 1) should see 8 entities
+        assert_eq!(state.ants[1][0].field_of_vision.len(), 8);
+        // Let's check that it was able to see the water next to it at (0, 0)
+        assert!(state.ants[1][0]
+            .field_of_vision
+            .iter()
+            .any(|entity| entity.name == "Water" && entity.row == 0 && entity.col == 0));
+        // Let's also check that it was able to see the ant hill where it is standing at (0, 1)
+        assert!(state.ants[1][0]
+            .field_of_vision
+            .iter()
+            .any(|entity| entity.name == "Hill"
+                && entity.row == 0
+                && entity.col == 1
+                && entity.player.unwrap() == 1
+                && entity.alive.unwrap()));
+    }
+
+    #[test]
+    fn when_converting_the_game_state_to_arrays_they_match_the_ants_of_each_player() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+        let state = game.start();
+
+        let arrays = state.as_arrays();
+
+        let ants: Vec<&PlayerAnt> = state.ants.iter().flatten().collect();
+        assert_eq!(
+            arrays.rows,
+            ants.iter().map(|ant| ant.row).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            arrays.cols,
+            ants.iter().map(|ant| ant.col).collect::<Vec<_>>()
+        );
+        assert_eq!(arrays.players, vec![0, 1]);
+        assert_eq!(arrays.alive, vec![true, true]);
+        assert_eq!(arrays.fov_offsets.len(), ants.len() + 1);
+        for (i, ant) in ants.iter().enumerate() {
+            let fov = arrays.fov_offsets[i]..arrays.fov_offsets[i + 1];
+            assert_eq!(arrays.ids[i], ant.id);
+            assert_eq!(fov.len(), ant.field_of_vision.len());
+
+            for (j, entity) in fov.zip(ant.field_of_vision.iter()) {
+                assert_eq!(arrays.fov_names[j], entity.name);
+                assert_eq!(
+                    (arrays.fov_rows[j], arrays.fov_cols[j]),
+                    (entity.row, entity.col)
+                );
+                assert_eq!(
+                    arrays.fov_players[j],
+                    entity.player.map_or(-1, |player| player as isize)
+                );
+                assert_eq!(arrays.fov_alive[j], entity.alive.unwrap_or(false));
+            }
+        }
+    }
+
+    #[test]
+    fn when_updating_a_game_the_game_state_has_the_turns_remaining_and_progress() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 4, 500, 0, None);
+        game.start();
+
+        game.update(vec![]);
+        let state = game.update(vec![]);
+
+        assert_eq!(state.turn, 2);
+        assert_eq!(state.max_turns, 4);
+        assert_eq!(state.turns_remaining(), 2);
+        assert_eq!(state.progress(), 0.5);
+    }
+
+    #[test]
+    fn when_starting_a_game_with_a_fov_radius_per_player_each_ant_sees_with_its_player_radius() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+        // Player 0 can only see its own cell while player 1 sees the whole map
+        game.set_fov_radius2_per_player(vec![0, 25]);
+
+        let state = game.start();
+
+        // The ant of player 0 only sees the hill it's standing on
+        assert_eq!(state.ants[0][0].field_of_vision.len(), 1);
+        assert_eq!(state.ants[0][0].field_of_vision[0].name, "Hill");
+
+        // The ant of player 1 sees every entity on the map but itself: 8 water, 2 hills, 6 food and the other ant
+        assert_eq!(state.ants[1][0].field_of_vision.len(), 17);
+    }
+
+    #[test]
+    fn when_starting_a_game_with_line_of_sight_ants_do_not_see_behind_water() {
+        let map = "\
+            rows 1
+            cols 5
+            players 2
+            m 0%..1";
+        let mut game = Game::new(map, 16, 1, 1, 0, 1500, 500, 0, None);
+        game.set_line_of_sight(true);
+
+        let state = game.start();
+        let seen: Vec<(usize, usize)> = state.ants[0][0]
+            .field_of_vision
+            .iter()
+            .map(|entity| (entity.row, entity.col))
+            .collect();
+
+        assert!(seen.contains(&(0, 1)));
+        assert!(!seen.contains(&(0, 4)));
+    }
+
+    #[test]
+    fn when_starting_a_game_each_ant_lists_the_cells_it_can_see_including_empty_ones() {
+        let map = "\
+            rows 3
+            cols 5
+            players 2
+            m 0....
+            m .....
+            m ....1";
+        let mut game = Game::new(map, 1, 1, 1, 0, 1500, 500, 0, None);
+        game.set_starting_food_per_hill(0);
+
+        let state = game.start();
+
+        // The only entity the ant sees is the hill it's standing on, but it also sees the empty cells next to it
+        assert_eq!(state.ants[0][0].field_of_vision.len(), 1);
+        assert_eq!(state.ants[0][0].field_of_vision[0].name, "Hill");
+        let mut visible_cells = state.ants[0][0].visible_cells.clone();
+        visible_cells.sort();
+        assert_eq!(visible_cells, vec![(0, 0), (0, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn when_creating_a_game_the_configuration_can_be_read_back() {
+        let map = "\
+            rows 1
+            cols 2
+            players 2
+            m 01";
+        let mut game = Game::new(map, 9, 5, 2, 3, 1500, 500, 0, None);
+        game.set_fov_radius2_per_player(vec![4, 16]);
+
+        assert_eq!(game.fov_radius2(), 9);
+        assert_eq!(game.fov_radius2_per_player(), vec![4, 16]);
+        assert_eq!(game.attack_radius2(), 5);
+        assert_eq!(game.food_radius2(), 2);
+        assert_eq!(game.food_rate(), 3);
+        assert_eq!(game.max_turns(), 1500);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Expected a field of vision radius for each of the 2 players, got 1."
+    )]
+    fn when_setting_a_fov_radius_per_player_with_the_wrong_number_of_players_a_panic_occurs() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+
+        game.set_fov_radius2_per_player(vec![4]);
+    }
+
+    #[test]
+    fn when_starting_a_game_the_initial_scores_are_computed_as_the_number_of_ant_hills_per_player()
+    {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %1.%
+            m %..%
+            m %00%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+
+        game.start();
+
+        assert_eq!(game.scores, vec![2, 2]);
+    }
+
+    #[test]
+    fn when_starting_a_game_with_a_zero_starting_score_every_player_starts_with_0_points() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %00%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+        game.set_starting_score(StartingScore::Zero());
+
+        game.start();
+
+        assert_eq!(game.scores, vec![0, 0]);
+    }
+
+    #[test]
+    fn when_starting_a_game_with_a_fixed_starting_score_every_player_starts_with_that_many_points()
+    {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %00%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+        game.set_starting_score(StartingScore::Fixed(10));
+
+        game.start();
+
+        assert_eq!(game.scores, vec![10, 10]);
+    }
+
+    #[test]
+    fn when_starting_a_game_on_a_map_with_a_bonus_the_bonus_is_added_to_the_starting_score() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            bonus 1 3
+            m %1.%
+            m %..%
+            m %..%
+            m %00%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+
+        game.start();
+        assert_eq!(game.scores, vec![2, 4]);
+
+        game.set_starting_score(StartingScore::Zero());
+        game.start();
+        assert_eq!(game.scores, vec![0, 3]);
+    }
+
+    #[test]
+    fn when_starting_a_game_with_multiple_starting_ants_per_hill_the_extra_ants_are_spawned_around_each_hill(
+    ) {
+        let map = "\
+            rows 7
+            cols 7
+            players 2
+            m .......
+            m .0.....
+            m .......
+            m .......
+            m .......
+            m .....1.
+            m .......";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+        game.set_starting_ants_per_hill(4);
+
+        let state = game.start();
+
+        assert_eq!(state.ants[0].len(), 4);
+        assert_eq!(state.ants[1].len(), 4);
+
+        // The first ant is spawned on the hill
+        assert_eq!(game.map.get(1, 1).unwrap().name(), "Ant");
+        assert!(game.map.get(1, 1).unwrap().on_ant_hill().is_some());
+        assert_eq!(game.map.get(5, 5).unwrap().name(), "Ant");
+        assert!(game.map.get(5, 5).unwrap().on_ant_hill().is_some());
+
+        // The extra ants are spawned within the rings around the hill
+        for (player, (hill_row, hill_col)) in [(1, 1), (5, 5)].iter().enumerate() {
+            for ant in state.ants[player].iter() {
+                assert!(ant.row.abs_diff(*hill_row) <= 2);
+                assert!(ant.col.abs_diff(*hill_col) <= 2);
+            }
+        }
+    }
+
+    #[test]
+    fn when_starting_a_game_with_more_starting_ants_than_land_only_the_ants_that_fit_are_spawned() {
+        let map = "\
+            rows 2
+            cols 2
+            players 1
+            m 0.
+            m %%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+        game.set_starting_ants_per_hill(3);
+
+        let state = game.start();
+
+        // Only the ant on the hill fits since the starting food took the only land cell
+        assert_eq!(state.ants[0].len(), 1);
+    }
+
+    #[test]
+    fn when_playing_a_game_the_statistics_accumulate_the_turn_stats() {
+        let map = "\
+            rows 3
+            cols 5
+            players 2
+            m 0*...
+            m .....
+            m ....1";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.set_starting_food_per_hill(0);
+        game.start();
+
+        // The ant of player 0 harvests the food next to its hill on the first turn
+        game.update(vec![]);
+        // Then it leaves its hill so that a new ant can spawn there with the harvested food
+        game.update(vec![Action::new(0, 0, Direction::South)]);
+
+        let statistics = game.statistics();
+        // Each player starts with 1 ant on its hill, and only player 0 spawns another one
+        assert_eq!(statistics[0].food_harvested, 1);
+        assert_eq!(statistics[0].ants_spawned, 2);
+        assert_eq!(statistics[1].food_harvested, 0);
+        assert_eq!(statistics[1].ants_spawned, 1);
+        for stats in &statistics {
+            assert_eq!(stats.ants_killed, 0);
+            assert_eq!(stats.ants_lost, 0);
+            assert_eq!(stats.hills_razed, 0);
+            assert_eq!(stats.hills_lost, 0);
+        }
+    }
+
+    #[test]
+    fn when_ants_collide_the_statistics_count_them_as_lost() {
+        let map = "\
+            rows 1
+            cols 3
+            players 2
+            m a.b";
+        let mut game = Game::new_unchecked(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.start();
+
+        game.update(vec![
+            Action::new(0, 0, Direction::East),
+            Action::new(0, 2, Direction::West),
+        ]);
+
+        let statistics = game.statistics();
+        assert_eq!(statistics[0].ants_lost, 1);
+        assert_eq!(statistics[1].ants_lost, 1);
+        assert_eq!(statistics[0].ants_killed, 0);
+        assert_eq!(statistics[1].ants_killed, 0);
+    }
+
+    #[test]
+    fn when_an_ant_dies_with_a_corpse_lifetime_the_corpse_stays_for_that_many_turns() {
+        let map = "\
+            rows 1
+            cols 7
+            players 2
+            m Aa....B";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.set_corpse_lifetime(2);
+        game.start();
+
+        game.map.get_mut(0, 1).unwrap().set_alive(false);
+        game.remove_dead_ants();
+
+        assert_eq!(game.map.get(0, 1).unwrap().name(), "Corpse");
+        assert_eq!(game.map.get(0, 1).unwrap().player(), Some(0));
+
+        let state = game.update(vec![]);
+        assert!(state.ants[0][0]
+            .field_of_vision
+            .iter()
+            .any(|entity| entity.name == "Corpse" && entity.row == 0 && entity.col == 1));
+
+        game.update(vec![]);
+        assert_eq!(game.map.get(0, 1).unwrap().name(), "Corpse");
+
+        game.update(vec![]);
+        assert!(game.map.get(0, 1).is_none());
+    }
+
+    #[test]
+    fn when_an_ant_dies_without_a_corpse_lifetime_it_is_removed_right_away() {
+        let map = "\
+            rows 1
+            cols 7
+            players 2
+            m Aa....B";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.start();
+
+        game.map.get_mut(0, 1).unwrap().set_alive(false);
+        game.remove_dead_ants();
+
+        assert!(game.map.get(0, 1).is_none());
+    }
+
+    #[test]
+    fn when_an_ant_moves_onto_a_corpse_the_corpse_is_replaced() {
+        let map = "\
+            rows 1
+            cols 7
+            players 2
+            m Aa....B";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.set_corpse_lifetime(5);
+        game.start();
+
+        game.map.get_mut(0, 1).unwrap().set_alive(false);
+        game.remove_dead_ants();
+
+        game.update(vec![Action::new(0, 0, Direction::East)]);
+
+        assert_eq!(game.map.get(0, 1).unwrap().name(), "Ant");
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
+    }
+
+    #[test]
+    fn when_playing_a_game_the_turn_and_finished_state_can_be_read_from_the_game() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 2, 500, 0, None);
+        game.start();
+
+        let state = game.update(vec![]);
+        assert_eq!(game.turn(), 1);
+        assert_eq!(game.is_finished(), state.finished);
+        assert_eq!(game.finished_reason(), None);
+
+        let state = game.update(vec![]);
+        assert_eq!(game.turn(), state.turn);
+        assert!(game.is_finished());
+        assert_eq!(
+            game.finished_reason(),
+            Some(FinishedReason::TurnLimitReached)
+        );
+    }
+
+    #[test]
+    fn when_fast_forwarding_a_game_the_state_matches_stepping_turn_by_turn() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let actions = vec![
+            vec![Action::new(3, 2, Direction::North)],
+            vec![Action::new(2, 2, Direction::West)],
+            vec![],
+        ];
+
+        let mut stepped = Game::new(map, 4, 4, 1, 1, 1500, 500, 3, None);
+        stepped.set_starting_food_per_hill(0);
+        stepped.start();
+        let mut expected = None;
+        for turn_actions in &actions {
+            expected = Some(stepped.update(turn_actions.clone()));
+        }
+
+        let mut game = Game::new(map, 4, 4, 1, 1, 1500, 500, 3, None);
+        game.set_starting_food_per_hill(0);
+        game.start();
+        let state = game.fast_forward(&actions);
+
+        assert_eq!(state, expected.unwrap());
+        assert_eq!(game.turn(), 3);
+    }
+
+    #[test]
+    fn when_fast_forwarding_a_game_past_its_end_it_stops_at_the_last_turn() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let mut game = Game::new(map, 4, 4, 1, 1, 2, 500, 0, None);
+        let state = game.start();
+
+        // Without any turn to play, the current state is returned
+        assert_eq!(game.fast_forward(&[]), state);
+
+        let state = game.fast_forward(&vec![vec![]; 5]);
+
+        assert!(state.finished);
+        assert_eq!(state.turn, 2);
+        assert_eq!(game.turn(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Game has not started! Call `start` to start the game.")]
+    fn when_fast_forwarding_a_game_that_has_not_started_a_panic_occurs() {
+        let map = "\
+            rows 1
+            cols 4
+            players 2
+            m 1..0";
+        let mut game = Game::new(map, 4, 4, 1, 1, 1500, 500, 0, None);
+        game.fast_forward(&[vec![]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Game has not started! Call `start` to start the game.")]
+    fn when_updating_a_game_that_has_not_started_a_panic_occurs() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+        game.update(vec![]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Game is finished! Call `start` to start a new game.")]
+    fn when_updating_a_game_that_has_finished_a_panic_occurs() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+        game.started = true;
+        game.finished = true;
+
+        game.update(vec![]);
+    }
+
+    #[test]
+    fn when_trying_to_update_a_game_that_has_not_started_an_error_is_returned() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+
+        assert_eq!(game.try_update(vec![]), Err(GameError::NotStarted));
+    }
+
+    #[test]
+    fn when_trying_to_update_a_game_that_has_finished_an_error_is_returned() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+        game.started = true;
+        game.finished = true;
+
+        assert_eq!(game.try_update(vec![]), Err(GameError::AlreadyFinished));
+    }
+
+    #[test]
+    fn when_trying_to_update_a_game_that_has_started_the_state_is_returned() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+        game.start();
+
+        let state = game.try_update(vec![]).unwrap();
+
+        assert_eq!(state.turn, 1);
+    }
+
+    #[test]
+    fn when_validating_a_game_with_an_attack_radius_within_the_vision_it_is_valid() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let game = Game::new(map, 5, 5, 1, 5, 1500, 500, 0, None);
+
+        assert_eq!(game.validate(), Ok(()));
+    }
+
+    #[test]
+    fn when_validating_a_game_with_an_attack_radius_beyond_the_vision_of_a_player_an_error_is_returned(
+    ) {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let mut game = Game::new(map, 9, 5, 1, 5, 1500, 500, 0, None);
+        game.set_fov_radius2_per_player(vec![9, 4]);
+
+        assert_eq!(
+            game.validate(),
+            Err(GameError::AttackBeyondVision {
+                player: 1,
+                attack_radius2: 5,
+                fov_radius2: 4,
+            })
+        );
+    }
+
+    #[test]
+    fn when_playing_a_game_with_an_attack_radius_beyond_the_vision_it_is_allowed_by_default() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1.%
+            m %..%
+            m %..%
+            m %.0%";
+        let mut game = Game::new(map, 1, 5, 1, 5, 1500, 500, 0, None);
+        assert!(game.validate().is_err());
+
+        game.start();
+        let state = game.update(vec![]);
+
+        assert_eq!(state.turn, 1);
+    }
+
+    #[test]
+    fn when_removing_dead_ants_all_dead_ants_are_removed() {
+        let map = "\
+            rows 2
+            cols 2
+            players 1
+            m 0.
+            m a.";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+
+        assert!(game.map.get(1, 0).unwrap().alive().unwrap());
+        game.map.get_mut(1, 0).unwrap().set_alive(false);
+
+        game.remove_dead_ants();
+
+        assert!(game.map.get(1, 0).is_none());
+    }
+
+    #[test]
+    fn when_removing_dead_ants_if_there_are_no_dead_ants_no_ants_are_removed() {
+        let map = "\
+            rows 2
+            cols 2
+            players 1
+            m 0.
+            m a.";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+
+        assert!(game.map.get(1, 0).unwrap().alive().unwrap());
+
+        game.remove_dead_ants();
+
+        assert!(game.map.get(1, 0).is_some());
+    }
+
+    #[test]
+    fn when_removing_dead_ants_if_a_dead_ant_is_on_a_hill_the_hill_is_restored() {
+        let map = "\
+            rows 2
+            cols 2
+            players 1
+            m A.
+            m ..";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
+        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
+
+        game.map.get_mut(0, 0).unwrap().set_alive(false);
+
+        game.remove_dead_ants();
+
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
+        assert_eq!(game.map.get(0, 0).unwrap().player().unwrap(), 0);
+    }
+
+    #[test]
+    fn when_removing_dead_ants_if_a_dead_ant_is_on_enemy_hill_the_hill_is_restored() {
+        let map = "\
+            rows 2
+            cols 2
+            players 2
+            m 0.
+            m b.";
+        let mut game = Game::new_unchecked(map, 4, 4, 1, 5, 1500, 500, 0, None);
+
+        // Move the ant to the enemy hill
+        game.map.move_entity((1, 0), (0, 0));
+
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
+        assert_eq!(game.map.get(0, 0).unwrap().player().unwrap(), 1);
+        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
+
+        game.map.get_mut(0, 0).unwrap().set_alive(false);
+
+        game.remove_dead_ants();
+
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
+        // The hill should be restored to the original owner
+        assert_eq!(game.map.get(0, 0).unwrap().player().unwrap(), 0);
+    }
+
+    #[test]
+    fn when_attacking_on_a_one_on_one_battle_both_ants_die() {
+        let map = "\
+            rows 3
+            cols 5
+            players 2
+            m .....
+            m .a.b.
+            m .....";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        assert!(game.map.get(1, 1).unwrap().alive().unwrap());
+        assert!(game.map.get(1, 3).unwrap().alive().unwrap());
+
+        game.attack();
+
+        assert!(!game.map.get(1, 1).unwrap().alive().unwrap());
+        assert!(!game.map.get(1, 3).unwrap().alive().unwrap());
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].ants_killed, 1);
+        assert_eq!(turn_stats[0].ants_lost, 1);
+        assert_eq!(turn_stats[1].ants_killed, 1);
+        assert_eq!(turn_stats[1].ants_lost, 1);
+    }
+
+    #[test]
+    fn when_attacking_on_a_two_on_one_battle_ant_a_dies() {
+        let map = "\
+            rows 3
+            cols 5
+            players 2
+            m ...b.
+            m .a...
+            m ...b.";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        assert!(game.map.get(0, 3).unwrap().alive().unwrap());
+        assert!(game.map.get(1, 1).unwrap().alive().unwrap());
+        assert!(game.map.get(2, 3).unwrap().alive().unwrap());
+
+        game.attack();
+
+        assert!(game.map.get(0, 3).unwrap().alive().unwrap());
+        assert!(!game.map.get(1, 1).unwrap().alive().unwrap());
+        assert!(game.map.get(2, 3).unwrap().alive().unwrap());
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].ants_killed, 0);
+        assert_eq!(turn_stats[0].ants_lost, 1);
+        assert_eq!(turn_stats[1].ants_killed, 2);
+        assert_eq!(turn_stats[1].ants_lost, 0);
+    }
+
+    #[test]
+    fn when_attacking_on_a_two_on_one_battle_the_events_record_who_killed_whom() {
+        use crate::replay::EventType;
+
+        let map = "\
+            rows 3
+            cols 5
+            players 2
+            m ...b.
+            m .a...
+            m ...b.";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        let events = Arc::new(Mutex::new(vec![]));
+        let observed = events.clone();
+        game.add_event_observer(move |_, event| {
+            observed.lock().unwrap().push(event.clone());
+        });
+        let ant_id = |game: &Game, row, col| game.map.get(row, col).unwrap().id().to_string();
+        let (a, b1, b2) = (
+            ant_id(&game, 1, 1),
+            ant_id(&game, 0, 3),
+            ant_id(&game, 2, 3),
+        );
+
+        game.attack();
+
+        let kills: Vec<(String, String)> = events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| event.event_type == EventType::Attack)
+            .map(|event| {
+                (
+                    event.entity_id.clone().unwrap(),
+                    event.target_id.clone().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(kills, vec![(b1, a.clone()), (b2, a)]);
+    }
+
+    #[test]
+    fn when_attacking_on_a_one_on_one_on_one_battle_all_ants_die() {
+        let map = "\
+            rows 3
+            cols 5
+            players 3
+            m ...b.
+            m .a...
+            m ...c.";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        assert!(game.map.get(0, 3).unwrap().alive().unwrap());
+        assert!(game.map.get(1, 1).unwrap().alive().unwrap());
+        assert!(game.map.get(2, 3).unwrap().alive().unwrap());
+
+        game.attack();
+
+        assert!(!game.map.get(0, 3).unwrap().alive().unwrap());
+        assert!(!game.map.get(1, 1).unwrap().alive().unwrap());
+        assert!(!game.map.get(2, 3).unwrap().alive().unwrap());
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].ants_killed, 2);
+        assert_eq!(turn_stats[0].ants_lost, 1);
+        assert_eq!(turn_stats[1].ants_killed, 2);
+        assert_eq!(turn_stats[1].ants_lost, 1);
+        assert_eq!(turn_stats[2].ants_killed, 2);
+        assert_eq!(turn_stats[2].ants_lost, 1);
+    }
+
+    #[test]
+    fn when_attacking_on_an_ant_sandwich_battle_the_middle_ant_dies() {
+        let map = "\
+            rows 3
+            cols 5
+            players 3
+            m .....
+            m a.b.c
+            m .....";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        assert!(game.map.get(1, 0).unwrap().alive().unwrap());
+        assert!(game.map.get(1, 2).unwrap().alive().unwrap());
+        assert!(game.map.get(1, 4).unwrap().alive().unwrap());
+
+        game.attack();
+
+        assert!(game.map.get(1, 0).unwrap().alive().unwrap());
+        assert!(!game.map.get(1, 2).unwrap().alive().unwrap());
+        assert!(game.map.get(1, 4).unwrap().alive().unwrap());
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].ants_killed, 1);
+        assert_eq!(turn_stats[0].ants_lost, 0);
+        assert_eq!(turn_stats[1].ants_killed, 0);
+        assert_eq!(turn_stats[1].ants_lost, 1);
+        assert_eq!(turn_stats[2].ants_killed, 1);
+        assert_eq!(turn_stats[2].ants_lost, 0);
+    }
+
+    #[test]
+    fn when_attacking_on_a_one_on_two_on_one_battle_ants_b_and_c_die() {
+        let map = "\
+            rows 3
+            cols 5
+            players 3
+            m ...b.
+            m .a.a.
+            m ...c.";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        assert!(game.map.get(0, 3).unwrap().alive().unwrap());
+        assert!(game.map.get(1, 1).unwrap().alive().unwrap());
+        assert!(game.map.get(1, 3).unwrap().alive().unwrap());
+        assert!(game.map.get(2, 3).unwrap().alive().unwrap());
+
+        game.attack();
+
+        assert!(!game.map.get(0, 3).unwrap().alive().unwrap());
+        assert!(game.map.get(1, 1).unwrap().alive().unwrap());
+        assert!(game.map.get(1, 3).unwrap().alive().unwrap());
+        assert!(!game.map.get(2, 3).unwrap().alive().unwrap());
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].ants_killed, 4);
+        assert_eq!(turn_stats[0].ants_lost, 0);
+        assert_eq!(turn_stats[1].ants_killed, 1);
+        assert_eq!(turn_stats[1].ants_lost, 1);
+        assert_eq!(turn_stats[2].ants_killed, 1);
+        assert_eq!(turn_stats[2].ants_lost, 1);
+    }
+
+    #[test]
+    fn when_attacking_on_a_wall_punch_battle_many_ants_die() {
+        let map = "\
+            rows 3
+            cols 9
+            players 2
+            m aaaaaaaaa
+            m ...bbb...
+            m ...bbb...";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
+        assert!(game.map.get(0, 1).unwrap().alive().unwrap());
+        assert!(game.map.get(0, 2).unwrap().alive().unwrap());
+        assert!(game.map.get(0, 3).unwrap().alive().unwrap());
+        assert!(game.map.get(0, 4).unwrap().alive().unwrap());
+        assert!(game.map.get(0, 5).unwrap().alive().unwrap());
+        assert!(game.map.get(0, 6).unwrap().alive().unwrap());
+        assert!(game.map.get(0, 7).unwrap().alive().unwrap());
+        assert!(game.map.get(0, 8).unwrap().alive().unwrap());
+        assert!(game.map.get(1, 3).unwrap().alive().unwrap());
+        assert!(game.map.get(1, 4).unwrap().alive().unwrap());
+        assert!(game.map.get(1, 5).unwrap().alive().unwrap());
+        assert!(game.map.get(2, 3).unwrap().alive().unwrap());
+        assert!(game.map.get(2, 4).unwrap().alive().unwrap());
+        assert!(game.map.get(2, 5).unwrap().alive().unwrap());
+
+        game.attack();
+
+        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
+        assert!(game.map.get(0, 1).unwrap().alive().unwrap());
+        assert!(!game.map.get(0, 2).unwrap().alive().unwrap());
+        assert!(!game.map.get(0, 3).unwrap().alive().unwrap());
+        assert!(!game.map.get(0, 4).unwrap().alive().unwrap());
+        assert!(!game.map.get(0, 5).unwrap().alive().unwrap());
+        assert!(!game.map.get(0, 6).unwrap().alive().unwrap());
+        assert!(game.map.get(0, 7).unwrap().alive().unwrap());
+        assert!(game.map.get(0, 8).unwrap().alive().unwrap());
+        assert!(!game.map.get(1, 3).unwrap().alive().unwrap());
+        assert!(!game.map.get(1, 4).unwrap().alive().unwrap());
+        assert!(!game.map.get(1, 5).unwrap().alive().unwrap());
+        assert!(!game.map.get(2, 3).unwrap().alive().unwrap());
+        assert!(game.map.get(2, 4).unwrap().alive().unwrap());
+        assert!(!game.map.get(2, 5).unwrap().alive().unwrap());
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].ants_killed, 21);
+        assert_eq!(turn_stats[0].ants_lost, 5);
+        assert_eq!(turn_stats[1].ants_killed, 22);
+        assert_eq!(turn_stats[1].ants_lost, 5);
+    }
+
+    #[test]
+    fn when_attacking_with_hill_defense_an_ant_on_its_own_hill_survives_and_the_attacker_dies() {
+        let map = "\
+            rows 1
+            cols 3
+            players 2
+            m Ab.";
+        let mut game = Game::new_unchecked(map, 4, 1, 1, 5, 1500, 500, 0, None);
+        game.set_hill_defense(true);
+
+        game.attack();
+
+        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
+        assert!(!game.map.get(0, 1).unwrap().alive().unwrap());
+    }
+
+    #[test]
+    fn when_attacking_with_hill_defense_an_ant_on_an_enemy_hill_can_still_die() {
+        let map = "\
+            rows 1
+            cols 3
+            players 2
+            m 1a.";
+        let mut game = Game::new_unchecked(map, 4, 1, 1, 5, 1500, 500, 0, None);
+        game.set_hill_defense(true);
+        game.map.set(
+            0,
+            0,
+            Box::new(Ant::new(
+                "b".to_string(),
+                1,
+                true,
+                Some(Box::new(Hill::new(0, true))),
+            )),
+        );
+
+        game.attack();
+
+        assert!(!game.map.get(0, 0).unwrap().alive().unwrap());
+        assert!(!game.map.get(0, 1).unwrap().alive().unwrap());
+    }
+
+    #[test]
+    fn when_resolving_battles_in_parallel_the_outcome_is_the_same_as_serially() {
+        let map = "\
+            rows 3
+            cols 9
+            players 2
+            m aaaaaaaaa
+            m ...bbb...
+            m ...bbb...";
+        let game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        let (parallel_to_kill, parallel_attacks) = game.resolve_battles(true);
+        let (serial_to_kill, serial_attacks) = game.resolve_battles(false);
+
+        assert_eq!(parallel_to_kill.len(), 10);
+        assert_eq!(parallel_to_kill, serial_to_kill);
+        assert_eq!(parallel_attacks, serial_attacks);
+    }
+
+    #[test]
+    fn when_attacking_after_a_move_that_kills_ants_the_dead_ants_should_be_ignored() {
+        let map = "\
+            rows 3
+            cols 5
+            players 2
+            m .b...
+            m .ab.
+            m ..b..";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        assert!(game.map.get(0, 1).unwrap().alive().unwrap());
+        assert!(game.map.get(1, 1).unwrap().alive().unwrap());
+        assert!(game.map.get(1, 2).unwrap().alive().unwrap());
+        assert!(game.map.get(2, 2).unwrap().alive().unwrap());
+
+        // Move an ant towards its ally causing it collision and both ants to die
+        game.map.move_entity((2, 2), (1, 2));
+
+        // Make sure they are dead
+        assert!(!game.map.get(1, 2).unwrap().alive().unwrap());
+        assert!(!game.map.get(2, 2).unwrap().alive().unwrap());
+
+        game.attack();
+
+        // The remaining ants from player 0 and player 1 should both attack each other and die
+        assert!(!game.map.get(0, 1).unwrap().alive().unwrap());
+        assert!(!game.map.get(1, 1).unwrap().alive().unwrap());
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].ants_killed, 1);
+        assert_eq!(turn_stats[0].ants_lost, 1);
+        assert_eq!(turn_stats[1].ants_killed, 1);
+        assert_eq!(turn_stats[1].ants_lost, 1);
+    }
+
+    #[test]
+    fn when_razing_hills_if_a_hill_does_not_have_an_ant_the_hill_is_not_razed_and_scores_are_not_changed(
+    ) {
+        let map = "\
+            rows 2
+            cols 2
+            players 1
+            m 0.
+            m ..";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.compute_initial_scores();
+
+        assert_eq!(game.scores, vec![1]);
+        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
+
+        game.raze_hills(&game.live_ant_locations());
+
+        assert_eq!(game.scores, vec![1]);
+        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].hills_razed, 0);
+    }
+
+    #[test]
+    fn when_razing_hills_if_a_hill_has_an_ant_of_the_same_player_the_hill_is_not_razed_and_scores_are_not_changed(
+    ) {
+        let map = "\
+            rows 2
+            cols 2
+            players 1
+            m 0.
+            m a.";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.compute_initial_scores();
+
+        assert_eq!(game.scores, vec![1]);
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
+        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
+
+        // Move the ant to the hill
+        game.map.move_entity((1, 0), (0, 0));
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
+
+        game.raze_hills(&game.live_ant_locations());
+
+        assert_eq!(game.scores, vec![1]);
+        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].hills_razed, 0);
+    }
+
+    #[test]
+    fn when_razing_hills_if_a_hill_has_a_dead_enemy_ant_the_hill_is_not_razed_and_scores_are_not_changed(
+    ) {
+        let map = "\
+            rows 2
+            cols 2
+            players 2
+            m 0.
+            m b1";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.compute_initial_scores();
+
+        assert_eq!(game.scores, vec![1, 1]);
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
+        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
+
+        // Move the enemy to the hill
+        game.map.move_entity((1, 0), (0, 0));
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
+
+        // Kill the enemy
+        game.map.get_mut(0, 0).unwrap().set_alive(false);
+
+        game.raze_hills(&game.live_ant_locations());
+
+        assert_eq!(game.scores, vec![1, 1]);
+        assert!(game
+            .map
+            .get(0, 0)
+            .unwrap()
+            .on_ant_hill()
+            .as_ref()
+            .unwrap()
+            .alive()
+            .unwrap());
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].hills_razed, 0);
+        assert_eq!(turn_stats[0].hills_lost, 0);
+        assert_eq!(turn_stats[1].hills_razed, 0);
+        assert_eq!(turn_stats[1].hills_lost, 0);
+    }
+
+    #[test]
+    fn when_razing_hills_if_a_hill_has_an_alive_enemy_ant_the_hill_is_razed_and_scores_are_updated()
+    {
+        let map = "\
+            rows 2
+            cols 2
+            players 2
+            m 0.
+            m b1";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.compute_initial_scores();
+
+        assert_eq!(game.scores, vec![1, 1]);
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
+        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
+
+        // Move the enemy to the hill
+        game.map.move_entity((1, 0), (0, 0));
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
+
+        game.raze_hills(&game.live_ant_locations());
+
+        // Player 0 loses 1 point for losing the hill
+        // Player 1 gains 2 points for razing the hill
+        assert_eq!(game.scores, vec![0, 3]);
+        assert!(!game
+            .map
+            .get(0, 0)
+            .unwrap()
+            .on_ant_hill()
+            .as_ref()
+            .unwrap()
+            .alive()
+            .unwrap());
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].hills_razed, 0);
+        assert_eq!(turn_stats[0].hills_lost, 1);
+        assert_eq!(turn_stats[1].hills_razed, 1);
+        assert_eq!(turn_stats[1].hills_lost, 0);
+    }
+
+    #[test]
+    fn when_razing_hills_if_the_owner_has_no_points_the_score_stays_at_zero() {
+        let map = "\
+            rows 2
+            cols 2
+            players 2
+            m 0.
+            m b1";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.scores = vec![0, 1];
+        game.map.move_entity((1, 0), (0, 0));
+
+        game.raze_hills(&game.live_ant_locations());
+
+        assert_eq!(game.scores, vec![0, 3]);
+        assert_eq!(game.turn_stats[0].hills_lost, 1);
+    }
+
+    #[test]
+    fn when_razing_hills_with_signed_scores_if_the_owner_has_no_points_the_score_goes_negative() {
+        let map = "\
+            rows 2
+            cols 2
+            players 2
+            m 0.
+            m b1";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_signed_scores(true);
+        game.points_for_losing_hill = 3;
+        game.scores = vec![1, 1];
+        game.map.move_entity((1, 0), (0, 0));
+
+        game.raze_hills(&game.live_ant_locations());
+
+        assert_eq!(game.scores, vec![-2, 3]);
+        assert_eq!(game.ranking(), vec![1, 0]);
+    }
+
+    #[test]
+    fn when_checking_for_endgame_with_signed_scores_the_projection_can_go_negative() {
+        let map = "\
+            rows 1
+            cols 5
+            players 3
+            m 0.1.2";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_signed_scores(true);
+        game.scores = vec![5, -3, 0];
+
+        game.check_for_endgame(&game.live_ant_locations());
+
+        // Player 2 could get at most 4 points, while player 1 could get at most 1 point
+        assert!(game.finished);
+        assert_eq!(game.finished_reason, Some(FinishedReason::RankStabilized));
+        assert_eq!(game.winner, Some(0));
+    }
+
+    #[test]
+    fn when_razing_hills_if_the_hill_was_already_razed_it_is_not_razed_again() {
+        let map = "\
+            rows 2
+            cols 2
+            players 2
+            m 0.
+            m b1";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.compute_initial_scores();
+        game.map.move_entity((1, 0), (0, 0));
+
+        game.raze_hills(&game.live_ant_locations());
+        // The enemy ant stays on the razed hill for another turn
+        game.raze_hills(&game.live_ant_locations());
+
+        assert_eq!(game.scores, vec![0, 3]);
+        assert_eq!(game.statistics[1].hills_razed, 1);
+    }
+
+    #[test]
+    fn when_an_ant_sees_a_razed_hill_the_hill_is_reported_as_razed() {
+        let map = "\
+            rows 2
+            cols 2
+            players 2
+            m 0.
+            m b1";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_reveal_hill_owner(true);
+        game.compute_initial_scores();
+        game.map.move_entity((1, 0), (0, 0));
+        game.raze_hills(&game.live_ant_locations());
+
+        let state = game.game_state(&game.live_ant_locations());
+        let seen = |name: &str, player: usize| {
+            state.ants[1][0]
+                .field_of_vision
+                .iter()
+                .find(|entity| entity.name == name && entity.player == Some(player))
+                .unwrap()
+                .clone()
+        };
+
+        let razed_hill = seen("Hill", 0);
+        assert_eq!(razed_hill.razed, Some(true));
+        assert_eq!(razed_hill.alive, Some(false));
+        assert_eq!(seen("Hill", 1).razed, Some(false));
+        assert!(state.ants[1][0]
+            .field_of_vision
+            .iter()
+            .all(|entity| entity.name == "Hill" || entity.razed.is_none()));
+    }
+
+    #[test]
+    fn when_razing_hills_in_remove_mode_the_hill_becomes_land_once_the_ant_leaves() {
+        let map = "\
+            rows 2
+            cols 2
+            players 2
+            m 0.
+            m b1";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_hill_raze_mode(HillRazeMode::Remove);
+        game.compute_initial_scores();
+        game.map.move_entity((1, 0), (0, 0));
+
+        game.raze_hills(&game.live_ant_locations());
+
+        assert_eq!(game.scores, vec![0, 3]);
+        assert!(game.map.get(0, 0).unwrap().on_ant_hill().is_none());
+
+        // Once the ant leaves, there is no hill left to spawn ants on
+        game.map.move_entity((0, 0), (0, 1));
+        assert!(game.map.get(0, 0).is_none());
+
+        game.hive = vec![1, 0];
+        game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+        assert!(game.map.get(0, 0).is_none());
+        assert_eq!(game.hive, vec![1, 0]);
+    }
+
+    #[test]
+    fn when_razing_hills_in_remove_mode_and_the_ant_dies_the_hill_becomes_land() {
+        let map = "\
+            rows 2
+            cols 2
+            players 2
+            m 0.
+            m b1";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_hill_raze_mode(HillRazeMode::Remove);
+        game.compute_initial_scores();
+        game.map.move_entity((1, 0), (0, 0));
+
+        game.raze_hills(&game.live_ant_locations());
+        game.map.get_mut(0, 0).unwrap().set_alive(false);
+        game.remove_dead_ants();
+
+        assert!(game.map.get(0, 0).is_none());
+    }
+
+    #[test]
+    fn when_razing_hills_and_the_ant_then_dies_on_the_hill_the_hill_stays_razed() {
+        let map = "\
+            rows 2
+            cols 2
+            players 2
+            m 0.
+            m b1";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.compute_initial_scores();
+        game.map.move_entity((1, 0), (0, 0));
+
+        game.raze_hills(&game.live_ant_locations());
+        game.map.get_mut(0, 0).unwrap().set_alive(false);
+        game.remove_dead_ants();
+
+        // The hill is restored as razed rather than resurrected
+        let hill = game.map.get(0, 0).unwrap();
+        assert_eq!(hill.name(), "Hill");
+        assert_eq!(hill.player(), Some(0));
+        assert!(!hill.alive().unwrap());
+
+        // So it no longer spawns ants
+        game.hive = vec![1, 0];
+        game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
+        assert_eq!(game.hive, vec![1, 0]);
+    }
+
+    #[test]
+    fn when_razing_hills_in_score_only_mode_the_hill_keeps_spawning_ants_and_is_only_scored_once() {
+        let map = "\
+            rows 2
+            cols 2
+            players 2
+            m 0.
+            m b1";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_hill_raze_mode(HillRazeMode::ScoreOnly);
+        game.compute_initial_scores();
+        game.map.move_entity((1, 0), (0, 0));
+
+        game.raze_hills(&game.live_ant_locations());
+        game.raze_hills(&game.live_ant_locations());
+
+        assert_eq!(game.scores, vec![0, 3]);
+        assert!(game
+            .map
+            .get(0, 0)
+            .unwrap()
+            .on_ant_hill()
+            .unwrap()
+            .alive()
+            .unwrap());
+
+        // Once the enemy leaves, the owner can spawn ants on the hill again
+        game.map.move_entity((0, 0), (0, 1));
+        game.hive = vec![1, 0];
+        game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+        assert_eq!(game.map.get(0, 0).unwrap().player(), Some(0));
+        assert_eq!(game.hive, vec![0, 0]);
+    }
+
+    #[test]
+    fn when_spawning_ants_from_hive_if_there_is_no_food_no_ants_are_spawned() {
+        let map = "\
+            rows 2
+            cols 2
+            players 2
+            m 01
+            m ..";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
+        assert_eq!(game.map.get(0, 1).unwrap().name(), "Hill");
+        assert_eq!(game.hive, vec![0, 0]);
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].ants_spawned, 0);
+        assert_eq!(turn_stats[1].ants_spawned, 0);
+    }
+
+    #[test]
+    fn when_spawning_ants_from_hive_if_the_hill_is_razed_no_ants_are_spawned() {
+        let map = "\
+            rows 2
+            cols 2
+            players 1
+            m 0.
+            m ..";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.hive = vec![1];
+
+        // Raze the hill
+        game.map.get_mut(0, 0).unwrap().set_alive(false);
+
+        game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
+        assert_eq!(game.hive, vec![1]);
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].ants_spawned, 0);
+    }
+
+    #[test]
+    fn when_spawning_ants_from_hive_if_there_is_food_and_one_hill_one_ant_is_spawned() {
+        let map = "\
+            rows 2
+            cols 2
+            players 2
+            m 01
+            m ..";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.hive = vec![1, 1];
+
+        game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
+        assert_eq!(game.map.get(0, 0).unwrap().player().unwrap(), 0);
+        assert_eq!(game.map.get(0, 1).unwrap().name(), "Ant");
+        assert_eq!(game.map.get(0, 1).unwrap().player().unwrap(), 1);
+        assert_eq!(game.hive, vec![0, 0]);
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].ants_spawned, 1);
+        assert_eq!(turn_stats[1].ants_spawned, 1);
+    }
+
+    #[test]
+    fn when_spawning_ants_from_hive_if_there_is_more_food_and_one_hill_only_one_ant_is_spawned() {
+        let map = "\
+            rows 2
+            cols 2
+            players 1
+            m 0.
+            m ..";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.hive = vec![5];
+
+        game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
+        assert_eq!(game.hive, vec![4]);
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].ants_spawned, 1);
+    }
+
+    #[test]
+    fn when_spawning_ants_from_hive_with_a_spawn_burst_the_extra_ants_are_spawned_around_the_hill()
+    {
+        let map = "\
+            rows 3
+            cols 3
+            players 1
+            m ...
+            m .0.
+            m ...";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_spawn_burst(3);
+        game.hive = vec![5];
+
+        game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+
+        assert_eq!(game.map.get(1, 1).unwrap().name(), "Ant");
+        assert_eq!(game.live_ant_locations().len(), 3);
+        assert_eq!(game.hive, vec![2]);
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].ants_spawned, 3);
+    }
+
+    #[test]
+    fn when_spawning_ants_from_hive_with_a_spawn_burst_only_the_ants_the_hive_can_pay_for_are_spawned(
+    ) {
+        let map = "\
+            rows 3
+            cols 3
+            players 1
+            m ...
+            m .0.
+            m ...";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_spawn_burst(3);
+        game.hive = vec![2];
+
+        game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+
+        assert_eq!(game.live_ant_locations().len(), 2);
+        assert_eq!(game.hive, vec![0]);
+    }
+
+    #[test]
+    fn when_spawning_ants_from_hive_with_a_spawn_burst_near_the_max_colony_size_the_burst_stops_at_it(
+    ) {
+        let map = "\
+            rows 3
+            cols 3
+            players 1
+            m a..
+            m .0.
+            m ...";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 3, 0, None);
+        game.set_spawn_burst(3);
+        game.hive = vec![5];
+
+        game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+
+        // Only 2 more ants fit in the colony, so only 2 are spawned and paid for
+        assert_eq!(game.live_ant_locations().len(), 3);
+        assert_eq!(game.hive, vec![3]);
+    }
+
+    #[test]
+    fn when_spawning_ants_from_hive_without_a_spawn_burst_near_the_max_colony_size_every_chosen_hill_spawns(
+    ) {
+        let map = "\
+            rows 1
+            cols 5
+            players 1
+            m a0.0.";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 2, 0, None);
+        game.hive = vec![5];
+
+        game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+
+        // Only 1 more ant fits in the colony, but both hills spawn their ant, as in the original game
+        assert_eq!(game.live_ant_locations().len(), 3);
+        assert_eq!(game.hive, vec![3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "The spawn burst must be at least 1.")]
+    fn when_setting_a_spawn_burst_of_zero_it_panics() {
+        let map = "\
+            rows 1
+            cols 2
+            players 1
+            m 0.";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        game.set_spawn_burst(0);
+    }
+
+    #[test]
+    fn when_spawning_ants_from_hive_if_there_is_only_one_food_and_multiple_hills_only_one_ant_is_spawned(
+    ) {
+        let map = "\
+            rows 2
+            cols 2
+            players 1
+            m 0.
+            m .0";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.hive = vec![1];
+
+        game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+
+        // Hill is chosen at random and we make it predictable based on the seed
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
+        assert_eq!(game.map.get(1, 1).unwrap().name(), "Ant");
+        assert_eq!(game.hive, vec![0]);
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].ants_spawned, 1);
+    }
+
+    #[test]
+    fn when_the_hive_is_split_per_hill_food_is_banked_at_the_nearest_hill_and_only_spawns_there() {
+        let map = "\
+            rows 1
+            cols 8
+            players 1
+            m 0*a....0";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_per_hill_hive(true);
+
+        game.harvest_food();
+
+        assert_eq!(game.hill_hive(0, 0), 1);
+        assert_eq!(game.hill_hive(0, 7), 0);
+        assert_eq!(game.hive, vec![1]);
+
+        game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
+        assert_eq!(game.map.get(0, 7).unwrap().name(), "Hill");
+        assert_eq!(game.hill_hive(0, 0), 0);
+        assert_eq!(game.hive, vec![0]);
+    }
+
+    #[test]
+    fn when_the_hive_is_split_per_hill_food_transferred_to_another_hill_spawns_there() {
+        let map = "\
+            rows 1
+            cols 8
+            players 1
+            m 0*a....0";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_per_hill_hive(true);
+        game.harvest_food();
+
+        // Only the food banked at the source hill can be moved
+        assert_eq!(game.transfer_hive_food(0, (0, 0), (0, 7), 5), 1);
+        game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
+        assert_eq!(game.map.get(0, 7).unwrap().name(), "Ant");
+        assert_eq!(game.hive, vec![0]);
+    }
+
+    #[test]
+    fn when_the_hive_is_split_per_hill_and_a_spawn_burst_nears_the_max_colony_size_the_burst_stops_at_it(
+    ) {
+        let map = "\
+            rows 3
+            cols 3
+            players 1
+            m a..
+            m .0.
+            m ...";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 3, 0, None);
+        game.set_per_hill_hive(true);
+        game.set_spawn_burst(3);
+        game.hill_hives.insert((0, 1, 1), 5);
+        game.hive = vec![5];
+
+        game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+
+        // Only 2 more ants fit in the colony, so only 2 are spawned and paid for
+        assert_eq!(game.live_ant_locations().len(), 3);
+        assert_eq!(game.hill_hive(1, 1), 3);
+        assert_eq!(game.hive, vec![3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "The hive can only be split per hill before the game starts.")]
+    fn when_splitting_the_hive_per_hill_after_starting_it_panics() {
+        let map = "\
+            rows 1
+            cols 4
+            players 2
+            m 0a.1";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.start();
+
+        game.set_per_hill_hive(true);
+    }
+
+    #[test]
+    fn when_the_hive_is_split_per_hill_and_a_hill_is_razed_its_food_moves_to_the_nearest_live_hill()
+    {
+        let map = "\
+            rows 2
+            cols 8
+            players 2
+            m 0...0..0
+            m b......1";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_per_hill_hive(true);
+        game.hill_hives.insert((0, 0, 0), 2);
+        game.hill_hives.insert((0, 0, 7), 1);
+        game.hive = vec![3, 0];
+
+        game.map.move_entity((1, 0), (0, 0));
+        game.raze_hills(&game.live_ant_locations());
+
+        // The hill at (0, 4) is nearer than the one at (0, 7), even though it had no food banked
+        assert_eq!(game.hill_hive(0, 0), 0);
+        assert_eq!(game.hill_hive(0, 4), 2);
+        assert_eq!(game.hill_hive(0, 7), 1);
+        assert_eq!(game.hive, vec![3, 0]);
+
+        game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+
+        // The food moved from the razed hill spawns an ant at the hill it was moved to
+        assert_eq!(game.map.get(0, 4).unwrap().name(), "Ant");
+        assert_eq!(game.map.get(0, 7).unwrap().name(), "Ant");
+        assert_eq!(game.hive, vec![1, 0]);
+    }
+
+    #[test]
+    fn when_spawning_ants_from_hive_the_hills_chosen_for_a_player_do_not_depend_on_the_other_players(
+    ) {
+        let map = "\
+            rows 2
+            cols 4
+            players 2
+            m 0.1.
+            m .0.1";
+        let spawned_for_player_1 = |hive: Vec<usize>| {
+            let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+            game.hive = hive;
+            game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+            game.live_ant_locations()
+                .into_iter()
+                .filter(|(player, _, _)| *player == 1)
+                .collect::<Vec<_>>()
+        };
+
+        // Player 0 spawning an ant draws from its own stream, so player 1's choice stays the same
+        assert_eq!(
+            spawned_for_player_1(vec![1, 1]),
+            spawned_for_player_1(vec![0, 1])
+        );
+        assert_eq!(spawned_for_player_1(vec![0, 1]).len(), 1);
+    }
+
+    #[test]
+    fn when_spawning_ants_from_hive_if_there_is_enough_food_and_multiple_hills_one_ant_is_spawned_per_hill(
+    ) {
+        let map = "\
+            rows 2
+            cols 2
+            players 2
+            m 01
+            m 10";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.hive = vec![5, 2];
+
+        game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
+        assert_eq!(game.map.get(0, 0).unwrap().player().unwrap(), 0);
+        assert_eq!(game.map.get(1, 1).unwrap().name(), "Ant");
+        assert_eq!(game.map.get(1, 1).unwrap().player().unwrap(), 0);
+
+        assert_eq!(game.map.get(0, 1).unwrap().name(), "Ant");
+        assert_eq!(game.map.get(0, 1).unwrap().player().unwrap(), 1);
+        assert_eq!(game.map.get(1, 0).unwrap().name(), "Ant");
+        assert_eq!(game.map.get(1, 0).unwrap().player().unwrap(), 1);
+
+        assert_eq!(game.hive, vec![3, 0]);
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].ants_spawned, 2);
+        assert_eq!(turn_stats[1].ants_spawned, 2);
+    }
+
+    #[test]
+    fn when_spawning_ants_from_hive_if_the_player_has_reached_the_max_colony_size_no_more_ants_are_spawned(
+    ) {
+        let map = "\
+            rows 2
+            cols 2
+            players 1
+            m 0a
+            m .a";
+        let max_colony_size = 2;
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, max_colony_size, 0, None);
+        game.hive = vec![5];
+
+        game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
+        assert_eq!(game.hive, vec![5]);
+
+        let turn_stats = game.turn_stats.clone();
+        assert_eq!(turn_stats[0].ants_spawned, 0);
+
+        // Kill one of the ants
+        game.map.get_mut(0, 1).unwrap().set_alive(false);
+        // And spawn again
+        game.spawn_ants_from_hive(&game.live_ants_per_player_count());
+
+        // Now the ant is spawned because there is enough food in the hive and the max colony size is not reached
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
+        assert_eq!(game.hive, vec![4]);
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].ants_spawned, 1);
+    }
+
+    #[test]
+    fn when_spawning_ants_on_a_hill_that_is_occupied_the_existing_entity_is_not_overwritten() {
+        let map = "\
+            rows 2
+            cols 2
+            players 1
+            m A0
+            m ..";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        let id = game.map.get(0, 0).unwrap().id().to_string();
+
+        let spawned = game.spawn_ants(vec![(0, 0, 0), (0, 0, 1), (0, 1, 0)]);
+
+        // Only the free hill gets a new ant, the empty land and the occupied hill are skipped
+        assert_eq!(spawned, 1);
+        assert_eq!(game.map.get(0, 0).unwrap().id(), id);
+        assert_eq!(game.map.get(0, 1).unwrap().name(), "Ant");
+        assert!(game.map.get(1, 0).is_none());
+    }
+
+    #[test]
+    fn when_spawning_ants_from_hive_if_an_ant_is_on_its_own_hill_it_is_not_overwritten_and_no_food_is_spent(
+    ) {
+        let map = "\
+            rows 2
+            cols 2
+            players 1
+            m A.
+            m ..";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        let id = game.map.get(0, 0).unwrap().id().to_string();
+        game.hive = vec![2];
+
+        game.spawn_ants_from_hive(&[1]);
+
+        assert_eq!(game.map.get(0, 0).unwrap().id(), id);
+        assert_eq!(game.map.ants().len(), 1);
+        assert_eq!(game.hive, vec![2]);
+        assert_eq!(game.turn_stats[0].ants_spawned, 0);
+    }
+
+    #[test]
+    fn when_starting_a_game_if_an_ant_is_on_its_own_hill_it_is_not_overwritten() {
+        let map = "\
+            rows 2
+            cols 3
+            players 2
+            m A.1
+            m ...";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        let state = game.start();
+
+        assert_eq!(state.ants[0].len(), 1);
+        assert_eq!((state.ants[0][0].row, state.ants[0][0].col), (0, 0));
+        assert_eq!(state.ants[1].len(), 1);
+    }
+
+    #[test]
+    fn when_spawning_food_on_an_occupied_cell_the_existing_entity_is_not_overwritten() {
+        let map = "\
+            rows 2
+            cols 2
+            players 1
+            m a0
+            m ..";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        game.spawn_food(vec![(0, 0), (0, 1), (1, 1)], 3);
+
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
+        assert_eq!(game.map.get(0, 1).unwrap().name(), "Hill");
+        assert_eq!(game.map.food(), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn when_spawning_food_on_an_occupied_cell_the_next_candidate_is_used() {
+        let map = "\
+            rows 2
+            cols 3
+            players 1
+            m a0.
+            m ...";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        let spawned = game.spawn_food(vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 1)], 2);
+
+        assert_eq!(spawned, 2);
+        assert_eq!(game.map.food(), vec![(0, 2), (1, 0)]);
+    }
+
+    #[test]
+    fn when_spawning_food_randomly_and_nearly_all_land_is_occupied_food_only_spawns_on_the_free_cells(
+    ) {
+        let map = "\
+            rows 3
+            cols 3
+            players 2
+            m a0%
+            m %.b
+            m 1%.";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        game.spawn_food_randomly();
+
+        assert_eq!(game.map.food(), vec![(1, 1), (2, 2)]);
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
+        assert_eq!(game.map.get(0, 1).unwrap().name(), "Hill");
+        assert_eq!(game.map.get(1, 2).unwrap().name(), "Ant");
+        assert_eq!(game.map.get(2, 0).unwrap().name(), "Hill");
+    }
+
+    #[test]
+    fn when_spawning_fixed_food_on_occupied_locations_the_next_locations_are_used() {
+        let map = "\
+            rows 2
+            cols 3
+            players 1
+            m a0.
+            m ...";
+        let mut game = Game::new(map, 4, 5, 1, 2, 1500, 500, 0, None);
+        game.set_food_spawn_strategy(FoodSpawnStrategy::Fixed(vec![
+            (0, 0),
+            (0, 1),
+            (1, 2),
+            (1, 1),
+            (1, 0),
+        ]));
+
+        game.spawn_food_randomly();
+
+        assert_eq!(game.map.food(), vec![(1, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn when_harvesting_food_if_there_are_no_ants_around_the_food_nothing_happens() {
+        let map = "\
+            rows 3
+            cols 3
+            players 1
+            m *..
+            m .*.
+            m ..*";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        game.harvest_food();
+
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Food");
+        assert_eq!(game.map.get(1, 1).unwrap().name(), "Food");
+        assert_eq!(game.map.get(2, 2).unwrap().name(), "Food");
+        assert_eq!(game.hive, vec![0]);
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].food_harvested, 0);
+    }
+
+    #[test]
+    fn when_harvesting_food_if_there_only_ants_from_the_same_player_around_the_food_the_food_is_harvested_into_the_hive(
+    ) {
+        let map = "\
+            rows 3
+            cols 3
+            players 2
+            m *ab
+            m .aa
+            m b.*";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        game.harvest_food();
+
+        assert!(game.map.get(0, 0).is_none());
+        assert!(game.map.get(2, 2).is_none());
+        assert_eq!(game.hive, vec![2, 0]);
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].food_harvested, 2);
+    }
+
+    #[test]
+    fn when_harvesting_food_if_there_are_ants_from_different_players_around_the_food_the_food_is_destroyed(
+    ) {
+        let map = "\
+            rows 3
+            cols 3
+            players 2
+            m *a.
+            m b.a
+            m .b*";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        game.harvest_food();
+
+        assert!(game.map.get(0, 0).is_none());
+        assert!(game.map.get(2, 2).is_none());
+        assert_eq!(game.hive, vec![0, 0]);
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].food_harvested, 0);
+        assert_eq!(turn_stats[1].food_harvested, 0);
+    }
+
+    #[test]
+    fn when_harvesting_food_with_majority_contested_food_the_player_with_more_ants_around_harvests_it(
+    ) {
+        let map = "\
+            rows 3
+            cols 3
+            players 2
+            m .a.
+            m a*b
+            m ...";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_contested_food(ContestedFood::Majority);
+
+        game.harvest_food();
+
+        assert!(game.map.get(1, 1).is_none());
+        assert_eq!(game.hive, vec![1, 0]);
+        assert_eq!(game.turn_stats[0].food_harvested, 1);
+        assert_eq!(game.turn_stats[1].food_harvested, 0);
+    }
+
+    #[test]
+    fn when_harvesting_food_with_majority_contested_food_a_tie_destroys_the_food() {
+        let map = "\
+            rows 3
+            cols 3
+            players 2
+            m *a.
+            m b..
+            m ...";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_contested_food(ContestedFood::Majority);
+
+        game.harvest_food();
+
+        assert!(game.map.get(0, 0).is_none());
+        assert_eq!(game.hive, vec![0, 0]);
+        assert_eq!(game.turn_stats[0].food_harvested, 0);
+        assert_eq!(game.turn_stats[1].food_harvested, 0);
+    }
+
+    #[test]
+    fn when_harvesting_food_with_a_food_radius_of_zero_only_ants_next_to_the_food_harvest_it() {
+        let map = "\
+            rows 3
+            cols 5
+            players 1
+            m *a..*
+            m .....
+            m ...a.";
+        let mut game = Game::new_unchecked(map, 4, 5, 0, 5, 1500, 500, 0, None);
+        // Even with a metric that includes diagonals
+        game.set_distance_metric(DistanceMetric::Chebyshev);
+
+        game.harvest_food();
+
+        assert!(game.map.get(0, 0).is_none());
+        assert_eq!(game.map.get(0, 4).unwrap().name(), "Food");
+        assert_eq!(game.hive, vec![1]);
+    }
+
+    #[test]
+    fn when_harvesting_food_the_food_radius_is_independent_of_the_attack_and_vision_radius() {
+        let map = "\
+            rows 1
+            cols 5
+            players 2
+            m *.a.b";
+        let mut game = Game::new_unchecked(map, 16, 16, 0, 5, 1500, 500, 0, None);
+
+        game.harvest_food();
+
+        // The ant can see the food, but it's too far to harvest it
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Food");
+        assert_eq!(game.hive, vec![0, 0]);
+    }
+
+    #[test]
+    fn when_harvesting_food_with_several_ants_around_the_food_the_ant_with_the_lowest_location_harvests_it(
+    ) {
+        let map = "\
+            rows 1
+            cols 4
+            players 1
+            m a*a*";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        game.harvest_food();
+
+        // The ant on the left harvests the first food, so the ant in the middle is free to harvest the second one
+        assert!(game.map.food().is_empty());
+        assert_eq!(game.hive, vec![2]);
+    }
+
+    #[test]
+    fn when_setting_the_players_their_names_and_colors_are_rendered_and_saved_in_the_replay() {
+        let map = "\
+            rows 1
+            cols 6
+            players 2
+            m 0a..b1";
+        let filename = std::env::temp_dir()
+            .join(format!("ants_replay_{}.json", uuid::Uuid::new_v4()))
+            .to_str()
+            .unwrap()
+            .to_string();
+        let mut game = Game::new(map, 4, 5, 1, 0, 1, 500, 0, Some(filename.clone()));
+        assert!(game.render().contains("Player 0: Score"));
+
+        game.set_players(
+            Some(vec!["Alice".to_string(), "Bob".to_string()]),
+            Some(vec![(255, 0, 0), (0, 0, 255)]),
+        );
+        game.start();
+        game.update(vec![]);
+
+        let output = game.render();
+        assert!(output.contains("\nAlice: Score = "));
+        assert!(output.contains("\nBob: Score = "));
+        assert_eq!(
+            game.render_theme.player_color(1),
+            Color::Rgb { r: 0, g: 0, b: 255 }
+        );
+
+        let replay: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&filename).unwrap()).unwrap();
+        std::fs::remove_file(&filename).unwrap();
+
+        assert_eq!(replay["player_names"], serde_json::json!(["Alice", "Bob"]));
+        assert_eq!(
+            replay["player_colors"],
+            serde_json::json!([[255, 0, 0], [0, 0, 255]])
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected a name for each of the 2 players, got 1.")]
+    fn when_setting_the_players_with_a_name_missing_a_panic_occurs() {
+        let map = "\
+            rows 1
+            cols 6
+            players 2
+            m 0a..b1";
+        let mut game = Game::new(map, 4, 5, 1, 0, 1, 500, 0, None);
+
+        game.set_players(Some(vec!["Alice".to_string()]), None);
+    }
+
+    #[test]
+    fn when_trying_to_set_the_players_with_a_color_missing_an_error_is_returned() {
+        let map = "\
+            rows 1
+            cols 6
+            players 2
+            m 0a..b1";
+        let mut game = Game::new(map, 4, 5, 1, 0, 1, 500, 0, None);
+
+        let result = game.try_set_players(
+            Some(vec!["Alice".to_string(), "Bob".to_string()]),
+            Some(vec![(255, 0, 0)]),
+        );
+
+        assert_eq!(
+            result,
+            Err("Expected a color for each of the 2 players, got 1.".to_string())
+        );
+        // Nothing is set, not even the valid names
+        assert_eq!(game.player_names, vec!["Player 0", "Player 1"]);
+        assert_eq!(game.player_colors, None);
+    }
+
+    #[test]
+    fn when_harvesting_food_harvested_and_destroyed_food_are_logged_as_different_events() {
+        use crate::replay::EventType;
+
+        let map = "\
+            rows 1
+            cols 7
+            players 2
+            m a*b..a*";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 0, 1500, 500, 0, None);
+        let events = Arc::new(Mutex::new(vec![]));
+        let observed = events.clone();
+        game.add_event_observer(move |_, event| {
+            observed.lock().unwrap().push(event.clone());
+        });
+        let harvester = game.map.get(0, 5).unwrap().id().to_string();
+
+        game.harvest_food();
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        // The contested food is destroyed without being stored
+        assert_eq!(events[0].event_type, EventType::Remove);
+        assert_eq!(events[0].location, Some((0, 1)));
+        assert_eq!(events[0].player, None);
+        assert_eq!(events[0].harvester_id, None);
+        // The food next to a single player's ant is stored in their hive
+        assert_eq!(events[1].event_type, EventType::Harvest);
+        assert_eq!(events[1].location, Some((0, 6)));
+        assert_eq!(events[1].player, Some(0));
+        assert_eq!(events[1].harvester_id, Some(harvester));
+        assert_eq!(game.hive, vec![1, 0]);
+    }
+
+    #[test]
+    fn when_harvesting_food_the_replay_records_the_id_of_the_harvesting_ant() {
+        let map = "\
+            rows 1
+            cols 6
+            players 2
+            m 0%a*%1";
+        let filename = std::env::temp_dir()
+            .join(format!("ants_replay_{}.json", uuid::Uuid::new_v4()))
+            .to_str()
+            .unwrap()
+            .to_string();
+        let mut game = Game::new(map, 4, 5, 1, 0, 1, 500, 0, Some(filename.clone()));
+        game.start();
+        let harvester = game.map.get(0, 2).unwrap().id().to_string();
+
+        game.update(vec![]);
+
+        let replay: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&filename).unwrap()).unwrap();
+        std::fs::remove_file(&filename).unwrap();
+
+        let harvests: Vec<&serde_json::Value> = replay["turns"][1]["events"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|event| event["entity"] == "Food" && event["event_type"] == "Harvest")
+            .collect();
+        assert_eq!(harvests.len(), 1);
+        assert_eq!(harvests[0]["location"], serde_json::json!([0, 3]));
+        assert_eq!(harvests[0]["harvester_id"], harvester.as_str());
+    }
+
+    #[test]
+    fn when_harvesting_food_an_ant_can_only_consume_one_food_at_a_time() {
+        let map = "\
+            rows 3
+            cols 3
+            players 1
+            m .*.
+            m *a*
+            m .*.";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        game.harvest_food();
+
+        assert!(game.map.get(0, 1).is_none());
+        assert!(game.map.get(1, 0).is_some());
+        assert!(game.map.get(1, 2).is_some());
+        assert!(game.map.get(2, 1).is_some());
+        assert_eq!(game.hive, vec![1]);
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].food_harvested, 1);
+    }
+
+    #[test]
+    fn when_harvesting_food_with_a_higher_limit_an_ant_can_consume_that_much_food_at_a_time() {
+        let map = "\
+            rows 3
+            cols 3
+            players 1
+            m .*.
+            m *a*
+            m .*.";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_max_food_per_ant_per_turn(2);
+
+        game.harvest_food();
+
+        assert!(game.map.get(0, 1).is_none());
+        assert!(game.map.get(1, 0).is_none());
+        assert!(game.map.get(1, 2).is_some());
+        assert!(game.map.get(2, 1).is_some());
+        assert_eq!(game.hive, vec![2]);
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].food_harvested, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "The maximum food per ant per turn must be at least 1.")]
+    fn when_setting_the_maximum_food_per_ant_per_turn_to_zero_a_panic_occurs() {
+        let map = "\
+            rows 1
+            cols 2
+            players 1
+            m a*";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        game.set_max_food_per_ant_per_turn(0);
+    }
+
+    #[test]
+    fn when_harvesting_food_two_distinct_ants_from_the_same_player_can_consume_food_at_the_same_time(
+    ) {
+        let map = "\
+            rows 3
+            cols 3
+            players 1
+            m .*a
+            m *a*
+            m .*.";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        game.harvest_food();
+
+        assert!(game.map.get(0, 1).is_none());
+        assert!(game.map.get(1, 0).is_none());
+        assert!(game.map.get(1, 2).is_some());
+        assert!(game.map.get(2, 1).is_some());
+        assert_eq!(game.hive, vec![2]);
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].food_harvested, 2);
+    }
+
+    #[test]
+    fn when_harvesting_food_if_the_hive_is_below_the_max_hive_the_food_is_stored() {
+        let map = "\
+            rows 3
+            cols 3
+            players 1
+            m *a.
+            m ...
+            m ...";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_max_hive(Some(2));
+        game.hive = vec![1];
+
+        game.harvest_food();
+
+        assert!(game.map.get(0, 0).is_none());
+        assert_eq!(game.hive, vec![2]);
+    }
+
+    #[test]
+    fn when_harvesting_food_if_the_hive_is_at_the_max_hive_the_food_is_consumed_but_not_stored() {
+        let map = "\
+            rows 3
+            cols 3
+            players 1
+            m *a.
+            m ...
+            m ...";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_max_hive(Some(2));
+        game.hive = vec![2];
+
+        game.harvest_food();
+
+        assert!(game.map.get(0, 0).is_none());
+        assert_eq!(game.hive, vec![2]);
+
+        let turn_stats = game.turn_stats;
+        assert_eq!(turn_stats[0].food_harvested, 1);
+    }
+
+    #[test]
+    fn when_harvesting_food_the_hive_is_included_in_the_game_state() {
+        let map = "\
+            rows 3
+            cols 3
+            players 2
+            m *a.
+            m ...
+            m .b*";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        game.harvest_food();
+        let state = game.game_state(&game.live_ant_locations());
+
+        assert_eq!(state.hive, vec![1, 1]);
+    }
+
+    #[test]
+    fn when_spawning_food_randomly_and_there_is_enough_land_all_food_is_spawned() {
+        let map = "\
+            rows 3
+            cols 3
+            players 1
+            m ...
+            m .a.
+            m ...";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 8, 1500, 500, 0, None);
+
+        game.spawn_food_randomly();
+
+        let food = game.map.food();
+        let expected_food = vec![
+            (0, 0),
+            (0, 1),
+            (0, 2),
+            (1, 0),
+            (1, 2),
+            (2, 0),
+            (2, 1),
+            (2, 2),
+        ];
+
+        assert_eq!(food.len(), 8);
+        assert_eq!(food, expected_food);
+    }
+
+    #[test]
+    fn when_spawning_food_randomly_and_there_is_not_enough_land_not_all_food_is_spawned() {
+        let map = "\
+            rows 3
+            cols 3
+            players 2
+            m aa.
+            m .a.
+            m b.b";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 9, 1500, 500, 0, None);
+
+        game.spawn_food_randomly();
+
+        let food = game.map.food();
+        let expected_food = vec![(0, 2), (1, 0), (1, 2), (2, 1)];
+
+        assert_eq!(food.len(), 4);
+        assert_eq!(food, expected_food);
+    }
+
+    #[test]
+    fn when_spawning_food_randomly_and_there_is_no_land_no_food_is_spawned() {
+        let map = "\
+            rows 3
+            cols 3
+            players 2
+            m aaa
+            m aaa
+            m aba";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 9, 1500, 500, 0, None);
+
+        game.spawn_food_randomly();
+        assert!(game.map.food().is_empty());
+    }
+
+    #[test]
+    fn when_spawning_food_randomly_and_there_is_enough_current_food_no_more_food_is_spawned() {
+        let map = "\
+            rows 3
+            cols 3
+            players 1
+            m *..
+            m .a.
+            m ...";
+        // If we use a `food_rate` of 1, we will only spawn 1 food per turn
+        // and since the map already has 1 food, we should not spawn any more
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 1, 1500, 500, 0, None);
+
+        game.spawn_food_randomly();
+        assert_eq!(game.map.food().len(), 1);
+    }
+
+    #[test]
+    fn when_spawning_food_randomly_and_there_is_some_food_already_only_the_missing_food_is_spawned()
+    {
+        let map = "\
+            rows 3
+            cols 3
+            players 1
+            m *..
+            m .a.
+            m ...";
+        // If we use a `food_rate` of 2, we will spawn 2 food per turn
+        // and since the map already has 1 food, we should spawn 1 more
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 2, 1500, 500, 0, None);
+
+        game.spawn_food_randomly();
+        assert_eq!(game.map.food().len(), 2);
+    }
+
+    #[test]
+    fn when_spawning_food_randomly_with_a_fixed_strategy_food_is_spawned_on_the_free_fixed_locations_in_order(
+    ) {
+        let map = "\
+            rows 3
+            cols 3
+            players 1
+            m ...
+            m .a.
+            m ...";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 2, 1500, 500, 0, None);
+        // The ant location is not free so it should be skipped
+        game.set_food_spawn_strategy(FoodSpawnStrategy::Fixed(vec![
+            (1, 1),
+            (2, 2),
+            (0, 0),
+            (0, 2),
+        ]));
+
+        game.spawn_food_randomly();
+
+        assert_eq!(game.map.food(), vec![(0, 0), (2, 2)]);
+    }
+
+    #[test]
+    fn when_spawning_food_randomly_with_a_fixed_strategy_repeated_locations_are_only_used_once() {
+        let map = "\
+            rows 3
+            cols 3
+            players 1
+            m ...
+            m .a.
+            m ...";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 2, 1500, 500, 0, None);
+        game.set_food_spawn_strategy(FoodSpawnStrategy::Fixed(vec![
+            (2, 2),
+            (2, 2),
+            (2, 2),
+            (0, 0),
+        ]));
+
+        game.spawn_food_randomly();
+
+        assert_eq!(game.map.food(), vec![(0, 0), (2, 2)]);
+    }
+
+    #[test]
+    fn when_starting_a_game_with_different_seeds_the_food_is_spawned_differently() {
+        let map = "\
+            rows 6
+            cols 6
+            players 2
+            m 0.....
+            m ......
+            m ......
+            m ......
+            m ......
+            m .....1";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+        let mut spawn = |seed: u64| {
+            game.start_with_seed(seed);
+            game.spawn_food_randomly();
+            game.map.food()
+        };
+
+        let first = spawn(1);
+        let second = spawn(2);
+
+        assert_ne!(first, second);
+        assert_eq!(spawn(1), first);
+    }
+
+    #[test]
+    fn when_starting_a_game_with_a_seed_it_matches_a_new_game_created_with_that_seed() {
+        let map = "\
+            rows 6
+            cols 6
+            players 2
+            m 0.....
+            m ......
+            m ......
+            m ......
+            m ......
+            m .....1";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+        game.start();
+        let state = game.start_with_seed(42);
+
+        let mut other = Game::new(map, 4, 4, 1, 5, 1500, 500, 42, None);
+
+        assert_eq!(state, other.start());
+    }
+
+    #[test]
+    fn when_creating_a_game_the_ant_ids_do_not_use_the_same_stream_as_the_food() {
+        let map = "\
+            rows 1
+            cols 3
+            players 2
+            m 0.1";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 7, None);
+        assert_ne!(game.id_rng.gen::<u64>(), game.rng.gen::<u64>());
+    }
+
+    #[test]
+    fn when_starting_a_game_with_a_fixed_strategy_the_starting_food_does_not_depend_on_the_seed() {
+        let map = "\
+            rows 4
+            cols 4
+            players 2
+            m %1..
+            m %...
+            m %...
+            m %.0.";
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+        game.set_food_spawn_strategy(FoodSpawnStrategy::Fixed(vec![]));
+        game.start();
+        let food = game.map.food();
+
+        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 42, None);
+        game.set_food_spawn_strategy(FoodSpawnStrategy::Fixed(vec![]));
+        game.start();
+
+        // The first 3 land cells around each hill are always chosen
+        assert_eq!(food, vec![(0, 2), (1, 1), (1, 2), (2, 1), (2, 2), (2, 3)]);
+        assert_eq!(game.map.food(), food);
+    }
+
+    #[test]
+    fn when_spawning_food_randomly_with_a_symmetric_mirror_strategy_food_is_spawned_on_all_symmetric_images(
+    ) {
+        let map = "\
+            rows 4
+            cols 6
+            players 2
+            m %0..1%
+            m ......
+            m .%..%.
+            m ......";
+        let mut game = Game::new(map, 4, 4, 1, 3, 1500, 500, 0, None);
+        game.set_food_spawn_strategy(FoodSpawnStrategy::SymmetricMirror());
+
+        game.spawn_food_randomly();
+
+        // The map is mirrored from left to right so every food must have its mirror image
+        let food = game.map.food();
+        assert_eq!(food.len(), 6);
+        for (row, col) in food.iter() {
+            assert!(food.contains(&(*row, 5 - col)));
+        }
+    }
+
+    #[test]
+    fn when_starting_a_game_with_a_symmetric_mirror_strategy_the_starting_food_is_symmetric() {
+        let map = "\
+            rows 4
+            cols 6
+            players 2
+            m %0..1%
+            m ......
+            m .%..%.
+            m ......";
+        let mut game = Game::new(map, 4, 4, 1, 3, 1500, 500, 0, None);
+        game.set_food_spawn_strategy(FoodSpawnStrategy::SymmetricMirror());
+
+        game.start();
+
+        // 3 food around the hill of each player, mirroring each other
+        let food = game.map.food();
+        assert_eq!(food.len(), 6);
+        for (row, col) in food.iter() {
+            assert!(food.contains(&(*row, 5 - col)));
+        }
+    }
+
+    #[test]
+    fn when_spawning_food_randomly_with_a_far_from_hills_strategy_food_trends_toward_the_far_corner(
+    ) {
+        let map = "\
+            rows 5
+            cols 5
+            players 1
+            m 0....
+            m .....
+            m .....
+            m .....
+            m .....";
+        let mut game = Game::new(map, 4, 5, 1, 1, 1500, 500, 0, None);
+        game.set_food_spawn_strategy(FoodSpawnStrategy::FarFromHills());
+
+        let mut near = 0;
+        let mut far = 0;
+        for _ in 0..200 {
+            game.spawn_food_randomly();
+            let (row, col) = game.map.food()[0];
+            game.map.remove(row, col);
+
+            if row + col <= 2 {
+                near += 1;
+            } else if row + col >= 6 {
+                far += 1;
+            }
+        }
+
+        // The corners have a similar number of land cells, but the far one is much farther from the hill
+        assert!(far > 2 * near, "far: {}, near: {}", far, near);
+    }
+
+    #[test]
+    fn when_spawning_food_randomly_with_a_far_from_hills_strategy_the_same_seed_spawns_the_same_food(
+    ) {
+        let map = "\
+            rows 4
+            cols 6
+            players 2
+            m %0..1%
+            m ......
+            m .%..%.
+            m ......";
+        let spawn = |seed: u64| {
+            let mut game = Game::new(map, 4, 4, 1, 2, 1500, 500, seed, None);
+            game.set_food_spawn_strategy(FoodSpawnStrategy::FarFromHills());
+            game.spawn_food_randomly();
+            game.map.food()
+        };
+
+        assert_eq!(spawn(7), spawn(7));
+    }
+
+    #[test]
+    fn when_spawning_food_randomly_with_a_min_distance_to_ants_no_food_is_next_to_an_ant_if_avoidable(
+    ) {
+        let map = "\
+            rows 5
+            cols 5
+            players 1
+            m 0....
+            m .....
+            m ..a..
+            m .....
+            m .....";
+        let spawn = |food_rate: usize| {
+            let mut game = Game::new(map, 4, 5, 1, food_rate, 1500, 500, 0, None);
+            game.set_min_food_ant_distance(2);
+            game.spawn_food_randomly();
+            game.map.food()
+        };
+        let next_to_ant = |(row, col): &(usize, usize)| row.abs_diff(2).max(col.abs_diff(2)) <= 1;
+
+        // The 15 land cells on the border are far enough from the ant
+        for food_rate in 1..=15 {
+            let food = spawn(food_rate);
+            assert_eq!(food.len(), food_rate);
+            assert!(!food.iter().any(next_to_ant));
+        }
+
+        // Once they are taken, the food spawns next to the ant
+        let food = spawn(17);
+        assert_eq!(food.len(), 17);
+        assert_eq!(food.iter().filter(|cell| next_to_ant(cell)).count(), 2);
+    }
+
+    #[test]
+    fn when_creating_a_game_with_an_rng_the_food_only_depends_on_the_state_of_the_rng() {
+        let map = "\
+            rows 4
+            cols 6
+            players 2
+            m %0..1%
+            m ......
+            m .%..%.
+            m ......";
+        let spawn = |seed: u64, rng: Option<StdRng>| {
+            let mut game = Game::new(map, 4, 4, 1, 2, 1500, 500, seed, None);
+            if let Some(rng) = rng {
+                game = game.with_rng(rng);
             }
+            game.spawn_food_randomly();
+            game.map.food()
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+        rng.gen::<u64>();
+
+        assert_eq!(spawn(1, Some(rng.clone())), spawn(2, Some(rng.clone())));
+        // A generator seeded like the game places the same food as the game's own
+        assert_eq!(spawn(3, None), spawn(4, Some(StdRng::seed_from_u64(3))));
+    }
+
+    #[test]
+    fn when_spawning_food_randomly_with_a_spread_strategy_the_food_has_lower_spatial_variance() {
+        // A large open map with a single hill in the corner
+        let mut map = "rows 40\ncols 40\nplayers 1\nm 0".to_string() + &".".repeat(39);
+        for _ in 1..40 {
+            map += &format!("\nm {}", ".".repeat(40));
+        }
+
+        // The variance of the number of food in each 10x10 region of the map, over a few seeds
+        let variance = |strategy: FoodSpawnStrategy| {
+            (0..10)
+                .map(|seed| {
+                    let mut game = Game::new(&map, 4, 5, 1, 16, 1500, 500, seed, None);
+                    game.set_food_spawn_strategy(strategy.clone());
+                    game.spawn_food_randomly();
+
+                    let mut counts = [0.0; 16];
+                    for (row, col) in game.map.food() {
+                        counts[(row / 10) * 4 + col / 10] += 1.0;
+                    }
+                    let mean = counts.iter().sum::<f64>() / counts.len() as f64;
+                    counts
+                        .iter()
+                        .map(|count| (count - mean).powi(2))
+                        .sum::<f64>()
+                        / counts.len() as f64
+                })
+                .sum::<f64>()
+        };
+
+        let random = variance(FoodSpawnStrategy::Random());
+        let spread = variance(FoodSpawnStrategy::Spread());
+
+        assert!(
+            spread < random / 2.0,
+            "spread: {}, random: {}",
+            spread,
+            random
+        );
+    }
+
+    #[test]
+    fn when_spawning_food_randomly_with_a_spread_strategy_the_food_goes_to_the_buckets_with_the_least_food(
+    ) {
+        let map = "\
+            rows 2
+            cols 4
+            players 1
+            m 0...
+            m ...*";
+
+        // A single food to spawn makes buckets of 3x3, so the last column is its own bucket, which has food already
+        for seed in 0..20 {
+            let mut game = Game::new(map, 4, 5, 1, 2, 1500, 500, seed, None);
+            game.set_food_spawn_strategy(FoodSpawnStrategy::Spread());
+
+            game.spawn_food_randomly();
+
+            let food = game.map.food();
+            assert_eq!(food.len(), 2);
+            assert!(food.iter().all(|cell| *cell == (1, 3) || cell.1 < 3));
+        }
+    }
+
+    #[test]
+    fn when_checking_for_endgame_if_the_food_is_not_being_gathered_the_game_ends() {
+        let map = "\
+            rows 3
+            cols 3
+            players 1
+            m *a*
+            m ***
+            m .**";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.cutoff_threshold = 1;
+
+        game.check_for_endgame(&game.live_ant_locations());
+
+        assert!(game.finished);
+        assert_eq!(game.finished_reason, Some(FinishedReason::TooMuchFood));
+    }
+
+    #[test]
+    fn when_checking_for_endgame_with_reachable_food_only_unreachable_food_does_not_end_the_game() {
+        let map = "\
+            rows 4
+            cols 6
+            players 2
+            m 0.%***
+            m a.%***
+            m .*%***
+            m b1%***";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.cutoff_threshold = 1;
+        game.set_reachable_food_only(true);
+        game.compute_initial_scores();
+
+        game.check_for_endgame(&game.live_ant_locations());
+
+        // Only the food next to the ant counts, which is not enough food to end the game
+        assert!(!game.finished);
+        assert_eq!(game.turns_with_too_much_food, 0);
+
+        // Without the option, the food behind the water ends the game
+        game.set_reachable_food_only(false);
+        game.check_for_endgame(&game.live_ant_locations());
+
+        assert!(game.finished);
+        assert_eq!(game.finished_reason, Some(FinishedReason::TooMuchFood));
+    }
+
+    #[test]
+    fn when_checking_for_endgame_if_the_players_are_walled_off_without_food_for_the_patience_turns_the_game_ends_in_a_stalemate(
+    ) {
+        let map = "\
+            rows 3
+            cols 5
+            players 2
+            m 0.%.1
+            m a.%.b
+            m *.%..";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_stalemate_patience(Some(2));
+        game.compute_initial_scores();
+        // Player 0 can still reach its food for now
+        game.check_for_endgame(&game.live_ant_locations());
+        assert_eq!(game.turns_in_stalemate, 0);
+
+        game.map.remove(2, 0);
+        game.check_for_endgame(&game.live_ant_locations());
+        assert!(!game.finished);
+
+        game.check_for_endgame(&game.live_ant_locations());
+        assert!(game.finished);
+        assert_eq!(game.finished_reason, Some(FinishedReason::Stalemate));
+        assert!(game.winner.is_none());
+    }
+
+    #[test]
+    fn when_checking_for_endgame_if_enemies_can_reach_each_other_there_is_no_stalemate() {
+        let map = "\
+            rows 3
+            cols 5
+            players 2
+            m 0.%.1
+            m a...b
+            m ..%..";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_stalemate_patience(Some(1));
+        game.compute_initial_scores();
+
+        game.check_for_endgame(&game.live_ant_locations());
+
+        assert!(!game.finished);
+        assert_eq!(game.turns_in_stalemate, 0);
+    }
+
+    #[test]
+    fn when_checking_for_endgame_if_only_one_player_remains_with_ants_the_game_ends() {
+        let map = "\
+            rows 3
+            cols 3
+            players 2
+            m a..
+            m aa.
+            m ...";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        game.check_for_endgame(&game.live_ant_locations());
+
+        assert!(game.finished);
+        assert_eq!(game.finished_reason, Some(FinishedReason::LoneSurvivor));
+        assert_eq!(game.winner, Some(0));
+    }
+
+    #[test]
+    fn when_finding_the_nearest_food_within_the_field_of_vision_only_visible_food_is_considered() {
+        let map = "\
+            rows 1
+            cols 6
+            players 1
+            m a...*.";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+
+        assert_eq!(game.nearest_food(0, 0, false), Some((0, 4)));
+        assert_eq!(game.nearest_food(0, 0, true), None);
+
+        game.set_fov_radius2_per_player(vec![16]);
+        assert_eq!(game.nearest_food(0, 0, true), Some((0, 4)));
+        // There is no ant to see from
+        assert_eq!(game.nearest_food(0, 1, true), None);
+    }
+
+    #[test]
+    fn when_updating_the_game_the_state_matches_the_live_ants_on_the_map_at_the_end_of_the_turn() {
+        let map = "\
+            rows 4
+            cols 6
+            players 2
+            m 0a....
+            m ..a...
+            m ...b..
+            m ....b1";
+        let mut game = Game::new(map, 4, 5, 1, 1, 1500, 500, 0, None);
+        game.start();
 
-            // If this player can surpass the leader, the rank isn't stabilized yet
-            if scores[player] > *leader_score {
-                return (false, None);
+        // The ants in the middle fight, while the others harvest food and spawn new ants
+        for _ in 0..3 {
+            let state = game.update(vec![]);
+            let expected = game.game_state(&game.live_ant_locations());
+
+            for player in 0..2 {
+                let ants = |state: &GameState| {
+                    state.ants[player]
+                        .iter()
+                        .map(|ant| (ant.id.clone(), ant.row, ant.col))
+                        .collect::<Vec<_>>()
+                };
+                assert_eq!(ants(&state), ants(&expected));
             }
         }
-
-        // If no player can surpass the leader, the rank is stabilized
-        (true, Some(leader))
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use std::vec;
-
-    use super::*;
-    use crate::entities::Food;
 
     #[test]
-    fn when_starting_a_game_the_map_is_reset() {
+    fn when_moving_onto_food_with_step_to_harvest_the_food_is_banked_and_the_ant_takes_its_cell() {
         let map = "\
-            rows 4
-            cols 4
+            rows 1
+            cols 5
             players 2
-            m %1.%
-            m %..%
-            m %..%
-            m %.0%";
-        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+            m a*.*b";
+        let mut game = Game::new_unchecked(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.set_step_to_harvest(true);
 
-        game.map.set(0, 0, Box::new(Food));
-        game.start();
+        game.move_ants(&[
+            Action::new(0, 0, Direction::East),
+            Action::new(0, 4, Direction::West),
+        ]);
 
-        assert_eq!(game.map.get(0, 0).unwrap().name(), "Water");
+        assert_eq!(game.map.get(0, 1).unwrap().name(), "Ant");
+        assert_eq!(game.map.get(0, 1).unwrap().player(), Some(0));
+        assert_eq!(game.map.get(0, 3).unwrap().name(), "Ant");
+        assert_eq!(game.map.get(0, 3).unwrap().player(), Some(1));
+        assert!(game.map.get(0, 0).is_none());
+        assert!(game.map.food().is_empty());
+        assert_eq!(game.hive, vec![1, 1]);
+        assert_eq!(game.turn_stats[0].food_harvested, 1);
+        assert_eq!(game.turn_stats[1].food_harvested, 1);
     }
 
     #[test]
-    fn when_starting_a_game_ants_are_spawned_on_ant_hills() {
+    fn when_moving_onto_food_with_carry_food_the_ant_picks_it_up_only_if_it_is_not_carrying() {
         let map = "\
-            rows 4
+            rows 1
             cols 4
-            players 2
-            m %1.%
-            m %..%
-            m %..%
-            m %.0%";
-        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+            players 1
+            m a**.";
+        let mut game = Game::new_unchecked(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.set_carry_food(true);
 
-        game.start();
+        game.move_ants(&[Action::new(0, 0, Direction::East)]);
 
         let ant = game.map.get(0, 1).unwrap();
         assert_eq!(ant.name(), "Ant");
-        assert_eq!(ant.player().unwrap(), 1);
-        assert!(ant.alive().unwrap());
-        assert_eq!(ant.on_ant_hill().as_ref().unwrap().player().unwrap(), 1);
+        assert_eq!(ant.carrying(), Some(true));
+        assert_eq!(game.map.food(), vec![(0, 2)]);
+        // The food is not stored until it's delivered
+        assert_eq!(game.hive, vec![0]);
+        assert_eq!(game.turn_stats[0].food_harvested, 0);
 
-        let ant = game.map.get(3, 2).unwrap();
-        assert_eq!(ant.name(), "Ant");
-        assert_eq!(ant.player().unwrap(), 0);
-        assert!(ant.alive().unwrap());
-        assert_eq!(ant.on_ant_hill().as_ref().unwrap().player().unwrap(), 0);
+        // Ants carry one food at a time, so the next food blocks the ant
+        game.move_ants(&[Action::new(0, 1, Direction::East)]);
+
+        assert_eq!(game.map.get(0, 1).unwrap().name(), "Ant");
+        assert_eq!(game.map.food(), vec![(0, 2)]);
     }
 
     #[test]
-    fn when_starting_a_game_food_is_spawned_around_land_locations_for_each_ant_hill() {
+    fn when_carrying_food_to_its_own_hill_the_food_is_delivered_to_the_hive() {
         let map = "\
-            rows 4
-            cols 4
+            rows 1
+            cols 8
             players 2
-            m %1.%
-            m %..%
-            m %..%
-            m %.0%";
-        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
-
+            m .*0..*.1";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.set_carry_food(true);
+        game.set_starting_food_per_hill(0);
         game.start();
+        let id = game.map.get(0, 2).unwrap().id().to_string();
 
-        // The map has 1 ant hill at (0, 1) for player 1 with 3 land cells around it
-        // So food should be spawned at (0, 2), (1, 1), and (1, 2)
-        assert_eq!(game.map.get(0, 2).as_ref().unwrap().name(), "Food");
-        assert_eq!(game.map.get(1, 1).as_ref().unwrap().name(), "Food");
-        assert_eq!(game.map.get(1, 2).as_ref().unwrap().name(), "Food");
+        // The ant next to the food doesn't harvest it
+        let state = game.update(vec![]);
+        assert_eq!(state.hive, vec![0, 0]);
+        assert_eq!(game.map.food(), vec![(0, 1), (0, 5)]);
 
-        // The map has 1 ant hill at (3, 2) for player 0 with 3 land cells around it
-        // So food should be spawned at (2, 1), (2, 2), and (3, 1)
-        assert_eq!(game.map.get(2, 1).as_ref().unwrap().name(), "Food");
-        assert_eq!(game.map.get(2, 2).as_ref().unwrap().name(), "Food");
-        assert_eq!(game.map.get(3, 1).as_ref().unwrap().name(), "Food");
+        let state = game.update(vec![Action::new(0, 2, Direction::West)]);
+        assert_eq!(state.hive, vec![0, 0]);
+        assert!(state.ants[0].iter().any(|ant| ant.id == id && ant.carrying));
+
+        let state = game.update(vec![Action::new(0, 1, Direction::East)]);
+        assert_eq!(state.hive, vec![1, 0]);
+        assert_eq!(state.turn_stats[0].food_harvested, 1);
+        assert!(state.ants[0]
+            .iter()
+            .any(|ant| ant.id == id && !ant.carrying));
     }
 
     #[test]
-    fn when_starting_a_game_the_correct_game_state_is_returned() {
+    fn when_an_ant_carrying_food_dies_the_food_is_lost() {
         let map = "\
-            rows 4
-            cols 4
+            rows 1
+            cols 6
             players 2
-            m %1.%
-            m %..%
-            m %..%
-            m %.0%";
-        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+            m A*.b..";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_carry_food(true);
 
-        let state = game.start();
+        game.move_ants(&[Action::new(0, 0, Direction::East)]);
+        game.attack();
+        game.deliver_food();
 
-        assert_eq!(state.turn, 0);
-        assert!(!state.finished);
-        assert!(state.finished_reason.is_none());
+        assert_eq!(game.map.get(0, 1).unwrap().carrying(), Some(true));
+        assert_eq!(game.map.get(0, 1).unwrap().alive(), Some(false));
+        assert!(game.map.food().is_empty());
+        assert_eq!(game.hive, vec![0, 0]);
+    }
 
-        // The map has 2 players
-        assert_eq!(state.scores, vec![1, 1]);
-        assert_eq!(state.ants.len(), 2);
+    #[test]
+    fn when_moving_onto_food_without_step_to_harvest_the_ant_does_not_move() {
+        let map = "\
+            rows 1
+            cols 3
+            players 1
+            m a*.";
+        let mut game = Game::new_unchecked(map, 4, 1, 1, 0, 1500, 500, 0, None);
 
-        // The map has 1 ant hill at (3, 2) for player 0
-        assert_eq!(state.ants[0].len(), 1);
-        assert_eq!(state.ants[0][0].row, 3);
-        assert_eq!(state.ants[0][0].col, 2);
-        assert_eq!(state.ants[0][0].player, 0);
-        assert!(state.ants[0][0].alive);
-        // Given the fov radius of 2, the ant at (3, 2) should see 8 entities
-        assert_eq!(state.ants[0][0].field_of_vision.len(), 8);
-        // Let's check that it was able to see the water next to it at (3, 3)
-        assert!(state.ants[0][0]
-            .field_of_vision
-            .iter()
-            .any(|entity| entity.name == "Water" && entity.row == 3 && entity.col == 3));
-        // Let's also check that it was able to see the ant hill where it is standing at (3, 2)
-        assert!(state.ants[0][0]
-            .field_of_vision
-            .iter()
-            .any(|entity| entity.name == "Hill"
-                && entity.row == 3
-                && entity.col == 2
-                && entity.player.unwrap() == 0
-                && entity.alive.unwrap()));
+        game.move_ants(&[Action::new(0, 0, Direction::East)]);
 
-        // The map has 1 ant hill at (0, 1) for player 1
-        assert_eq!(state.ants[1].len(), 1);
-        assert_eq!(state.ants[1][0].row, 0);
-        assert_eq!(state.ants[1][0].col, 1);
-        assert_eq!(state.ants[1][0].player, 1);
-        assert!(state.ants[1][0].alive);
-        // Given the fov radius of 2, the ant at (0, 1) should see 8 entities
-        assert_eq!(state.ants[1][0].field_of_vision.len(), 8);
-        // Let's check that it was able to see the water next to it at (0, 0)
-        assert!(state.ants[1][0]
-            .field_of_vision
-            .iter()
-            .any(|entity| entity.name == "Water" && entity.row == 0 && entity.col == 0));
-        // Let's also check that it was able to see the ant hill where it is standing at (0, 1)
-        assert!(state.ants[1][0]
-            .field_of_vision
-            .iter()
-            .any(|entity| entity.name == "Hill"
-                && entity.row == 0
-                && entity.col == 1
-                && entity.player.unwrap() == 1
-                && entity.alive.unwrap()));
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
+        assert_eq!(game.map.get(0, 1).unwrap().name(), "Food");
+        assert_eq!(game.hive, vec![0]);
     }
 
     #[test]
-    fn when_starting_a_game_the_initial_scores_are_computed_as_the_number_of_ant_hills_per_player()
-    {
+    fn when_playing_two_games_with_the_same_seed_the_replays_are_identical() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
         let map = "\
-            rows 4
-            cols 4
+            rows 6
+            cols 6
             players 2
-            m %1.%
-            m %1.%
-            m %..%
-            m %00%";
-        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+            m 0....%
+            m ..*...
+            m .%..%.
+            m ...%..
+            m ..*...
+            m %....1";
+        let play = || {
+            let filename = std::env::temp_dir()
+                .join(format!("ants_replay_{}.json", uuid::Uuid::new_v4()))
+                .to_str()
+                .unwrap()
+                .to_string();
+            let mut game = Game::new(map, 8, 2, 1, 2, 50, 500, 42, Some(filename.clone()));
+            let mut rng = StdRng::seed_from_u64(7);
+            let mut state = game.start();
+
+            while !state.finished {
+                let actions = state
+                    .ants
+                    .iter()
+                    .flatten()
+                    .map(|ant| Action::new(ant.row, ant.col, rng.gen()))
+                    .collect();
+                state = game.update(actions);
+            }
 
-        game.start();
+            let replay = std::fs::read(&filename).unwrap();
+            std::fs::remove_file(&filename).unwrap();
 
-        assert_eq!(game.scores, vec![2, 2]);
+            let mut hasher = DefaultHasher::new();
+            replay.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        assert_eq!(play(), play());
     }
 
     #[test]
-    #[should_panic(expected = "Game has not started! Call `start` to start the game.")]
-    fn when_updating_a_game_that_has_not_started_a_panic_occurs() {
+    fn when_saving_a_replay_only_the_spawns_of_the_initial_setup_are_marked_as_initial() {
         let map = "\
-            rows 4
-            cols 4
+            rows 3
+            cols 5
             players 2
-            m %1.%
-            m %..%
-            m %..%
-            m %.0%";
-        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+            m 0a.b1
+            m .....
+            m .....";
+        let filename = std::env::temp_dir()
+            .join(format!("ants_replay_{}.json", uuid::Uuid::new_v4()))
+            .to_str()
+            .unwrap()
+            .to_string();
+        let mut game = Game::new(map, 4, 1, 1, 1, 1, 500, 0, Some(filename.clone()));
+        game.start();
         game.update(vec![]);
+
+        let replay: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&filename).unwrap()).unwrap();
+        std::fs::remove_file(&filename).unwrap();
+
+        let spawns = |turn: usize| {
+            replay["turns"][turn]["events"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|event| event["event_type"] == "Spawn")
+                .cloned()
+                .collect::<Vec<serde_json::Value>>()
+        };
+
+        assert!(!spawns(0).is_empty());
+        assert!(spawns(0).iter().all(|event| event["initial"] == true));
+        assert!(!spawns(1).is_empty());
+        assert!(spawns(1).iter().all(|event| event.get("initial").is_none()));
     }
 
     #[test]
-    #[should_panic(expected = "Game is finished! Call `start` to start a new game.")]
-    fn when_updating_a_game_that_has_finished_a_panic_occurs() {
+    fn when_the_game_ends_the_replay_contains_the_final_standings() {
         let map = "\
-            rows 4
-            cols 4
+            rows 1
+            cols 5
             players 2
-            m %1.%
-            m %..%
-            m %..%
-            m %.0%";
-        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
-        game.started = true;
-        game.finished = true;
+            m 0a.b1";
+        let filename = std::env::temp_dir()
+            .join(format!("ants_replay_{}.json", uuid::Uuid::new_v4()))
+            .to_str()
+            .unwrap()
+            .to_string();
+        let mut game = Game::new(map, 4, 1, 1, 0, 1, 500, 0, Some(filename.clone()));
+        game.start();
 
+        // The game ends after the first turn since the turn limit is 1
         game.update(vec![]);
+
+        let replay: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&filename).unwrap()).unwrap();
+        std::fs::remove_file(&filename).unwrap();
+
+        assert_eq!(replay["schema_version"], crate::SCHEMA_VERSION);
+        assert_eq!(replay["finished_reason"], "TurnLimitReached");
+        assert_eq!(replay["result"]["reason"], "TurnLimitReached");
+        assert_eq!(replay["result"]["scores"], serde_json::json!([1, 1]));
+        assert_eq!(replay["result"]["ants"], serde_json::json!([2, 2]));
+        assert_eq!(replay["result"]["hive"], serde_json::json!([0, 0]));
+        assert_eq!(replay["result"]["ranking"], serde_json::json!([0, 1]));
+        assert_eq!(replay["result"]["statistics"][0]["ants_spawned"], 1);
     }
 
     #[test]
-    fn when_removing_dead_ants_all_dead_ants_are_removed() {
+    fn when_a_player_skips_a_turn_their_ants_hold_position_and_they_are_penalized() {
         let map = "\
-            rows 2
-            cols 2
-            players 1
-            m 0.
-            m a.";
-        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+            rows 1
+            cols 8
+            players 2
+            m 0a....b1";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.set_skip_penalty(1);
+        game.start();
 
-        assert!(game.map.get(1, 0).unwrap().alive().unwrap());
-        game.map.get_mut(1, 0).unwrap().set_alive(false);
+        game.skip_player_turn(1);
+        let state = game.update(vec![
+            Action::new(0, 1, Direction::East),
+            Action::new(0, 6, Direction::West),
+        ]);
 
-        game.remove_dead_ants();
+        assert_eq!(game.map.get(0, 2).unwrap().player(), Some(0));
+        assert_eq!(game.map.get(0, 6).unwrap().player(), Some(1));
+        assert!(game.map.get(0, 5).is_none());
+        assert_eq!(state.scores, vec![1, 0]);
 
-        assert!(game.map.get(1, 0).is_none());
+        // The skip only lasts for one turn
+        assert!(game.skipped_players.is_empty());
     }
 
     #[test]
-    fn when_removing_dead_ants_if_there_are_no_dead_ants_no_ants_are_removed() {
+    fn when_an_ant_is_idle_for_the_idle_patience_with_an_empty_hive_it_starves() {
         let map = "\
-            rows 2
-            cols 2
-            players 1
-            m 0.
-            m a.";
-        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
-
-        assert!(game.map.get(1, 0).unwrap().alive().unwrap());
+            rows 1
+            cols 8
+            players 2
+            m 0......1";
+        let mut game = Game::new(map, 1, 1, 1, 0, 1500, 500, 0, None);
+        game.set_starting_food_per_hill(0);
+        game.set_idle_patience(2);
+        game.start();
 
-        game.remove_dead_ants();
+        // Only the ant of player 1 moves, so the ant of player 0 is idle
+        let state = game.update(vec![Action::new(0, 7, Direction::West)]);
+        assert_eq!(state.ants[0][0].idle_turns, 1);
+        assert!(state.ants[0][0].alive);
+        assert_eq!(state.ants[1][0].idle_turns, 0);
 
-        assert!(game.map.get(1, 0).is_some());
+        let state = game.update(vec![Action::new(0, 6, Direction::West)]);
+        assert!(state.ants[0].is_empty());
+        assert_eq!(state.ants[1].len(), 1);
+        assert_eq!(state.turn_stats[0].ants_lost, 1);
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
     }
 
     #[test]
-    fn when_removing_dead_ants_if_a_dead_ant_is_on_a_hill_the_hill_is_restored() {
+    fn when_an_ant_is_idle_for_the_idle_patience_it_eats_from_the_hive_and_moving_resets_its_count()
+    {
         let map = "\
-            rows 2
-            cols 2
-            players 1
-            m A.
-            m ..";
-        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+            rows 1
+            cols 8
+            players 2
+            m 0......1";
+        let mut game = Game::new(map, 1, 1, 1, 0, 1500, 500, 0, None);
+        game.set_starting_food_per_hill(0);
+        game.set_idle_patience(2);
+        game.start();
+        game.hive[0] = 1;
 
-        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
-        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
+        game.update(vec![Action::new(0, 7, Direction::West)]);
+        let state = game.update(vec![Action::new(0, 6, Direction::West)]);
 
-        game.map.get_mut(0, 0).unwrap().set_alive(false);
+        // The ant ate the food in the hive instead of starving
+        assert!(state.ants[0][0].alive);
+        assert_eq!(state.ants[0][0].idle_turns, 0);
+        assert_eq!(state.hive[0], 0);
 
-        game.remove_dead_ants();
+        let state = game.update(vec![Action::new(0, 5, Direction::West)]);
+        assert_eq!(state.ants[0][0].idle_turns, 1);
 
-        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
-        assert_eq!(game.map.get(0, 0).unwrap().player().unwrap(), 0);
+        // Moving resets the count, so the ant doesn't starve on the next turn
+        let state = game.update(vec![
+            Action::new(0, 0, Direction::East),
+            Action::new(0, 4, Direction::West),
+        ]);
+        assert!(state.ants[0][0].alive);
+        assert_eq!(state.ants[0][0].idle_turns, 0);
     }
 
     #[test]
-    fn when_removing_dead_ants_if_a_dead_ant_is_on_enemy_hill_the_hill_is_restored() {
+    #[should_panic(expected = "Invalid player 2, the game only has 2 players.")]
+    fn when_skipping_the_turn_of_an_invalid_player_it_panics() {
         let map = "\
-            rows 2
+            rows 1
             cols 2
             players 2
-            m 0.
-            m b.";
-        let mut game = Game::new(map, 4, 4, 1, 5, 1500, 500, 0, None);
+            m 01";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.skip_player_turn(2);
+    }
 
-        // Move the ant to the enemy hill
-        game.map.move_entity((1, 0), (0, 0));
+    #[test]
+    fn when_a_player_is_eliminated_their_ants_and_hills_are_removed_and_the_game_continues() {
+        let map = "\
+            rows 1
+            cols 11
+            players 3
+            m 0...1...2..";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.start();
 
-        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
-        assert_eq!(game.map.get(0, 0).unwrap().player().unwrap(), 1);
-        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
+        game.eliminate_player(2);
 
-        game.map.get_mut(0, 0).unwrap().set_alive(false);
+        assert!(game.map.get(0, 8).is_none());
+        assert_eq!(game.statistics[2].ants_lost, 1);
 
-        game.remove_dead_ants();
+        let state = game.update(vec![]);
 
-        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
-        // The hill should be restored to the original owner
-        assert_eq!(game.map.get(0, 0).unwrap().player().unwrap(), 0);
+        assert!(!state.finished);
+        assert_eq!(state.ants.len(), 3);
+        assert_eq!(state.ants[0].len(), 1);
+        assert_eq!(state.ants[1].len(), 1);
+        assert!(state.ants[2].is_empty());
     }
 
     #[test]
-    fn when_attacking_on_a_one_on_one_battle_both_ants_die() {
+    fn when_a_player_is_eliminated_and_only_one_player_remains_the_game_ends() {
         let map = "\
-            rows 3
-            cols 5
+            rows 1
+            cols 8
             players 2
-            m .....
-            m .a.b.
-            m .....";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+            m 0......1";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.start();
 
-        assert!(game.map.get(1, 1).unwrap().alive().unwrap());
-        assert!(game.map.get(1, 3).unwrap().alive().unwrap());
+        game.eliminate_player(1);
+        let state = game.update(vec![]);
 
-        game.attack();
+        assert!(state.finished);
+        assert_eq!(state.finished_reason, Some(FinishedReason::LoneSurvivor));
+        assert_eq!(state.winner, Some(0));
+        assert!(state.ants[1].is_empty());
+    }
 
-        assert!(!game.map.get(1, 1).unwrap().alive().unwrap());
-        assert!(!game.map.get(1, 3).unwrap().alive().unwrap());
+    #[test]
+    fn when_a_player_is_eliminated_an_enemy_ant_on_their_hill_keeps_standing_on_land() {
+        let map = "\
+            rows 1
+            cols 8
+            players 3
+            m 0..1...2";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.start();
+        game.map.set(
+            0,
+            3,
+            Box::new(Ant::new(
+                "a".to_string(),
+                0,
+                true,
+                Some(Box::new(Hill::new(1, true))),
+            )),
+        );
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].ants_killed, 1);
-        assert_eq!(turn_stats[0].ants_lost, 1);
-        assert_eq!(turn_stats[1].ants_killed, 1);
-        assert_eq!(turn_stats[1].ants_lost, 1);
+        game.eliminate_player(1);
+
+        let ant = game.map.get(0, 3).unwrap();
+        assert_eq!(ant.player(), Some(0));
+        assert!(ant.on_ant_hill().is_none());
     }
 
     #[test]
-    fn when_attacking_on_a_two_on_one_battle_ant_a_dies() {
+    fn when_tracking_explored_cells_they_accumulate_across_turns() {
         let map = "\
-            rows 3
-            cols 5
+            rows 1
+            cols 8
             players 2
-            m ...b.
-            m .a...
-            m ...b.";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
-
-        assert!(game.map.get(0, 3).unwrap().alive().unwrap());
-        assert!(game.map.get(1, 1).unwrap().alive().unwrap());
-        assert!(game.map.get(2, 3).unwrap().alive().unwrap());
+            m 0......1";
+        let mut game = Game::new(map, 1, 1, 1, 0, 1500, 500, 0, None);
+        game.set_track_explored(true);
 
-        game.attack();
+        let state = game.start();
+        assert_eq!(
+            state.explored[0],
+            vec![vec![true, true, false, false, false, false, false, false]]
+        );
+        assert_eq!(
+            state.explored[1],
+            vec![vec![false, false, false, false, false, false, true, true]]
+        );
 
-        assert!(game.map.get(0, 3).unwrap().alive().unwrap());
-        assert!(!game.map.get(1, 1).unwrap().alive().unwrap());
-        assert!(game.map.get(2, 3).unwrap().alive().unwrap());
+        // Make room for the ant to move away from its hill and the food around it
+        game.map.remove(0, 1);
+        game.map.remove(0, 2);
+        game.update(vec![Action::new(0, 0, Direction::East)]);
+        let state = game.update(vec![Action::new(0, 1, Direction::East)]);
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].ants_killed, 0);
-        assert_eq!(turn_stats[0].ants_lost, 1);
-        assert_eq!(turn_stats[1].ants_killed, 2);
-        assert_eq!(turn_stats[1].ants_lost, 0);
+        // The cells seen in previous turns stay explored
+        assert_eq!(
+            state.explored[0],
+            vec![vec![true, true, true, true, false, false, false, false]]
+        );
     }
 
     #[test]
-    fn when_attacking_on_a_one_on_one_on_one_battle_all_ants_die() {
+    fn when_tracking_explored_cells_after_the_game_started_only_the_cells_seen_from_then_on_are_explored(
+    ) {
         let map = "\
-            rows 3
-            cols 5
-            players 3
-            m ...b.
-            m .a...
-            m ...c.";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
-
-        assert!(game.map.get(0, 3).unwrap().alive().unwrap());
-        assert!(game.map.get(1, 1).unwrap().alive().unwrap());
-        assert!(game.map.get(2, 3).unwrap().alive().unwrap());
-
-        game.attack();
+            rows 1
+            cols 8
+            players 2
+            m 0......1";
+        let mut game = Game::new(map, 1, 1, 1, 0, 1500, 500, 0, None);
+        game.start();
 
-        assert!(!game.map.get(0, 3).unwrap().alive().unwrap());
-        assert!(!game.map.get(1, 1).unwrap().alive().unwrap());
-        assert!(!game.map.get(2, 3).unwrap().alive().unwrap());
+        game.set_track_explored(true);
+        let state = game.update(vec![]);
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].ants_killed, 2);
-        assert_eq!(turn_stats[0].ants_lost, 1);
-        assert_eq!(turn_stats[1].ants_killed, 2);
-        assert_eq!(turn_stats[1].ants_lost, 1);
-        assert_eq!(turn_stats[2].ants_killed, 2);
-        assert_eq!(turn_stats[2].ants_lost, 1);
+        assert_eq!(
+            state.explored[0],
+            vec![vec![true, true, false, false, false, false, false, false]]
+        );
+        assert_eq!(
+            state.explored[1],
+            vec![vec![false, false, false, false, false, false, true, true]]
+        );
     }
 
     #[test]
-    fn when_attacking_on_an_ant_sandwich_battle_the_middle_ant_dies() {
+    fn when_not_tracking_explored_cells_the_game_state_has_none() {
         let map = "\
-            rows 3
-            cols 5
-            players 3
-            m .....
-            m a.b.c
-            m .....";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
-
-        assert!(game.map.get(1, 0).unwrap().alive().unwrap());
-        assert!(game.map.get(1, 2).unwrap().alive().unwrap());
-        assert!(game.map.get(1, 4).unwrap().alive().unwrap());
-
-        game.attack();
+            rows 1
+            cols 2
+            players 2
+            m 01";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
 
-        assert!(game.map.get(1, 0).unwrap().alive().unwrap());
-        assert!(!game.map.get(1, 2).unwrap().alive().unwrap());
-        assert!(game.map.get(1, 4).unwrap().alive().unwrap());
+        let state = game.start();
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].ants_killed, 1);
-        assert_eq!(turn_stats[0].ants_lost, 0);
-        assert_eq!(turn_stats[1].ants_killed, 0);
-        assert_eq!(turn_stats[1].ants_lost, 1);
-        assert_eq!(turn_stats[2].ants_killed, 1);
-        assert_eq!(turn_stats[2].ants_lost, 0);
+        assert!(state.explored.is_empty());
     }
 
     #[test]
-    fn when_attacking_on_a_one_on_two_on_one_battle_ants_b_and_c_die() {
+    fn when_tracking_board_hashes_two_identical_runs_have_the_same_hashes() {
         let map = "\
             rows 3
-            cols 5
-            players 3
-            m ...b.
-            m .a.a.
-            m ...c.";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+            cols 6
+            players 2
+            m 0a..*.
+            m ..%...
+            m ...b1.";
+        let run = || {
+            let filename = std::env::temp_dir()
+                .join(format!("ants_replay_{}.json", uuid::Uuid::new_v4()))
+                .to_str()
+                .unwrap()
+                .to_string();
+            let mut game = Game::new(map, 4, 1, 1, 1, 3, 500, 7, Some(filename.clone()));
+            game.set_track_board_hash(true);
+            let mut hashes = vec![game.start().board_hash.unwrap()];
+            for direction in [Direction::East, Direction::East, Direction::East] {
+                let ants = game.live_ant_locations();
+                let actions = ants
+                    .iter()
+                    .map(|(_, row, col)| Action::new(*row, *col, direction.clone()))
+                    .collect();
+                hashes.push(game.update(actions).board_hash.unwrap());
+            }
 
-        assert!(game.map.get(0, 3).unwrap().alive().unwrap());
-        assert!(game.map.get(1, 1).unwrap().alive().unwrap());
-        assert!(game.map.get(1, 3).unwrap().alive().unwrap());
-        assert!(game.map.get(2, 3).unwrap().alive().unwrap());
+            let replay: serde_json::Value =
+                serde_json::from_str(&std::fs::read_to_string(&filename).unwrap()).unwrap();
+            std::fs::remove_file(&filename).unwrap();
+            let logged: Vec<u64> = replay["turns"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|turn| turn["board_hash"].as_u64().unwrap())
+                .collect();
+            assert_eq!(logged, hashes);
 
-        game.attack();
+            hashes
+        };
 
-        assert!(!game.map.get(0, 3).unwrap().alive().unwrap());
-        assert!(game.map.get(1, 1).unwrap().alive().unwrap());
-        assert!(game.map.get(1, 3).unwrap().alive().unwrap());
-        assert!(!game.map.get(2, 3).unwrap().alive().unwrap());
+        let hashes = run();
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].ants_killed, 4);
-        assert_eq!(turn_stats[0].ants_lost, 0);
-        assert_eq!(turn_stats[1].ants_killed, 1);
-        assert_eq!(turn_stats[1].ants_lost, 1);
-        assert_eq!(turn_stats[2].ants_killed, 1);
-        assert_eq!(turn_stats[2].ants_lost, 1);
+        assert_eq!(run(), hashes);
+        // The ants moved on every turn, so the board changed
+        assert!(hashes.windows(2).all(|pair| pair[0] != pair[1]));
     }
 
     #[test]
-    fn when_attacking_on_a_wall_punch_battle_many_ants_die() {
+    fn when_not_tracking_board_hashes_the_game_state_has_none() {
         let map = "\
-            rows 3
-            cols 9
+            rows 1
+            cols 2
             players 2
-            m aaaaaaaaa
-            m ...bbb...
-            m ...bbb...";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+            m 01";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
 
-        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
-        assert!(game.map.get(0, 1).unwrap().alive().unwrap());
-        assert!(game.map.get(0, 2).unwrap().alive().unwrap());
-        assert!(game.map.get(0, 3).unwrap().alive().unwrap());
-        assert!(game.map.get(0, 4).unwrap().alive().unwrap());
-        assert!(game.map.get(0, 5).unwrap().alive().unwrap());
-        assert!(game.map.get(0, 6).unwrap().alive().unwrap());
-        assert!(game.map.get(0, 7).unwrap().alive().unwrap());
-        assert!(game.map.get(0, 8).unwrap().alive().unwrap());
-        assert!(game.map.get(1, 3).unwrap().alive().unwrap());
-        assert!(game.map.get(1, 4).unwrap().alive().unwrap());
-        assert!(game.map.get(1, 5).unwrap().alive().unwrap());
-        assert!(game.map.get(2, 3).unwrap().alive().unwrap());
-        assert!(game.map.get(2, 4).unwrap().alive().unwrap());
-        assert!(game.map.get(2, 5).unwrap().alive().unwrap());
+        let state = game.start();
 
-        game.attack();
+        assert_eq!(state.board_hash, None);
+        assert_ne!(game.board_hash(), 0);
+    }
 
-        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
-        assert!(game.map.get(0, 1).unwrap().alive().unwrap());
-        assert!(!game.map.get(0, 2).unwrap().alive().unwrap());
-        assert!(!game.map.get(0, 3).unwrap().alive().unwrap());
-        assert!(!game.map.get(0, 4).unwrap().alive().unwrap());
-        assert!(!game.map.get(0, 5).unwrap().alive().unwrap());
-        assert!(!game.map.get(0, 6).unwrap().alive().unwrap());
-        assert!(game.map.get(0, 7).unwrap().alive().unwrap());
-        assert!(game.map.get(0, 8).unwrap().alive().unwrap());
-        assert!(!game.map.get(1, 3).unwrap().alive().unwrap());
-        assert!(!game.map.get(1, 4).unwrap().alive().unwrap());
-        assert!(!game.map.get(1, 5).unwrap().alive().unwrap());
-        assert!(!game.map.get(2, 3).unwrap().alive().unwrap());
-        assert!(game.map.get(2, 4).unwrap().alive().unwrap());
-        assert!(!game.map.get(2, 5).unwrap().alive().unwrap());
+    #[test]
+    fn when_moving_an_ant_multiple_steps_it_stops_at_the_first_obstacle() {
+        let map = "\
+            rows 1
+            cols 6
+            players 1
+            m a...%.";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].ants_killed, 21);
-        assert_eq!(turn_stats[0].ants_lost, 5);
-        assert_eq!(turn_stats[1].ants_killed, 22);
-        assert_eq!(turn_stats[1].ants_lost, 5);
+        game.move_ants(&[Action::new_with_steps(0, 0, Direction::East, 5)]);
+
+        assert_eq!(game.map.get(0, 3).unwrap().name(), "Ant");
+        assert!(game.map.get(0, 0).is_none());
     }
 
     #[test]
-    fn when_attacking_after_a_move_that_kills_ants_the_dead_ants_should_be_ignored() {
+    fn when_moving_an_ant_multiple_steps_it_stops_at_the_edge_of_the_map() {
         let map = "\
             rows 3
-            cols 5
-            players 2
-            m .b...
-            m .ab.
-            m ..b..";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
-
-        assert!(game.map.get(0, 1).unwrap().alive().unwrap());
-        assert!(game.map.get(1, 1).unwrap().alive().unwrap());
-        assert!(game.map.get(1, 2).unwrap().alive().unwrap());
-        assert!(game.map.get(2, 2).unwrap().alive().unwrap());
+            cols 1
+            players 1
+            m .
+            m a
+            m .";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
 
-        // Move an ant towards its ally causing it collision and both ants to die
-        game.map.move_entity((2, 2), (1, 2));
+        game.move_ants(&[Action::new_with_steps(1, 0, Direction::North, 3)]);
 
-        // Make sure they are dead
-        assert!(!game.map.get(1, 2).unwrap().alive().unwrap());
-        assert!(!game.map.get(2, 2).unwrap().alive().unwrap());
+        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
+    }
 
-        game.attack();
+    #[test]
+    fn when_moving_off_any_edge_of_the_map_the_ant_stays_and_no_move_is_logged() {
+        use crate::replay::EventType;
 
-        // The remaining ants from player 0 and player 1 should both attack each other and die
-        assert!(!game.map.get(0, 1).unwrap().alive().unwrap());
-        assert!(!game.map.get(1, 1).unwrap().alive().unwrap());
+        let map = "\
+            rows 3
+            cols 3
+            players 1
+            m .a.
+            m a.a
+            m .a.";
+        let edges = [
+            (0, 1, Direction::North),
+            (1, 2, Direction::East),
+            (2, 1, Direction::South),
+            (1, 0, Direction::West),
+        ];
+        let mut game = Game::new_unchecked(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.set_log_blocked_moves(true);
+        let events = Arc::new(Mutex::new(vec![]));
+        let observed = events.clone();
+        game.add_event_observer(move |_, event| {
+            observed.lock().unwrap().push(event.clone());
+        });
+
+        game.move_ants(
+            &edges
+                .iter()
+                .map(|(row, col, direction)| Action::new(*row, *col, direction.clone()))
+                .collect::<Vec<_>>(),
+        );
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].ants_killed, 1);
-        assert_eq!(turn_stats[0].ants_lost, 1);
-        assert_eq!(turn_stats[1].ants_killed, 1);
-        assert_eq!(turn_stats[1].ants_lost, 1);
+        for (row, col, direction) in edges {
+            let ant = game.map.get(row, col).unwrap();
+            assert_eq!(ant.name(), "Ant");
+            assert_eq!(ant.alive(), Some(true));
+            assert_eq!(game.last_moves.get(ant.id()), Some(&(direction, false)));
+        }
+        let events = events.lock().unwrap();
+        assert!(events
+            .iter()
+            .all(|event| event.event_type == EventType::BlockedMove
+                && event.reason == Some("OutOfBounds".to_string())));
+        assert_eq!(events.len(), 4);
     }
 
     #[test]
-    fn when_razing_hills_if_a_hill_does_not_have_an_ant_the_hill_is_not_razed_and_scores_are_not_changed(
-    ) {
+    fn when_moving_an_ant_multiple_steps_into_another_ant_both_die_where_they_meet() {
         let map = "\
-            rows 2
-            cols 2
-            players 1
-            m 0.
-            m ..";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
-        game.compute_initial_scores();
+            rows 1
+            cols 6
+            players 2
+            m a...b.";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
 
-        assert_eq!(game.scores, vec![1]);
-        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
+        game.move_ants(&[Action::new_with_steps(0, 0, Direction::East, 5)]);
 
-        game.raze_hills();
+        let ant = game.map.get(0, 3).unwrap();
+        assert_eq!(ant.player(), Some(0));
+        assert!(!ant.alive().unwrap());
+        assert!(!game.map.get(0, 4).unwrap().alive().unwrap());
+    }
 
-        assert_eq!(game.scores, vec![1]);
-        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
+    #[test]
+    fn when_moving_an_ant_with_one_step_it_moves_a_single_cell() {
+        let map = "\
+            rows 1
+            cols 3
+            players 1
+            m a..";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].hills_razed, 0);
+        game.move_ants(&[Action::new_with_steps(0, 0, Direction::East, 1)]);
+
+        assert_eq!(game.map.get(0, 1).unwrap().name(), "Ant");
+        assert!(game.map.get(0, 2).is_none());
     }
 
-    #[test]
-    fn when_razing_hills_if_a_hill_has_an_ant_of_the_same_player_the_hill_is_not_razed_and_scores_are_not_changed(
-    ) {
+    #[test]
+    fn when_digging_water_next_to_an_ant_it_becomes_land_and_the_cost_is_paid() {
         let map = "\
-            rows 2
-            cols 2
+            rows 1
+            cols 3
             players 1
-            m 0.
-            m a.";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
-        game.compute_initial_scores();
+            m a%.";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_dig_cost(Some(2));
+        game.hive = vec![3];
 
-        assert_eq!(game.scores, vec![1]);
-        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
-        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
+        game.move_ants(&[Action::dig(0, 0, Direction::East)]);
 
-        // Move the ant to the hill
-        game.map.move_entity((1, 0), (0, 0));
+        assert!(game.map.get(0, 1).is_none());
         assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
+        assert_eq!(game.hive, vec![1]);
+    }
 
-        game.raze_hills();
+    #[test]
+    fn when_digging_without_enough_food_in_the_hive_the_water_stays() {
+        let map = "\
+            rows 1
+            cols 3
+            players 1
+            m a%.";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_dig_cost(Some(2));
+        game.hive = vec![1];
 
-        assert_eq!(game.scores, vec![1]);
-        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
+        game.move_ants(&[Action::dig(0, 0, Direction::East)]);
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].hills_razed, 0);
+        assert_eq!(game.map.get(0, 1).unwrap().name(), "Water");
+        assert_eq!(game.hive, vec![1]);
     }
 
     #[test]
-    fn when_razing_hills_if_a_hill_has_a_dead_enemy_ant_the_hill_is_not_razed_and_scores_are_not_changed(
-    ) {
+    fn when_digging_a_cell_that_is_not_water_nothing_happens() {
         let map = "\
             rows 2
             cols 2
-            players 2
-            m 0.
-            m b1";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
-        game.compute_initial_scores();
+            players 1
+            m a*
+            m %.";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_dig_cost(Some(1));
+        game.hive = vec![5];
 
-        assert_eq!(game.scores, vec![1, 1]);
-        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
-        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
+        game.move_ants(&[
+            Action::dig(0, 0, Direction::East),
+            Action::dig(0, 0, Direction::North),
+            Action::dig(0, 0, Direction::West),
+        ]);
 
-        // Move the enemy to the hill
-        game.map.move_entity((1, 0), (0, 0));
+        assert_eq!(game.map.get(0, 1).unwrap().name(), "Food");
+        assert_eq!(game.map.get(1, 0).unwrap().name(), "Water");
         assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
+        assert_eq!(game.hive, vec![5]);
+    }
 
-        // Kill the enemy
-        game.map.get_mut(0, 0).unwrap().set_alive(false);
-
-        game.raze_hills();
+    #[test]
+    fn when_digging_is_disabled_the_water_stays() {
+        let map = "\
+            rows 1
+            cols 3
+            players 1
+            m a%.";
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.hive = vec![3];
 
-        assert_eq!(game.scores, vec![1, 1]);
-        assert!(game
-            .map
-            .get(0, 0)
-            .unwrap()
-            .on_ant_hill()
-            .as_ref()
-            .unwrap()
-            .alive()
-            .unwrap());
+        game.move_ants(&[Action::dig(0, 0, Direction::East)]);
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].hills_razed, 0);
-        assert_eq!(turn_stats[0].hills_lost, 0);
-        assert_eq!(turn_stats[1].hills_razed, 0);
-        assert_eq!(turn_stats[1].hills_lost, 0);
+        assert_eq!(game.map.get(0, 1).unwrap().name(), "Water");
+        assert_eq!(game.hive, vec![3]);
     }
 
     #[test]
-    fn when_razing_hills_if_a_hill_has_an_alive_enemy_ant_the_hill_is_razed_and_scores_are_updated()
-    {
+    fn when_hill_owners_are_not_revealed_only_enemy_hills_are_seen_without_a_player() {
         let map = "\
-            rows 2
-            cols 2
+            rows 1
+            cols 6
             players 2
-            m 0.
-            m b1";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
-        game.compute_initial_scores();
+            m 0a.b1.";
+        let mut game = Game::new(map, 25, 1, 1, 0, 1500, 500, 0, None);
+        game.set_reveal_hill_owner(false);
+        let state = game.start();
 
-        assert_eq!(game.scores, vec![1, 1]);
-        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
-        assert!(game.map.get(0, 0).unwrap().alive().unwrap());
+        let hills = |player: usize| {
+            state.ants[player]
+                .iter()
+                .find(|ant| ant.col == 1 || ant.col == 3)
+                .unwrap()
+                .field_of_vision
+                .iter()
+                .filter(|entity| entity.name == "Hill")
+                .map(|entity| (entity.col, entity.player))
+                .collect::<Vec<(usize, Option<usize>)>>()
+        };
 
-        // Move the enemy to the hill
-        game.map.move_entity((1, 0), (0, 0));
-        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
+        assert_eq!(hills(0), vec![(0, Some(0)), (4, None)]);
+        assert_eq!(hills(1), vec![(0, None), (4, Some(1))]);
 
-        game.raze_hills();
+        // Ants are never masked
+        assert!(state.ants[0][0]
+            .field_of_vision
+            .iter()
+            .any(|entity| entity.name == "Ant" && entity.player == Some(1)));
+    }
 
-        // Player 0 loses 1 point for losing the hill
-        // Player 1 gains 2 points for razing the hill
-        assert_eq!(game.scores, vec![0, 3]);
-        assert!(!game
-            .map
-            .get(0, 0)
-            .unwrap()
-            .on_ant_hill()
-            .as_ref()
-            .unwrap()
-            .alive()
-            .unwrap());
+    #[test]
+    fn when_hill_owners_are_revealed_enemy_hills_are_seen_with_their_player() {
+        let map = "\
+            rows 1
+            cols 6
+            players 2
+            m 0a.b1.";
+        let mut game = Game::new(map, 25, 1, 1, 0, 1500, 500, 0, None);
+        let state = game.start();
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].hills_razed, 0);
-        assert_eq!(turn_stats[0].hills_lost, 1);
-        assert_eq!(turn_stats[1].hills_razed, 1);
-        assert_eq!(turn_stats[1].hills_lost, 0);
+        assert!(state.ants[0][0]
+            .field_of_vision
+            .iter()
+            .any(|entity| entity.name == "Hill" && entity.player == Some(1)));
     }
 
     #[test]
-    fn when_spawning_ants_from_hive_if_there_is_no_food_no_ants_are_spawned() {
+    fn when_getting_the_game_state_each_ant_includes_the_score_and_hive_of_its_owner() {
         let map = "\
-            rows 2
-            cols 2
+            rows 1
+            cols 6
             players 2
-            m 01
-            m ..";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
-
-        game.spawn_ants_from_hive();
+            m 0a..b1";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.start();
+        game.scores = vec![3, 5];
+        game.hive = vec![2, 0];
 
-        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
-        assert_eq!(game.map.get(0, 1).unwrap().name(), "Hill");
-        assert_eq!(game.hive, vec![0, 0]);
+        let state = game.update(vec![]);
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].ants_spawned, 0);
-        assert_eq!(turn_stats[1].ants_spawned, 0);
+        assert!(state.ants[0]
+            .iter()
+            .all(|ant| ant.owner_score == 3 && ant.owner_hive == 2));
+        assert!(state.ants[1]
+            .iter()
+            .all(|ant| ant.owner_score == 5 && ant.owner_hive == 0));
     }
 
     #[test]
-    fn when_spawning_ants_from_hive_if_the_hill_is_razed_no_ants_are_spawned() {
+    fn when_ants_are_ordered_to_move_the_state_includes_their_last_move() {
         let map = "\
             rows 2
-            cols 2
-            players 1
-            m 0.
-            m ..";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
-        game.hive = vec![1];
+            cols 6
+            players 2
+            m 0a.%b1
+            m ......";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        let state = game.start();
 
-        // Raze the hill
-        game.map.get_mut(0, 0).unwrap().set_alive(false);
+        assert!(state
+            .ants
+            .iter()
+            .flatten()
+            .all(|ant| ant.last_direction.is_none()));
 
-        game.spawn_ants_from_hive();
+        let state = game.update(vec![
+            Action::new(0, 1, Direction::East),
+            Action::new(0, 4, Direction::West),
+        ]);
 
-        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
-        assert_eq!(game.hive, vec![1]);
+        let a = state.ants[0].iter().find(|ant| ant.col == 2).unwrap();
+        assert_eq!(a.last_direction, Some(Direction::East));
+        assert!(a.last_move_succeeded);
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].ants_spawned, 0);
+        let b = state.ants[1].iter().find(|ant| ant.col == 4).unwrap();
+        assert_eq!(b.last_direction, Some(Direction::West));
+        assert!(!b.last_move_succeeded);
+
+        let state = game.update(vec![]);
+
+        assert!(state
+            .ants
+            .iter()
+            .flatten()
+            .all(|ant| ant.last_direction.is_none()));
+        assert!(state
+            .ants
+            .iter()
+            .flatten()
+            .all(|ant| !ant.last_move_succeeded));
     }
 
     #[test]
-    fn when_spawning_ants_from_hive_if_there_is_food_and_one_hill_one_ant_is_spawned() {
+    fn when_looking_up_an_ant_by_id_it_is_found_wherever_it_moved() {
         let map = "\
-            rows 2
-            cols 2
+            rows 1
+            cols 8
             players 2
-            m 01
-            m ..";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
-        game.hive = vec![1, 1];
+            m 0a.%..b1";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        let state = game.start();
+        let id = state.ants[0]
+            .iter()
+            .find(|ant| ant.col == 1)
+            .unwrap()
+            .id
+            .clone();
 
-        game.spawn_ants_from_hive();
+        game.update(vec![Action::new(0, 1, Direction::East)]);
+        let ant = game.ant_by_id(&id).unwrap();
 
-        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
-        assert_eq!(game.map.get(0, 0).unwrap().player().unwrap(), 0);
-        assert_eq!(game.map.get(0, 1).unwrap().name(), "Ant");
-        assert_eq!(game.map.get(0, 1).unwrap().player().unwrap(), 1);
-        assert_eq!(game.hive, vec![0, 0]);
+        assert_eq!((ant.row, ant.col), (0, 2));
+        assert_eq!(ant.player, 0);
+        assert!(game.ant_by_id("missing").is_none());
+    }
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].ants_spawned, 1);
-        assert_eq!(turn_stats[1].ants_spawned, 1);
+    #[test]
+    fn when_getting_the_legal_moves_of_an_ant_only_the_unblocked_directions_are_returned() {
+        let map = "\
+            rows 3
+            cols 3
+            players 2
+            m a%.
+            m *b.
+            m .a.";
+        let game = Game::new_unchecked(map, 4, 1, 1, 0, 1500, 500, 0, None);
+
+        // North and West are off the map, East is water and South is food
+        assert!(game.legal_moves(0, 0).is_empty());
+        // Moving onto a live ant is legal, even though both ants die
+        assert_eq!(
+            game.legal_moves(1, 1),
+            vec![Direction::East, Direction::South]
+        );
     }
 
     #[test]
-    fn when_spawning_ants_from_hive_if_there_is_more_food_and_one_hill_only_one_ant_is_spawned() {
+    fn when_getting_the_legal_moves_of_a_cell_without_a_live_ant_none_are_returned() {
         let map = "\
             rows 2
             cols 2
             players 1
-            m 0.
-            m ..";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
-        game.hive = vec![5];
-
-        game.spawn_ants_from_hive();
-
-        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
-        assert_eq!(game.hive, vec![4]);
+            m a.
+            m 0.";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.map.get_mut(0, 0).unwrap().set_alive(false);
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].ants_spawned, 1);
+        assert!(game.legal_moves(0, 0).is_empty());
+        assert!(game.legal_moves(1, 0).is_empty());
+        assert!(game.legal_moves(0, 1).is_empty());
+        assert!(game.legal_moves(5, 5).is_empty());
     }
 
     #[test]
-    fn when_spawning_ants_from_hive_if_there_is_only_one_food_and_multiple_hills_only_one_ant_is_spawned(
-    ) {
+    fn when_getting_all_the_entities_every_occupied_cell_is_returned_once_in_board_order() {
         let map = "\
             rows 2
-            cols 2
-            players 1
-            m 0.
-            m .0";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
-        game.hive = vec![1];
-
-        game.spawn_ants_from_hive();
+            cols 3
+            players 2
+            m 0*%
+            m .B1";
+        let game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
 
-        // Hill is chosen at random and we make it predictable based on the seed
-        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
-        assert_eq!(game.map.get(1, 1).unwrap().name(), "Ant");
-        assert_eq!(game.hive, vec![0]);
+        let entities: Vec<(String, usize, usize, Option<usize>)> = game
+            .entities()
+            .into_iter()
+            .map(|entity| (entity.name, entity.row, entity.col, entity.player))
+            .collect();
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].ants_spawned, 1);
+        // The ant on the hill is returned as the ant
+        assert_eq!(
+            entities,
+            vec![
+                ("Hill".to_string(), 0, 0, Some(0)),
+                ("Food".to_string(), 0, 1, None),
+                ("Water".to_string(), 0, 2, None),
+                ("Ant".to_string(), 1, 1, Some(1)),
+                ("Hill".to_string(), 1, 2, Some(1)),
+            ]
+        );
     }
 
     #[test]
-    fn when_spawning_ants_from_hive_if_there_is_enough_food_and_multiple_hills_one_ant_is_spawned_per_hill(
-    ) {
+    fn when_getting_the_debug_state_the_whole_board_is_revealed_unlike_in_the_agent_state() {
         let map = "\
-            rows 2
-            cols 2
+            rows 1
+            cols 8
             players 2
-            m 01
-            m 10";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
-        game.hive = vec![5, 2];
+            m 0......1";
+        let mut game = Game::new(map, 1, 1, 1, 0, 1500, 500, 0, None);
+        game.set_starting_food_per_hill(0);
 
-        game.spawn_ants_from_hive();
+        let state = game.start();
+        let debug_state = game.debug_state();
 
-        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
-        assert_eq!(game.map.get(0, 0).unwrap().player().unwrap(), 0);
-        assert_eq!(game.map.get(1, 1).unwrap().name(), "Ant");
-        assert_eq!(game.map.get(1, 1).unwrap().player().unwrap(), 0);
+        // The ants are too far apart to see each other, but the debug state has every entity
+        assert!(state.board.is_none());
+        assert!(state.ants[0][0]
+            .field_of_vision
+            .iter()
+            .all(|entity| entity.player != Some(1)));
+        let board = debug_state.board.unwrap();
+        assert_eq!(board.len(), 2);
+        assert!(board
+            .iter()
+            .any(|entity| entity.name == "Ant" && entity.player == Some(1) && entity.col == 7));
+        // Apart from the board, it's the same state
+        assert_eq!(debug_state.ants, state.ants);
+        assert_eq!(debug_state.scores, state.scores);
+    }
 
-        assert_eq!(game.map.get(0, 1).unwrap().name(), "Ant");
-        assert_eq!(game.map.get(0, 1).unwrap().player().unwrap(), 1);
-        assert_eq!(game.map.get(1, 0).unwrap().name(), "Ant");
-        assert_eq!(game.map.get(1, 0).unwrap().player().unwrap(), 1);
+    #[test]
+    fn when_observing_events_the_observer_receives_every_event_of_the_turn() {
+        use crate::replay::EventType;
+        use std::sync::{Arc, Mutex};
 
-        assert_eq!(game.hive, vec![3, 0]);
+        let map = "\
+            rows 1
+            cols 8
+            players 2
+            m 0a.%..b1";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        let events = Arc::new(Mutex::new(vec![]));
+        let observed = events.clone();
+        game.add_event_observer(move |turn, event| {
+            observed.lock().unwrap().push((turn, event.clone()));
+        });
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].ants_spawned, 2);
-        assert_eq!(turn_stats[1].ants_spawned, 2);
+        game.start();
+        let ant_id = game.map.get(0, 1).unwrap().id().to_string();
+        game.update(vec![Action::new(0, 1, Direction::East)]);
+
+        let events = events.lock().unwrap();
+        // The ants spawned on the hills at the start of the game
+        assert_eq!(
+            events
+                .iter()
+                .filter(|(turn, event)| *turn == 0
+                    && event.event_type == EventType::Spawn
+                    && event.entity == "Ant")
+                .count(),
+            2
+        );
+        let moves: Vec<&Event> = events
+            .iter()
+            .filter(|(turn, event)| *turn == 1 && event.event_type == EventType::Move)
+            .map(|(_, event)| event)
+            .collect();
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].entity_id, Some(ant_id));
+        assert_eq!(moves[0].location, Some((0, 1)));
+        assert_eq!(moves[0].destination, Some((0, 2)));
     }
 
     #[test]
-    fn when_spawning_ants_from_hive_if_the_player_has_reached_the_max_colony_size_no_more_ants_are_spawned(
-    ) {
-        let map = "\
-            rows 2
-            cols 2
-            players 1
-            m 0a
-            m .a";
-        let max_colony_size = 2;
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, max_colony_size, 0, None);
-        game.hive = vec![5];
-
-        game.spawn_ants_from_hive();
+    fn when_moving_ants_with_blocked_moves_logged_a_failed_move_is_logged_with_its_reason() {
+        use crate::replay::EventType;
+        use std::sync::{Arc, Mutex};
 
-        assert_eq!(game.map.get(0, 0).unwrap().name(), "Hill");
-        assert_eq!(game.hive, vec![5]);
+        let map = "\
+            rows 1
+            cols 8
+            players 2
+            m 0a%...b1";
+        let blocked_moves = |log_blocked_moves: bool| {
+            let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+            game.set_log_blocked_moves(log_blocked_moves);
+            let events = Arc::new(Mutex::new(vec![]));
+            let observed = events.clone();
+            game.add_event_observer(move |_, event| {
+                if event.event_type == EventType::BlockedMove {
+                    observed.lock().unwrap().push(event.clone());
+                }
+            });
 
-        let turn_stats = game.turn_stats.clone();
-        assert_eq!(turn_stats[0].ants_spawned, 0);
+            game.start();
+            game.update(vec![Action::new(0, 1, Direction::East)]);
 
-        // Kill one of the ants
-        game.map.get_mut(0, 1).unwrap().set_alive(false);
-        // And spawn again
-        game.spawn_ants_from_hive();
+            let events = events.lock().unwrap().clone();
+            events
+        };
 
-        // Now the ant is spawned because there is enough food in the hive and the max colony size is not reached
-        assert_eq!(game.map.get(0, 0).unwrap().name(), "Ant");
-        assert_eq!(game.hive, vec![4]);
+        // Normal replays stay lean
+        assert!(blocked_moves(false).is_empty());
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].ants_spawned, 1);
+        let events = blocked_moves(true);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].location, Some((0, 1)));
+        assert_eq!(events[0].destination, Some((0, 2)));
+        assert_eq!(events[0].reason, Some("Water".to_string()));
     }
 
     #[test]
-    fn when_harvesting_food_if_there_are_no_ants_around_the_food_nothing_happens() {
+    fn when_updating_with_a_reward_hook_the_state_includes_the_rewards_of_the_turn() {
+        use crate::replay::EventType;
+
         let map = "\
-            rows 3
-            cols 3
-            players 1
-            m *..
-            m .*.
-            m ..*";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+            rows 2
+            cols 8
+            players 2
+            m 0a.%..b1
+            m ..*.....";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.set_starting_food_per_hill(0);
+        game.set_reward_hook(|last_state, actions, state, events| {
+            let moves = events
+                .iter()
+                .filter(|event| event.event_type == EventType::Move)
+                .count();
+            (0..state.hive.len())
+                .map(|player| {
+                    (state.hive[player] - last_state.hive[player]) as f64
+                        + (actions.len() * 10 + moves * 100) as f64
+                })
+                .collect()
+        });
+        let state = game.start();
+        assert_eq!(state.rewards, None);
 
-        game.harvest_food();
+        let state = game.update(vec![Action::new(0, 1, Direction::East)]);
 
-        assert_eq!(game.map.get(0, 0).unwrap().name(), "Food");
-        assert_eq!(game.map.get(1, 1).unwrap().name(), "Food");
-        assert_eq!(game.map.get(2, 2).unwrap().name(), "Food");
-        assert_eq!(game.hive, vec![0]);
+        // Player 0 harvested 1 food, with 1 action and 1 move in the turn
+        assert_eq!(state.rewards, Some(vec![111.0, 110.0]));
+        assert_eq!(game.last_rewards(), state.rewards);
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].food_harvested, 0);
+        // Only the events of the turn are passed to the hook
+        let state = game.update(vec![]);
+        assert_eq!(state.rewards, Some(vec![0.0, 0.0]));
     }
 
     #[test]
-    fn when_harvesting_food_if_there_only_ants_from_the_same_player_around_the_food_the_food_is_harvested_into_the_hive(
-    ) {
+    fn when_setting_a_reward_hook_after_starting_the_next_turn_includes_the_rewards() {
+        use crate::replay::EventType;
+
         let map = "\
-            rows 3
-            cols 3
+            rows 2
+            cols 8
             players 2
-            m *ab
-            m .aa
-            m b.*";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
-
-        game.harvest_food();
-
-        assert!(game.map.get(0, 0).is_none());
-        assert!(game.map.get(2, 2).is_none());
-        assert_eq!(game.hive, vec![2, 0]);
+            m 0a.%..b1
+            m ..*.....";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.set_starting_food_per_hill(0);
+        game.start();
+        game.update(vec![]);
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].food_harvested, 2);
+        game.set_reward_hook(|last_state, _, state, events| {
+            let moves = events
+                .iter()
+                .filter(|event| event.event_type == EventType::Move)
+                .count();
+            (0..state.hive.len())
+                .map(|player| (state.hive[player] - last_state.hive[player] + moves * 10) as f64)
+                .collect()
+        });
+        let state = game.update(vec![Action::new(0, 1, Direction::East)]);
+
+        // Player 0 harvested 1 food, with 1 move in the turn
+        assert_eq!(state.rewards, Some(vec![11.0, 10.0]));
     }
 
     #[test]
-    fn when_harvesting_food_if_there_are_ants_from_different_players_around_the_food_the_food_is_destroyed(
-    ) {
+    fn when_a_python_reward_hook_raises_the_call_that_played_the_turn_raises_it() {
         let map = "\
-            rows 3
-            cols 3
+            rows 1
+            cols 5
             players 2
-            m *a.
-            m b.a
-            m .b*";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
-
-        game.harvest_food();
+            m 0a.b1";
+        pyo3::prepare_freethreaded_python();
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        Python::with_gil(|py| {
+            let hook = py
+                .eval(c"lambda last_state, actions, state, events: [state.turn / (state.turn - 1)] * 2", None, None)
+                .unwrap();
+            game.set_reward_hook_py(hook.unbind());
+            game.start_py().unwrap();
 
-        assert!(game.map.get(0, 0).is_none());
-        assert!(game.map.get(2, 2).is_none());
-        assert_eq!(game.hive, vec![0, 0]);
+            let error = game.update_py(vec![]).unwrap_err();
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].food_harvested, 0);
-        assert_eq!(turn_stats[1].food_harvested, 0);
+            assert!(error.is_instance_of::<pyo3::exceptions::PyZeroDivisionError>(py));
+            // The turn was still played, and the next one gets its rewards
+            assert_eq!(game.turn(), 1);
+            assert_eq!(
+                game.update_py(vec![]).unwrap().rewards,
+                Some(vec![2.0, 2.0])
+            );
+        });
     }
 
     #[test]
-    fn when_harvesting_food_an_ant_can_only_consume_one_food_at_a_time() {
+    fn when_updating_without_a_reward_hook_the_state_has_no_rewards() {
         let map = "\
-            rows 3
-            cols 3
-            players 1
-            m .*.
-            m *a*
-            m .*.";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+            rows 1
+            cols 5
+            players 2
+            m 0a.b1";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.start();
 
-        game.harvest_food();
+        let state = game.update(vec![]);
 
-        assert!(game.map.get(0, 1).is_none());
-        assert!(game.map.get(1, 0).is_some());
-        assert!(game.map.get(1, 2).is_some());
-        assert!(game.map.get(2, 1).is_some());
-        assert_eq!(game.hive, vec![1]);
+        assert_eq!(state.rewards, None);
+        assert_eq!(game.last_rewards(), None);
+    }
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].food_harvested, 1);
+    #[test]
+    fn when_a_python_event_observer_raises_the_call_that_played_the_turn_raises_it() {
+        let map = "\
+            rows 1
+            cols 5
+            players 2
+            m 0a.b1";
+        pyo3::prepare_freethreaded_python();
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        Python::with_gil(|py| {
+            let observer = py
+                .eval(
+                    c"lambda turn, event: 1 / 0 if turn == 1 else None",
+                    None,
+                    None,
+                )
+                .unwrap();
+            game.add_event_observer_py(observer.unbind());
+            game.start_py().unwrap();
+
+            let error = game
+                .update_py(vec![Action::new(0, 1, Direction::East)])
+                .unwrap_err();
+
+            assert!(error.is_instance_of::<pyo3::exceptions::PyZeroDivisionError>(py));
+            // The turn was still played, and the exception is only raised once
+            assert_eq!(game.turn(), 1);
+            assert!(game.update_py(vec![]).is_ok());
+        });
     }
 
     #[test]
-    fn when_harvesting_food_two_distinct_ants_from_the_same_player_can_consume_food_at_the_same_time(
-    ) {
+    fn when_observing_events_the_replay_still_logs_them() {
         let map = "\
-            rows 3
-            cols 3
-            players 1
-            m .*a
-            m *a*
-            m .*.";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+            rows 1
+            cols 5
+            players 2
+            m 0a.b1";
+        let filename = std::env::temp_dir()
+            .join(format!("ants_replay_{}.json", uuid::Uuid::new_v4()))
+            .to_str()
+            .unwrap()
+            .to_string();
+        let mut game = Game::new(map, 4, 1, 1, 0, 1, 500, 0, Some(filename.clone()));
+        game.add_event_observer(|_, _| {});
+        game.start();
 
-        game.harvest_food();
+        game.update(vec![]);
 
-        assert!(game.map.get(0, 1).is_none());
-        assert!(game.map.get(1, 0).is_none());
-        assert!(game.map.get(1, 2).is_some());
-        assert!(game.map.get(2, 1).is_some());
-        assert_eq!(game.hive, vec![2]);
+        let replay: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&filename).unwrap()).unwrap();
+        std::fs::remove_file(&filename).unwrap();
 
-        let turn_stats = game.turn_stats;
-        assert_eq!(turn_stats[0].food_harvested, 2);
+        assert!(!replay["turns"][0]["events"].as_array().unwrap().is_empty());
+        assert_eq!(replay["result"]["reason"], "TurnLimitReached");
     }
 
     #[test]
-    fn when_spawning_food_randomly_and_there_is_enough_land_all_food_is_spawned() {
+    fn when_razing_before_attacking_an_ant_that_dies_in_battle_still_razes_the_hill() {
         let map = "\
-            rows 3
-            cols 3
-            players 1
-            m ...
-            m .a.
-            m ...";
-        let mut game = Game::new(map, 4, 5, 1, 8, 1500, 500, 0, None);
-
-        game.spawn_food_randomly();
-
-        let food = game.map.food();
-        let expected_food = vec![
-            (0, 0),
-            (0, 1),
-            (0, 2),
-            (1, 0),
-            (1, 2),
-            (2, 0),
-            (2, 1),
-            (2, 2),
-        ];
+            rows 1
+            cols 5
+            players 2
+            m 0...1";
+        let play = |phase_order: Vec<TurnPhase>| {
+            let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+            game.set_phase_order(phase_order);
+            game.start();
+            // An enemy ant on the hill of player 0, right next to an ant of player 0
+            game.map.set(
+                0,
+                0,
+                Box::new(Ant::new(
+                    "b".to_string(),
+                    1,
+                    true,
+                    Some(Box::new(Hill::new(0, true))),
+                )),
+            );
+            game.map
+                .set(0, 1, Box::new(Ant::new("a".to_string(), 0, true, None)));
+
+            game.update(vec![]).scores
+        };
+
+        // By default, both ants die in battle before the hill can be razed
+        assert_eq!(play(TurnPhase::default_order()), vec![1, 1]);
+        // But razing first lets the enemy ant raze the hill before dying
+        assert_eq!(
+            play(vec![
+                TurnPhase::Move,
+                TurnPhase::Raze,
+                TurnPhase::Attack,
+                TurnPhase::SpawnAnts,
+                TurnPhase::Harvest,
+                TurnPhase::SpawnFood,
+            ]),
+            vec![0, 3]
+        );
+    }
 
-        assert_eq!(food.len(), 8);
-        assert_eq!(food, expected_food);
+    #[test]
+    #[should_panic(expected = "The phase order must contain each phase exactly once")]
+    fn when_setting_a_phase_order_with_a_repeated_phase_it_panics() {
+        let map = "\
+            rows 1
+            cols 2
+            players 2
+            m 01";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+
+        game.set_phase_order(vec![
+            TurnPhase::Move,
+            TurnPhase::Move,
+            TurnPhase::Raze,
+            TurnPhase::SpawnAnts,
+            TurnPhase::Harvest,
+            TurnPhase::SpawnFood,
+        ]);
     }
 
     #[test]
-    fn when_spawning_food_randomly_and_there_is_not_enough_land_not_all_food_is_spawned() {
+    fn when_running_a_game_to_completion_the_agent_plays_every_turn() {
         let map = "\
-            rows 3
-            cols 3
+            rows 1
+            cols 8
             players 2
-            m aa.
-            m .a.
-            m b.b";
-        let mut game = Game::new(map, 4, 5, 1, 9, 1500, 500, 0, None);
+            m 0......1";
+        let mut game = Game::new(map, 4, 1, 1, 0, 5, 500, 0, None);
+        let mut turns = vec![];
+
+        let state = game.run_to_completion(|state| {
+            turns.push(state.turn);
+            vec![]
+        });
+
+        assert!(state.finished);
+        assert_eq!(state.turn, 5);
+        assert_eq!(turns, vec![0, 1, 2, 3, 4]);
+    }
 
-        game.spawn_food_randomly();
+    #[test]
+    fn when_updating_the_game_from_a_slice_the_actions_can_be_reused() {
+        let map = "\
+            rows 1
+            cols 6
+            players 2
+            m a....b";
+        let mut game = Game::new_unchecked(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.start();
 
-        let food = game.map.food();
-        let expected_food = vec![(0, 2), (1, 0), (1, 2), (2, 1)];
+        let mut actions = vec![Action::new(0, 0, Direction::East)];
+        game.update_from_slice(&actions);
+        actions[0] = Action::new(0, 1, Direction::East);
+        let state = game.update_from_slice(&actions);
 
-        assert_eq!(food.len(), 4);
-        assert_eq!(food, expected_food);
+        assert_eq!(state.turn, 2);
+        assert_eq!(state.ants[0].len(), 1);
+        assert_eq!((state.ants[0][0].row, state.ants[0][0].col), (0, 2));
     }
 
     #[test]
-    fn when_spawning_food_randomly_and_there_is_no_land_no_food_is_spawned() {
+    fn when_ranking_players_they_are_sorted_by_score_then_ants_then_hills() {
         let map = "\
             rows 3
-            cols 3
-            players 2
-            m aaa
-            m aaa
-            m aba";
-        let mut game = Game::new(map, 4, 5, 1, 9, 1500, 500, 0, None);
+            cols 4
+            players 4
+            m 0a1b
+            m 2c..
+            m .33.";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        // Player 3 has the highest score and players 0, 1 and 2 are tied
+        game.scores = vec![2, 2, 2, 3];
 
-        game.spawn_food_randomly();
-        assert!(game.map.food().is_empty());
+        // Players 0, 1 and 2 all have 1 ant and 1 hill, so the tie is broken by the player number
+        assert_eq!(game.ranking(), vec![3, 0, 1, 2]);
+
+        game.scores = vec![2, 2, 1, 3];
+        game.map.remove(0, 1);
+        // Now player 0 has no ants, so player 1 is ahead of it
+        assert_eq!(game.ranking(), vec![3, 1, 0, 2]);
     }
 
     #[test]
-    fn when_spawning_food_randomly_and_there_is_enough_current_food_no_more_food_is_spawned() {
+    fn when_getting_the_current_standings_each_player_has_their_score_ants_and_hills_in_ranking_order(
+    ) {
         let map = "\
-            rows 3
-            cols 3
-            players 1
-            m *..
-            m .a.
-            m ...";
-        // If we use a `food_rate` of 1, we will only spawn 1 food per turn
-        // and since the map already has 1 food, we should not spawn any more
-        let mut game = Game::new(map, 4, 5, 1, 1, 1500, 500, 0, None);
+            rows 2
+            cols 4
+            players 2
+            m 0a1b
+            m ..b.";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.scores = vec![2, 2];
 
-        game.spawn_food_randomly();
-        assert_eq!(game.map.food().len(), 1);
+        // Tied on score, player 1 is ahead with more ants
+        assert_eq!(game.current_standings(), vec![(1, 2, 2, 1), (0, 2, 1, 1)]);
+        assert_eq!(game.ranking(), vec![1, 0]);
     }
 
     #[test]
-    fn when_spawning_food_randomly_and_there_is_some_food_already_only_the_missing_food_is_spawned()
-    {
+    fn when_the_game_has_not_finished_there_is_no_winner() {
         let map = "\
-            rows 3
-            cols 3
-            players 1
-            m *..
-            m .a.
-            m ...";
-        // If we use a `food_rate` of 2, we will spawn 2 food per turn
-        // and since the map already has 1 food, we should spawn 1 more
-        let mut game = Game::new(map, 4, 5, 1, 2, 1500, 500, 0, None);
+            rows 1
+            cols 2
+            players 2
+            m 01";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.scores = vec![2, 1];
 
-        game.spawn_food_randomly();
-        assert_eq!(game.map.food().len(), 2);
+        assert!(game.winner().is_none());
+        assert_eq!(game.ranking(), vec![0, 1]);
     }
 
     #[test]
-    fn when_checking_for_endgame_if_the_food_is_not_being_gathered_the_game_ends() {
+    fn when_the_game_has_finished_the_winner_is_the_same_as_in_the_game_state() {
         let map = "\
-            rows 3
-            cols 3
-            players 1
-            m *a*
-            m ***
-            m .**";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
-        game.cutoff_threshold = 1;
+            rows 1
+            cols 6
+            players 2
+            m 0a.bb1";
+        let mut game = Game::new(map, 4, 1, 1, 0, 1, 500, 0, None);
+        game.start();
 
-        game.check_for_endgame();
+        // Player 1 leads the ranking with more ants, so they win when the turn limit ends the game
+        let state = game.update(vec![]);
+        assert_eq!(
+            state.finished_reason,
+            Some(FinishedReason::TurnLimitReached)
+        );
+        assert_eq!(game.ranking(), vec![1, 0]);
+        assert_eq!(state.winner, Some(1));
+        assert_eq!(game.winner(), state.winner);
 
-        assert!(game.finished);
-        assert_eq!(game.finished_reason, Some(FinishedReason::TooMuchFood));
+        let mut game = Game::new(map, 4, 1, 1, 0, 1500, 500, 0, None);
+        game.start();
+        game.eliminate_player(0);
+
+        let state = game.update(vec![]);
+        assert_eq!(state.finished_reason, Some(FinishedReason::LoneSurvivor));
+        assert_eq!(state.winner, Some(1));
+        assert_eq!(game.winner(), state.winner);
     }
 
     #[test]
-    fn when_checking_for_endgame_if_only_one_player_remains_with_ants_the_game_ends() {
+    fn when_checking_for_endgame_if_a_player_with_no_points_would_lose_their_hills_the_projection_does_not_underflow(
+    ) {
         let map = "\
-            rows 3
-            cols 3
-            players 2
-            m a..
-            m aa.
-            m ...";
+            rows 1
+            cols 5
+            players 3
+            m 0.1.2";
         let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        // Players 1 and 2 lost all their points, but still have a hill each
+        game.scores = vec![5, 0, 0];
 
-        game.check_for_endgame();
+        game.check_for_endgame(&game.live_ant_locations());
 
+        // Even razing the other 2 hills, players 1 and 2 would only get 4 points
         assert!(game.finished);
-        assert_eq!(game.finished_reason, Some(FinishedReason::LoneSurvivor));
+        assert_eq!(game.finished_reason, Some(FinishedReason::RankStabilized));
         assert_eq!(game.winner, Some(0));
+        assert_eq!(game.scores, vec![5, 0, 0]);
     }
 
     #[test]
@@ -2016,7 +9089,7 @@ mod tests {
         let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
         game.compute_initial_scores();
 
-        game.check_for_endgame();
+        game.check_for_endgame(&game.live_ant_locations());
 
         assert!(!game.finished);
         assert!(game.finished_reason.is_none());
@@ -2025,6 +9098,28 @@ mod tests {
         assert_eq!(game.scores, vec![1, 1]);
     }
 
+    #[test]
+    fn when_checking_for_endgame_with_a_zero_starting_score_the_rank_is_not_stabilized_even_if_the_hills_are_uneven(
+    ) {
+        let map = "\
+            rows 3
+            cols 3
+            players 2
+            m 0.0
+            m ...
+            m 0.1";
+        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        game.set_starting_score(StartingScore::Zero());
+        game.compute_initial_scores();
+
+        game.check_for_endgame(&game.live_ant_locations());
+
+        // Everyone is tied at 0, so nobody is in the lead yet
+        assert!(!game.finished);
+        assert!(game.finished_reason.is_none());
+        assert_eq!(game.scores, vec![0, 0]);
+    }
+
     #[test]
     fn when_checking_for_endgame_if_the_current_leader_cannot_be_surpassed_the_rank_is_stabilized_and_the_game_ends(
     ) {
@@ -2035,13 +9130,13 @@ mod tests {
             m 0..
             m ...
             m .3.";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
         // If player 0 razes the hills of player 1 and 2, the scores are 0=5, 1=0, 2=0, 3=1
         // In this case, even if player 3 were to raze the hill of player 0, the score would be 0=4, 1=0, 2=0, 3=3
         // so player 3 can't possibly do better than 2nd place and the game ends
         game.scores = vec![5, 0, 0, 1];
 
-        game.check_for_endgame();
+        game.check_for_endgame(&game.live_ant_locations());
 
         assert!(game.finished);
         assert_eq!(game.finished_reason, Some(FinishedReason::RankStabilized));
@@ -2060,14 +9155,14 @@ mod tests {
             m 0..
             m .2.
             m .3.";
-        let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
+        let mut game = Game::new_unchecked(map, 4, 5, 1, 5, 1500, 500, 0, None);
         // If player 0 razes the hills of player 1, the scores are 0=3, 1=0, 2=1, 3=1
         // In this case, if player 2 were to raze all the other hills, the score would be 0=2, 1=0, 2=3, 3=0
         // and player 2 would win, so the rank is not stabilized yet.
         // Note that the same happens if player 3 were to raze all the other hills.
         game.scores = vec![3, 0, 1, 1];
 
-        game.check_for_endgame();
+        game.check_for_endgame(&game.live_ant_locations());
 
         assert!(!game.finished);
         assert!(game.finished_reason.is_none());
@@ -2088,7 +9183,7 @@ mod tests {
         let mut game = Game::new(map, 4, 5, 1, 5, 1500, 500, 0, None);
         game.turn = 1500;
 
-        game.check_for_endgame();
+        game.check_for_endgame(&game.live_ant_locations());
 
         assert!(game.finished);
         assert_eq!(game.finished_reason, Some(FinishedReason::TurnLimitReached));
@@ -5,29 +5,83 @@
 
 pub mod game;
 pub use game::Action;
+pub use game::ContestedFood;
 pub use game::Direction;
+pub use game::DistanceMetric;
 pub use game::FinishedReason;
+pub use game::FoodSpawnStrategy;
 pub use game::Game;
+pub use game::GameError;
 pub use game::GameState;
+pub use game::HillRazeMode;
+pub use game::StartingScore;
+pub use game::StateArrays;
+pub use game::TurnPhase;
 pub use game::TurnStats;
 
+// The prototype mirrors every method of `Map`, even the ones the benchmark doesn't call
+#[cfg(any(test, feature = "bench"))]
+#[allow(dead_code)]
+pub(crate) mod compact;
+#[cfg(feature = "bench")]
+pub use compact::benchmark_map_storage;
+
+pub mod render;
+pub use render::RenderTheme;
+
+pub mod tournament;
+pub use tournament::Tournament;
+
+pub use map::transform_map;
+use map::transform_map_py;
+pub use map::translate_map;
+use map::translate_map_py;
+pub use map::MapTransform;
+pub use map::Tile;
+pub use map::TileMap;
+
+pub use replay::render_frames;
+use replay::render_frames_py;
+pub use replay::Event;
+pub use replay::EventType;
+pub use replay::SCHEMA_VERSION;
+
+mod encoding;
 mod entities;
 mod map;
 mod replay;
 
 use game::PlayerAnt;
+use game::PlayerStats;
 use game::StateEntity;
 use pyo3::prelude::*;
 
 #[pymodule]
 fn ants_ai(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Action>()?;
+    m.add_class::<ContestedFood>()?;
     m.add_class::<Direction>()?;
+    m.add_class::<DistanceMetric>()?;
+    m.add_class::<Event>()?;
+    m.add_class::<EventType>()?;
     m.add_class::<FinishedReason>()?;
+    m.add_class::<FoodSpawnStrategy>()?;
     m.add_class::<Game>()?;
     m.add_class::<GameState>()?;
+    m.add_class::<HillRazeMode>()?;
+    m.add_class::<MapTransform>()?;
+    m.add_class::<Tile>()?;
+    m.add_class::<TileMap>()?;
     m.add_class::<PlayerAnt>()?;
+    m.add_class::<PlayerStats>()?;
+    m.add_class::<StartingScore>()?;
+    m.add_class::<StateArrays>()?;
+    m.add_class::<TurnPhase>()?;
     m.add_class::<TurnStats>()?;
     m.add_class::<StateEntity>()?;
+    m.add_function(wrap_pyfunction!(render_frames_py, m)?)?;
+    m.add_function(wrap_pyfunction!(transform_map_py, m)?)?;
+    m.add_function(wrap_pyfunction!(translate_map_py, m)?)?;
+    m.add("SCHEMA_VERSION", SCHEMA_VERSION)?;
     Ok(())
 }
@@ -1,62 +1,388 @@
-use crate::entities::{from_char, player_to_color, Ant, Entity, Hill};
+use crate::entities::{from_char, Ant, Entity, Food, Hill, Water};
+use crate::render::RenderTheme;
 use crossterm::{
     cursor::Hide,
     execute,
     style::{Color, Print, SetForegroundColor},
     terminal::{Clear, ClearType},
 };
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rand::distributions::{Distribution, Standard};
+use rand::Rng;
 use regex::Regex;
+use std::collections::{HashMap, VecDeque};
 use std::io::{stdout, Write};
+use uuid::Uuid;
+
+/// Represents the direction an ant can move.
+#[derive(Clone, Debug, PartialEq)]
+#[pyclass(module = "ants_engine", eq, eq_int)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// Returns all the directions, in the order North, East, South and West.
+    pub(crate) fn all() -> Vec<Direction> {
+        vec![
+            Direction::North,
+            Direction::East,
+            Direction::South,
+            Direction::West,
+        ]
+    }
+
+    /// Returns the cell one step away from `from` in this direction, on a bounded map.
+    /// Stepping off the top or left edge of the map stays on `from`, and stepping off the bottom or right edge
+    /// returns a cell outside the map, neither of which is ever a valid move. See `Map::step` for wrapping maps.
+    pub(crate) fn step(&self, from: (usize, usize)) -> (usize, usize) {
+        match self {
+            Direction::North => (from.0.saturating_sub(1), from.1),
+            Direction::East => (from.0, from.1 + 1),
+            Direction::South => (from.0 + 1, from.1),
+            Direction::West => (from.0, from.1.saturating_sub(1)),
+        }
+    }
+}
+
+impl Distribution<Direction> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Direction {
+        match rng.gen_range(0..4) {
+            0 => Direction::North,
+            1 => Direction::East,
+            2 => Direction::South,
+            _ => Direction::West,
+        }
+    }
+}
+
+/// Represents how the distance between two cells is measured for the field of vision, attack and food ranges.
+///
+/// Ranges are always given as a radius **squared**, whatever the metric, so that the same value means the same radius:
+/// a cell is within range when the distance to it, squared, is at most the given radius squared.
+#[derive(Clone, Debug, PartialEq)]
+#[pyclass(module = "ants_engine", eq, eq_int)]
+pub enum DistanceMetric {
+    /// The straight line distance, i.e. `sqrt(rows^2 + cols^2)`. This is the default.
+    Euclidean2,
+    /// The number of steps when only moving in the 4 cardinal directions, i.e. `rows + cols`.
+    Manhattan,
+    /// The number of steps when also moving diagonally, i.e. `max(rows, cols)`.
+    Chebyshev,
+}
+
+impl DistanceMetric {
+    /// Returns whether the cell at the given row and column offsets is within the radius **squared**.
+    ///
+    /// # Arguments
+    /// * `rows` - The difference in rows between the two cells.
+    /// * `cols` - The difference in columns between the two cells.
+    /// * `radius2` - The radius **squared**.
+    pub fn within(&self, rows: usize, cols: usize, radius2: usize) -> bool {
+        self.distance2(rows, cols) <= radius2
+    }
+
+    /// Returns the distance **squared** to the cell at the given row and column offsets.
+    ///
+    /// # Arguments
+    /// * `rows` - The difference in rows between the two cells.
+    /// * `cols` - The difference in columns between the two cells.
+    pub fn distance2(&self, rows: usize, cols: usize) -> usize {
+        match self {
+            DistanceMetric::Euclidean2 => rows.pow(2) + cols.pow(2),
+            DistanceMetric::Manhattan => (rows + cols).pow(2),
+            DistanceMetric::Chebyshev => rows.max(cols).pow(2),
+        }
+    }
+}
 
 pub struct Map {
     width: usize,
     height: usize,
     players: usize,
     grid: Vec<Option<Box<dyn Entity>>>,
+    /// Whether the map is toroidal, i.e. stepping off an edge comes back on the opposite edge.
+    wrap: bool,
+    symmetries: Vec<Symmetry>,
+    /// The points each player starts with on top of the starting score, from the optional `bonus` lines.
+    start_bonus: Vec<usize>,
+}
+
+/// Represents a transformation under which the terrain of a map (water and hills) stays the same.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Symmetry {
+    /// The map looks the same after rotating it 90 degrees clockwise. Only possible for square maps.
+    Rotation90,
+    /// The map looks the same after rotating it 180 degrees.
+    Rotation180,
+    /// The map looks the same after rotating it 270 degrees clockwise. Only possible for square maps.
+    Rotation270,
+    /// The map looks the same after mirroring it from left to right.
+    MirrorHorizontal,
+    /// The map looks the same after mirroring it from top to bottom.
+    MirrorVertical,
+}
+
+/// Represents why an ant could not move to a cell.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlockedMoveReason {
+    /// The destination is outside the map.
+    OutOfBounds,
+    /// There is no live ant at the starting cell.
+    NoLiveAnt,
+    /// The destination is water.
+    Water,
+    /// The destination has food on it.
+    Food,
+    /// The destination has a dead ant on it.
+    DeadAnt,
+}
+
+/// Represents a transformation of a map, e.g. to generate variants of a map for data augmentation.
+#[derive(Clone, Debug, PartialEq)]
+#[pyclass(module = "ants_engine", eq, eq_int)]
+pub enum MapTransform {
+    /// Rotates the map 90 degrees clockwise, swapping its width and height.
+    Rotate90,
+    /// Mirrors the map from left to right.
+    MirrorHorizontal,
+    /// Mirrors the map from top to bottom.
+    MirrorVertical,
+}
+
+/// Transforms a map, moving every entity to its new location while keeping its owner.
+/// The result is in the same format as the given map, so it can be used to create a new game.
+///
+/// # Arguments
+/// * `map_contents` - The contents of the map to transform.
+/// * `transform` - The transformation to apply.
+/// * `show_ants_on_hill` - Whether ants standing on their hill are written as such, see `Map::to_contents`.
+///
+/// Returns an error if the map is malformed.
+pub fn transform_map(
+    map_contents: &str,
+    transform: MapTransform,
+    show_ants_on_hill: bool,
+) -> Result<String, String> {
+    let map = Map::parse(map_contents)?;
+    let map = match transform {
+        MapTransform::Rotate90 => map.rotate90(),
+        MapTransform::MirrorHorizontal => map.mirror_horizontal(),
+        MapTransform::MirrorVertical => map.mirror_vertical(),
+    };
+
+    Ok(map.to_contents(show_ants_on_hill))
+}
+
+/// Transforms a map, see `transform_map`. `show_ants_on_hill` defaults to `true`.
+/// In Python, a malformed map raises a `ValueError`.
+#[pyfunction]
+#[pyo3(name = "transform_map", signature = (map_contents, transform, show_ants_on_hill=true))]
+pub fn transform_map_py(
+    map_contents: &str,
+    transform: MapTransform,
+    show_ants_on_hill: bool,
+) -> PyResult<String> {
+    transform_map(map_contents, transform, show_ants_on_hill).map_err(PyValueError::new_err)
+}
+
+/// Represents what a character of a map stands for, see `TileMap`.
+#[derive(Clone, Debug, PartialEq)]
+#[pyclass(module = "ants_engine", eq)]
+pub enum Tile {
+    /// An empty land cell.
+    Land(),
+    Water(),
+    Food(),
+    /// An ant of the given player.
+    Ant(usize),
+    /// An ant of the given player standing on its hill.
+    AntOnHill(usize),
+    /// A hill of the given player.
+    Hill(usize),
+}
+
+impl Tile {
+    fn to_entity(&self) -> Option<Box<dyn Entity>> {
+        match self {
+            Tile::Land() => None,
+            Tile::Water() => Some(Box::new(Water)),
+            Tile::Food() => Some(Box::new(Food)),
+            Tile::Ant(player) => Some(Box::new(Ant::new(
+                Uuid::new_v4().to_string(),
+                *player,
+                true,
+                None,
+            ))),
+            Tile::AntOnHill(player) => Some(Box::new(Ant::new(
+                Uuid::new_v4().to_string(),
+                *player,
+                true,
+                Some(Box::new(Hill::new(*player, true))),
+            ))),
+            Tile::Hill(player) => Some(Box::new(Hill::new(*player, true))),
+        }
+    }
+}
+
+/// Represents the characters of a map and the tiles they stand for, e.g. to load maps authored in other tools.
+/// Characters that are not in the table keep their usual meaning, e.g. `%` for water.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[pyclass(module = "ants_engine", eq)]
+pub struct TileMap {
+    tiles: HashMap<char, Tile>,
+}
+
+#[pymethods]
+impl TileMap {
+    /// Creates a table where each of the given characters stands for its tile, overriding its usual meaning if any.
+    ///
+    /// # Arguments
+    /// * `tiles` - The tile each character stands for.
+    #[new]
+    pub fn new(tiles: HashMap<char, Tile>) -> TileMap {
+        TileMap { tiles }
+    }
+}
+
+impl TileMap {
+    /// Returns the entity the character stands for, `None` for land, or an error if the character stands for nothing.
+    fn to_entity(&self, value: char) -> Result<Option<Box<dyn Entity>>, String> {
+        match self.tiles.get(&value) {
+            Some(tile) => Ok(tile.to_entity()),
+            None => from_char(value),
+        }
+    }
+}
+
+/// Translates a map that uses custom characters to the usual ones, so that it can be used to create a new game.
+///
+/// # Arguments
+/// * `map_contents` - The contents of the map to translate.
+/// * `tile_map` - The tile each custom character stands for. Other characters keep their usual meaning.
+/// * `show_ants_on_hill` - Whether ants standing on their hill are written as such, see `Map::to_contents`.
+///
+/// Returns an error if the map is malformed, e.g. if it has a character that is neither usual nor in the table.
+pub fn translate_map(
+    map_contents: &str,
+    tile_map: &TileMap,
+    show_ants_on_hill: bool,
+) -> Result<String, String> {
+    Ok(Map::parse_with_tiles(map_contents, tile_map)?.to_contents(show_ants_on_hill))
+}
+
+/// Translates a map, see `translate_map`. `show_ants_on_hill` defaults to `true`.
+/// In Python, a malformed map raises a `ValueError`.
+#[pyfunction]
+#[pyo3(name = "translate_map", signature = (map_contents, tile_map, show_ants_on_hill=true))]
+pub fn translate_map_py(
+    map_contents: &str,
+    tile_map: &TileMap,
+    show_ants_on_hill: bool,
+) -> PyResult<String> {
+    translate_map(map_contents, tile_map, show_ants_on_hill).map_err(PyValueError::new_err)
+}
+
+/// Parses a number of the map's header, which is only matched as digits but can still be too large.
+fn parse_number(value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("The map has an invalid number {}.", value))
+}
+
+impl Symmetry {
+    fn apply(&self, cell: (usize, usize), width: usize, height: usize) -> (usize, usize) {
+        let (row, col) = cell;
+        match self {
+            Symmetry::Rotation90 => (col, width - 1 - row),
+            Symmetry::Rotation180 => (height - 1 - row, width - 1 - col),
+            Symmetry::Rotation270 => (height - 1 - col, row),
+            Symmetry::MirrorHorizontal => (row, width - 1 - col),
+            Symmetry::MirrorVertical => (height - 1 - row, col),
+        }
+    }
 }
 
 impl Map {
-    pub fn parse(map_contents: &str) -> Map {
+    /// Parses the map, returning a descriptive error if it's malformed, e.g. if it has an invalid character.
+    pub fn parse(map_contents: &str) -> Result<Map, String> {
+        Map::parse_with_tiles(map_contents, &TileMap::default())
+    }
+
+    /// Parses the map with the given table of characters, overriding the usual meaning of some of them.
+    pub fn parse_with_tiles(map_contents: &str, tile_map: &TileMap) -> Result<Map, String> {
         let metadata = Regex::new(r"rows (\d+)\s+cols (\d+)")
             .unwrap()
             .captures(map_contents)
-            .unwrap();
+            .ok_or("The map must start with its `rows` and `cols` lines.")?;
 
-        let height = metadata.get(1).unwrap().as_str().parse().unwrap();
-        let width = metadata.get(2).unwrap().as_str().parse().unwrap();
+        let height = parse_number(metadata.get(1).unwrap().as_str())?;
+        let width = parse_number(metadata.get(2).unwrap().as_str())?;
 
         let players = Regex::new(r"players (\d+)")
             .unwrap()
             .captures(map_contents)
+            .ok_or("The map must have a `players` line.")?;
+        let players = parse_number(players.get(1).unwrap().as_str())?;
+
+        // Maps are bounded unless their header declares otherwise with an optional `wrap 1` line
+        let wrap = Regex::new(r"(?m)^\s*wrap (\d+)")
             .unwrap()
-            .get(1)
-            .unwrap()
-            .as_str()
-            .parse()
-            .unwrap();
+            .captures(map_contents)
+            .is_some_and(|captures| captures.get(1).unwrap().as_str() != "0");
 
         let mut map = Map::new(width, height, players);
+        map.wrap = wrap;
 
-        Regex::new(r"m (.*)")
+        // Maps can give players a head start with optional `bonus <player> <points>` lines
+        for captures in Regex::new(r"(?m)^[ \t]*bonus[ \t]+(\d+)[ \t]+(\d+)")
             .unwrap()
             .captures_iter(map_contents)
-            .map(|captures| captures.get(1).unwrap().as_str().trim())
-            .enumerate()
-            .for_each(|(row, line)| {
-                line.chars().enumerate().for_each(|(col, value)| {
-                    if let Some(entity) = from_char(value) {
-                        map.set(row, col, entity);
-                    }
-                });
-            });
+        {
+            let player = parse_number(captures.get(1).unwrap().as_str())?;
+            if player >= players {
+                return Err(format!(
+                    "The bonus is for player {}, but the map only has {} players.",
+                    player, players
+                ));
+            }
+            map.start_bonus[player] = parse_number(captures.get(2).unwrap().as_str())?;
+        }
 
-        map
+        // Rows are the lines starting with `m`, which can be indented and separated from their contents by any
+        // spaces or tabs. Whitespace around the contents is not part of the row, but the characters in between are
+        let lines = Regex::new(r"(?m)^[ \t]*m[ \t]+(.*?)[ \t\r]*$").unwrap();
+        let lines = lines
+            .captures_iter(map_contents)
+            .map(|captures| captures.get(1).unwrap().as_str());
+        for (row, line) in lines.enumerate() {
+            for (col, value) in line.chars().enumerate() {
+                if row >= height || col >= width {
+                    return Err(format!(
+                        "The map has a cell at ({}, {}), outside its {} rows and {} cols.",
+                        row, col, height, width
+                    ));
+                }
+
+                if let Some(entity) = tile_map.to_entity(value)? {
+                    map.set(row, col, entity);
+                }
+            }
+        }
+
+        map.symmetries = map.detect_symmetries();
+
+        Ok(map)
     }
 
-    pub fn get(&self, row: usize, col: usize) -> Option<&Box<dyn Entity>> {
+    pub fn get(&self, row: usize, col: usize) -> Option<&dyn Entity> {
         self.grid
             .get(row * self.width + col)
-            .and_then(|opt| opt.as_ref())
+            .and_then(|opt| opt.as_deref())
     }
 
     pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut Box<dyn Entity>> {
@@ -69,6 +395,16 @@ impl Map {
         self.grid[row * self.width + col] = Some(value);
     }
 
+    pub fn try_set(&mut self, row: usize, col: usize, value: Box<dyn Entity>) -> bool {
+        // Only empty cells can be set, to avoid silently overwriting another entity
+        if self.get(row, col).is_some() {
+            return false;
+        }
+
+        self.set(row, col, value);
+        true
+    }
+
     pub fn remove(&mut self, row: usize, col: usize) {
         self.grid[row * self.width + col] = None;
     }
@@ -81,10 +417,61 @@ impl Map {
         self.height
     }
 
+    /// Returns whether the map is toroidal, i.e. stepping off an edge comes back on the opposite edge.
+    /// Only the compact prototype reads it, the game steps through `step` instead.
+    #[cfg(any(test, feature = "bench"))]
+    pub fn wraps(&self) -> bool {
+        self.wrap
+    }
+
+    /// Returns the cell one step away from `from` in the given direction.
+    /// On a wrapping map, stepping off an edge comes back on the opposite edge, see `Direction::step` otherwise.
+    pub fn step(&self, from: (usize, usize), direction: &Direction) -> (usize, usize) {
+        if !self.wrap || !self.in_bounds(from) {
+            return direction.step(from);
+        }
+
+        let (row, col) = from;
+        match direction {
+            Direction::North => ((row + self.height - 1) % self.height, col),
+            Direction::East => (row, (col + 1) % self.width),
+            Direction::South => ((row + 1) % self.height, col),
+            Direction::West => (row, (col + self.width - 1) % self.width),
+        }
+    }
+
+    /// Returns the number of rows and columns between two cells, going around the edges when the map wraps.
+    pub fn abs_diff(&self, from: (usize, usize), to: (usize, usize)) -> (usize, usize) {
+        (
+            axis_offset(from.0, to.0, self.height, self.wrap).unsigned_abs(),
+            axis_offset(from.1, to.1, self.width, self.wrap).unsigned_abs(),
+        )
+    }
+
     pub fn players(&self) -> usize {
         self.players
     }
 
+    /// Returns the points each player starts with on top of the starting score, `0` unless the map declares a bonus.
+    pub fn start_bonus(&self) -> &[usize] {
+        &self.start_bonus
+    }
+
+    pub fn symmetric_images(&self, row: usize, col: usize) -> Vec<(usize, usize)> {
+        // The images of a cell are the cell itself plus where it ends up under each of the map's symmetries
+        // Since the symmetries of a map form a group, this is the same for every cell in the set of images
+        let mut images = vec![(row, col)];
+
+        for symmetry in &self.symmetries {
+            let image = symmetry.apply((row, col), self.width, self.height);
+            if !images.contains(&image) {
+                images.push(image);
+            }
+        }
+
+        images
+    }
+
     pub fn ant_hills(&self) -> Vec<(&dyn Entity, usize, usize)> {
         self.all(|entity| matches!(entity.name(), "Hill"))
     }
@@ -93,6 +480,27 @@ impl Map {
         self.all(|entity| matches!(entity.name(), "Ant"))
     }
 
+    pub fn entities(&self) -> Vec<(&dyn Entity, usize, usize)> {
+        self.all(|_| true)
+    }
+
+    /// Returns the number of hills of each player, including razed hills and hills with an ant on them.
+    pub fn hills_per_player(&self) -> Vec<usize> {
+        let mut hills = vec![0; self.players];
+        for entity in self.grid.iter().flatten() {
+            let hill = match entity.name() {
+                "Hill" => Some(entity.as_ref()),
+                _ => entity.on_ant_hill(),
+            };
+
+            if let Some(player) = hill.and_then(|hill| hill.player()) {
+                hills[player] += 1;
+            }
+        }
+
+        hills
+    }
+
     pub fn food(&self) -> Vec<(usize, usize)> {
         self.all(|entity| matches!(entity.name(), "Food"))
             .into_iter()
@@ -123,28 +531,54 @@ impl Map {
         // If it is, add it to the list of coordinates
         let mut lands = Vec::new();
 
+        // An empty map has no land at all
+        if self.width == 0 || self.height == 0 {
+            return lands;
+        }
+
         // For each coordinate around the given one in all 8 directions
         for i in -1..=1 {
             for j in -1..=1 {
-                let n_row = row as i32 + i;
-                let n_col = col as i32 + j;
-
                 // Skip if the coordinate is out of bounds
-                if n_row < 0
-                    || n_row >= self.height as i32
-                    || n_col < 0
-                    || n_col >= self.width as i32
-                {
+                let Some((n_row, n_col)) = self.offset_cell((row, col), (i, j)) else {
                     continue;
-                }
+                };
 
-                // Skip if the cell is not empty
-                if self.get(n_row as usize, n_col as usize).is_some() {
+                // Skip if the cell is not empty, or if it was already found by going around a small wrapping map
+                if self.get(n_row, n_col).is_some() || lands.contains(&(n_row, n_col)) {
                     continue;
                 }
 
                 // If the cell is empty then it's land
-                lands.push((n_row as usize, n_col as usize));
+                lands.push((n_row, n_col));
+            }
+        }
+
+        lands
+    }
+
+    pub fn land_in_ring(&self, row: usize, col: usize, distance: usize) -> Vec<(usize, usize)> {
+        // The ring is made of the cells that are exactly `distance` steps away in any of the 8 directions
+        let mut lands = Vec::new();
+        let distance = distance as isize;
+
+        for i in -distance..=distance {
+            for j in -distance..=distance {
+                if i.abs() != distance && j.abs() != distance {
+                    continue;
+                }
+
+                // Skip if the coordinate is out of bounds
+                let Some((n_row, n_col)) = self.offset_cell((row, col), (i, j)) else {
+                    continue;
+                };
+
+                // Skip if the cell is not empty, or if it was already found by going around a small wrapping map
+                if self.get(n_row, n_col).is_some() || lands.contains(&(n_row, n_col)) {
+                    continue;
+                }
+
+                lands.push((n_row, n_col));
             }
         }
 
@@ -155,38 +589,278 @@ impl Map {
         &self,
         center: (usize, usize),
         radius2: usize,
+        metric: &DistanceMetric,
+        line_of_sight: bool,
     ) -> Vec<(&dyn Entity, usize, usize)> {
         let (row, col) = center;
-        let radius = (radius2 as f64).sqrt() as usize;
         let mut fov = Vec::new();
 
+        // Nothing can be seen on an empty map
+        if self.width == 0 || self.height == 0 {
+            return fov;
+        }
+
         // Compute the field of vision around the center coordinate
-        // These are all the entities that are within the radius of the center
-        // i.e. the entities whose coordinates are at most `radius` distance away from the center
-        // using the euclidean distance formula: (x1 - x2)^2 + (y1 - y2)^2 <= radius^2
-        for i in row.saturating_sub(radius)..=(row + radius).min(self.height - 1) {
-            for j in col.saturating_sub(radius)..=(col + radius).min(self.width - 1) {
-                if (i as i32 - row as i32).pow(2) + (j as i32 - col as i32).pow(2) <= radius2 as i32
-                {
-                    if let Some(entity) = self.get(i, j) {
-                        // If the entity is on a hill (i.e. an ant on a hill), include the hill in the field of vision
-                        if let Some(hill) = entity.on_ant_hill() {
-                            fov.push((hill.as_ref(), i, j));
-                        }
+        // These are all the entities in the cells that are within the radius of the center
+        for (i, j) in self.cells_within(center, radius2, metric, line_of_sight) {
+            if let Some(entity) = self.get(i, j) {
+                // If the entity is on a hill (i.e. an ant on a hill), include the hill in the field of vision
+                if let Some(hill) = entity.on_ant_hill() {
+                    fov.push((hill, i, j));
+                }
+
+                // Skip the actual entity if it's the given center coordinate
+                if i == row && j == col {
+                    continue;
+                }
+
+                // Add the entity to the field of vision
+                fov.push((entity, i, j));
+            }
+        }
+
+        fov
+    }
+
+    /// Returns all the cells, including the center, that are within the radius of the center.
+    /// With `line_of_sight`, the cells hidden behind water are excluded, see `in_line_of_sight`.
+    pub fn cells_within(
+        &self,
+        center: (usize, usize),
+        radius2: usize,
+        metric: &DistanceMetric,
+        line_of_sight: bool,
+    ) -> Vec<(usize, usize)> {
+        cells_within(
+            (self.width, self.height),
+            self.wrap,
+            center,
+            radius2,
+            metric,
+            line_of_sight,
+            |cell| self.is_water(cell),
+        )
+    }
+
+    /// Returns whether `to` can be seen from `from`, i.e. there is no water on the line between them.
+    /// The line is traced with Bresenham's algorithm, and the cells at both ends never block the view so water itself can be seen.
+    /// On a wrapping map, the line goes the shortest way around the edges.
+    pub fn in_line_of_sight(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        in_line_of_sight((self.width, self.height), self.wrap, from, to, |cell| {
+            self.is_water(cell)
+        })
+    }
+
+    /// Returns the directions to follow from `from` to reach `to` in the fewest moves, or `None` if it can't be reached.
+    /// Water is always impassable, while food and ants are only impassable if `avoid_entities` is `true`.
+    /// The destination itself is always considered passable unless it's water, so that paths can lead to food or enemies.
+    pub fn shortest_path(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+        avoid_entities: bool,
+    ) -> Option<Vec<Direction>> {
+        if !self.in_bounds(from) || !self.in_bounds(to) || !self.is_passable(to, false) {
+            return None;
+        }
+
+        if from == to {
+            return Some(Vec::new());
+        }
+
+        let (parents, _) = self.breadth_first_search(from, |cell| cell == to, avoid_entities);
+        parents[self.index(to)].as_ref()?;
+
+        // Walk back from the destination to the origin following the parents
+        let mut path = Vec::new();
+        let mut current = to;
+        while current != from {
+            let (direction, parent) = parents[self.index(current)].clone().unwrap();
+            path.push(direction);
+            current = parent;
+        }
+        path.reverse();
 
-                        // Skip the actual entity if it's the given center coordinate
-                        if i == row && j == col {
-                            continue;
+        Some(path)
+    }
+
+    /// Returns the number of moves of the shortest path from `from` to `to`, or `None` if it can't be reached.
+    /// See `shortest_path` for which cells are passable.
+    pub fn distance(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+        avoid_entities: bool,
+    ) -> Option<usize> {
+        self.shortest_path(from, to, avoid_entities)
+            .map(|path| path.len())
+    }
+
+    /// Returns the food closest to `from` by the number of moves, or `None` if no food can be reached.
+    /// Ties are broken by exploring the directions in the order North, East, South and West.
+    ///
+    /// # Arguments
+    /// * `from` - The `(row, col)` to start from.
+    /// * `is_candidate` - Whether a given food location should be considered.
+    pub fn nearest_food(
+        &self,
+        from: (usize, usize),
+        is_candidate: impl Fn((usize, usize)) -> bool,
+    ) -> Option<(usize, usize)> {
+        if !self.in_bounds(from) {
+            return None;
+        }
+
+        let is_food = |(row, col): (usize, usize)| {
+            self.get(row, col)
+                .is_some_and(|entity| entity.name() == "Food")
+        };
+        let (_, food) =
+            self.breadth_first_search(from, |cell| is_food(cell) && is_candidate(cell), false);
+
+        food
+    }
+
+    /// Returns the connected region of each cell, as a grid of region ids, where water belongs to no region.
+    /// Two cells are in the same region if an ant can walk from one to the other, ignoring food and other ants.
+    /// Regions are numbered from 0 in the order their first cell appears, row by row.
+    pub fn regions(&self) -> Vec<Vec<Option<usize>>> {
+        let mut regions = vec![vec![None; self.width]; self.height];
+        let mut next_region = 0;
+
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if regions[row][col].is_some() || !self.is_passable((row, col), false) {
+                    continue;
+                }
+
+                // Flood fill the new region from this cell
+                regions[row][col] = Some(next_region);
+                let mut queue = VecDeque::from([(row, col)]);
+                while let Some(cell) = queue.pop_front() {
+                    for (_, (n_row, n_col)) in self.neighbors(cell) {
+                        if regions[n_row][n_col].is_none()
+                            && self.is_passable((n_row, n_col), false)
+                        {
+                            regions[n_row][n_col] = Some(next_region);
+                            queue.push_back((n_row, n_col));
                         }
+                    }
+                }
+
+                next_region += 1;
+            }
+        }
+
+        regions
+    }
+
+    /// Returns the id of the connected region of the given cell, see `regions`.
+    /// `None` if the cell is water or out of bounds.
+    pub fn region_of(&self, row: usize, col: usize) -> Option<usize> {
+        if !self.in_bounds((row, col)) {
+            return None;
+        }
+
+        self.regions()[row][col]
+    }
+
+    /// Returns the player controlling each cell and the number of cells controlled by each player.
+    /// A cell is controlled by the player with the closest live hill by the number of moves, even if there is an ant on the hill.
+    /// Cells that are equally close to hills of different players are contested and, like water and cells no hill can reach, are controlled by no one.
+    #[allow(clippy::type_complexity)]
+    pub fn territory(&self) -> (Vec<Vec<Option<usize>>>, Vec<usize>) {
+        // `None` means the cell was not reached yet, `Some(None)` that it is contested
+        let mut owners: Vec<Vec<Option<Option<usize>>>> = vec![vec![None; self.width]; self.height];
+        let mut distances = vec![vec![0; self.width]; self.height];
+        let mut queue = VecDeque::new();
+
+        // Start the search from all the live hills at the same time
+        for (row, col) in
+            (0..self.height).flat_map(|row| (0..self.width).map(move |col| (row, col)))
+        {
+            let hill = self.get(row, col).and_then(|entity| match entity.name() {
+                "Hill" => Some(entity),
+                _ => entity.on_ant_hill(),
+            });
 
-                        // Add the entity to the field of vision
-                        fov.push((entity.as_ref(), i, j));
+            if let Some(hill) = hill.filter(|hill| hill.alive().unwrap()) {
+                owners[row][col] = Some(hill.player());
+                queue.push_back((row, col));
+            }
+        }
+
+        while let Some((row, col)) = queue.pop_front() {
+            // All the cells at the same distance are reached before this one is expanded, so its owner is final
+            let owner = owners[row][col].unwrap();
+
+            for (_, (n_row, n_col)) in self.neighbors((row, col)) {
+                if !self.is_passable((n_row, n_col), false) {
+                    continue;
+                }
+
+                match owners[n_row][n_col] {
+                    None => {
+                        owners[n_row][n_col] = Some(owner);
+                        distances[n_row][n_col] = distances[row][col] + 1;
+                        queue.push_back((n_row, n_col));
+                    }
+                    // Reached at the same distance from a different player, so it's contested
+                    Some(other)
+                        if other != owner && distances[n_row][n_col] == distances[row][col] + 1 =>
+                    {
+                        owners[n_row][n_col] = Some(None);
                     }
+                    _ => {}
                 }
             }
         }
 
-        fov
+        let territory: Vec<Vec<Option<usize>>> = owners
+            .into_iter()
+            .map(|row| row.into_iter().map(|owner| owner.flatten()).collect())
+            .collect();
+        let mut areas = vec![0; self.players];
+        for player in territory.iter().flatten().flatten() {
+            areas[*player] += 1;
+        }
+
+        (territory, areas)
+    }
+
+    /// Returns the number of moves from each cell to the closest live hill of any player, even if there is an ant on the hill.
+    /// Water and cells no hill can reach have no distance.
+    pub fn hill_distances(&self) -> Vec<Vec<Option<usize>>> {
+        let mut distances = vec![vec![None; self.width]; self.height];
+        let mut queue = VecDeque::new();
+
+        // Start the search from all the live hills at the same time
+        for (row, col) in
+            (0..self.height).flat_map(|row| (0..self.width).map(move |col| (row, col)))
+        {
+            let hill = self.get(row, col).and_then(|entity| match entity.name() {
+                "Hill" => Some(entity),
+                _ => entity.on_ant_hill(),
+            });
+
+            if hill.is_some_and(|hill| hill.alive().unwrap()) {
+                distances[row][col] = Some(0);
+                queue.push_back((row, col));
+            }
+        }
+
+        while let Some((row, col)) = queue.pop_front() {
+            let distance = distances[row][col].unwrap();
+
+            for (_, (n_row, n_col)) in self.neighbors((row, col)) {
+                if distances[n_row][n_col].is_none() && self.is_passable((n_row, n_col), false) {
+                    distances[n_row][n_col] = Some(distance + 1);
+                    queue.push_back((n_row, n_col));
+                }
+            }
+        }
+
+        distances
     }
 
     pub fn move_entity(&mut self, from: (usize, usize), to: (usize, usize)) -> bool {
@@ -229,12 +903,14 @@ impl Map {
                 }
             };
 
-            Box::new(Ant::new(
+            let mut ant = Ant::new(
                 entity.id().to_string(),
                 entity.player().unwrap(),
                 entity.alive().unwrap(),
                 to_hill,
-            ))
+            );
+            ant.set_carrying(entity.carrying().unwrap());
+            Box::new(ant)
         };
         self.set(to.0, to.1, ant);
 
@@ -255,7 +931,15 @@ impl Map {
         true
     }
 
-    pub fn draw(&self, turn: usize, scores: &[usize], ants: &[usize], hive: &[usize]) {
+    pub fn draw(
+        &self,
+        turn: usize,
+        scores: &[isize],
+        ants: &[usize],
+        hive: &[usize],
+        names: &[String],
+        theme: &RenderTheme,
+    ) {
         let mut stdout = stdout();
 
         // Display information about the game
@@ -263,10 +947,7 @@ impl Map {
             stdout,
             Clear(ClearType::All),
             Hide,
-            Print("Players: "),
-            Print(self.players.to_string()),
-            Print("\nTurn: "),
-            Print(turn.to_string())
+            Print(self.game_summary(turn))
         )
         .unwrap();
 
@@ -274,16 +955,9 @@ impl Map {
         for player in 0..self.players {
             execute!(
                 stdout,
-                SetForegroundColor(player_to_color(player)),
-                Print("\nPlayer "),
-                Print(player.to_string()),
-                Print(": "),
-                Print("Score = "),
-                Print(scores[player].to_string()),
-                Print(", Ants = "),
-                Print(ants[player].to_string()),
-                Print(", Hive = "),
-                Print(hive[player].to_string()),
+                SetForegroundColor(theme.player_color(player)),
+                Print("\n"),
+                Print(self.player_summary(player, scores, ants, hive, names)),
                 SetForegroundColor(Color::Reset)
             )
             .unwrap();
@@ -293,11 +967,11 @@ impl Map {
         // Display the map
         for row in 0..self.height {
             for col in 0..self.width {
-                let entity = self.get(row, col);
+                let (symbol, color) = theme.symbol(self.get(row, col));
                 execute!(
                     stdout,
-                    SetForegroundColor(entity.map_or(Color::Reset, |entity| entity.color())),
-                    Print(entity.map_or('.', |entity| entity.char())),
+                    SetForegroundColor(color),
+                    Print(symbol),
                     SetForegroundColor(Color::Reset)
                 )
                 .unwrap();
@@ -308,97 +982,975 @@ impl Map {
         stdout.flush().unwrap();
     }
 
-    fn new(width: usize, height: usize, players: usize) -> Map {
-        let mut grid = Vec::with_capacity(width * height);
-        // Initialize the grid with `None` values
-        grid.resize_with(width * height, || None);
+    /// Renders the game to a plain string, i.e. the same visual as `draw` but without colors or terminal commands.
+    ///
+    /// # Arguments
+    /// * `turn` - The current turn.
+    /// * `scores` - The scores of each player.
+    /// * `ants` - The number of live ants of each player.
+    /// * `hive` - The amount of food in the hive of each player.
+    /// * `names` - The name of each player.
+    /// * `theme` - The symbols to render the map with, colors are ignored.
+    pub fn render_to_string(
+        &self,
+        turn: usize,
+        scores: &[isize],
+        ants: &[usize],
+        hive: &[usize],
+        names: &[String],
+        theme: &RenderTheme,
+    ) -> String {
+        let mut output = self.game_summary(turn);
+
+        for player in 0..self.players {
+            output.push('\n');
+            output.push_str(&self.player_summary(player, scores, ants, hive, names));
+        }
+        output.push_str("\n\n");
+
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let (symbol, _) = theme.symbol(self.get(row, col));
+                output.push(symbol);
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+
+    /// Returns the map rotated 90 degrees clockwise, with its width and height swapped.
+    /// Every entity keeps its owner and id, only its location changes.
+    pub fn rotate90(self) -> Map {
+        let (width, height) = (self.width, self.height);
+        self.transform(height, width, |(row, col)| (col, height - 1 - row))
+    }
+
+    /// Returns the map mirrored from left to right.
+    /// Every entity keeps its owner and id, only its location changes.
+    pub fn mirror_horizontal(self) -> Map {
+        let (width, height) = (self.width, self.height);
+        self.transform(width, height, |cell| {
+            Symmetry::MirrorHorizontal.apply(cell, width, height)
+        })
+    }
+
+    /// Returns the map mirrored from top to bottom.
+    /// Every entity keeps its owner and id, only its location changes.
+    pub fn mirror_vertical(self) -> Map {
+        let (width, height) = (self.width, self.height);
+        self.transform(width, height, |cell| {
+            Symmetry::MirrorVertical.apply(cell, width, height)
+        })
+    }
+
+    /// Returns the map in the same format it's parsed from.
+    ///
+    /// # Arguments
+    /// * `show_ants_on_hill` - Whether ants standing on their hill are written as such (e.g. `A`), otherwise they are written as plain ants (e.g. `a`) and their hill is left out.
+    pub fn to_contents(&self, show_ants_on_hill: bool) -> String {
+        // The default theme uses the same symbols as the map format
+        let theme = RenderTheme {
+            show_ants_on_hill,
+            ..RenderTheme::default()
+        };
+        let mut output = format!(
+            "rows {}\ncols {}\nplayers {}\n",
+            self.height, self.width, self.players
+        );
+        if self.wrap {
+            output.push_str("wrap 1\n");
+        }
+        for (player, bonus) in self.start_bonus.iter().enumerate() {
+            if *bonus > 0 {
+                output.push_str(&format!("bonus {} {}\n", player, bonus));
+            }
+        }
+
+        for row in 0..self.height {
+            output.push_str("m ");
+            for col in 0..self.width {
+                let (symbol, _) = theme.symbol(self.get(row, col));
+                output.push(symbol);
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+
+    /// Moves every entity to the cell given by `to` in a new map of the given size.
+    fn transform(
+        self,
+        width: usize,
+        height: usize,
+        to: impl Fn((usize, usize)) -> (usize, usize),
+    ) -> Map {
+        let mut map = Map::new(width, height, self.players);
+        map.start_bonus = self.start_bonus;
+        map.wrap = self.wrap;
+        for (index, entity) in self.grid.into_iter().enumerate() {
+            if let Some(entity) = entity {
+                let (row, col) = to((index / self.width, index % self.width));
+                map.set(row, col, entity);
+            }
+        }
+
+        map.symmetries = map.detect_symmetries();
+        map
+    }
+
+    fn game_summary(&self, turn: usize) -> String {
+        format!("Players: {}\nTurn: {}", self.players, turn)
+    }
+
+    fn player_summary(
+        &self,
+        player: usize,
+        scores: &[isize],
+        ants: &[usize],
+        hive: &[usize],
+        names: &[String],
+    ) -> String {
+        format!(
+            "{}: Score = {}, Ants = {}, Hive = {}",
+            names[player], scores[player], ants[player], hive[player]
+        )
+    }
+
+    fn new(width: usize, height: usize, players: usize) -> Map {
+        let mut grid = Vec::with_capacity(width * height);
+        // Initialize the grid with `None` values
+        grid.resize_with(width * height, || None);
+
+        Map {
+            width,
+            height,
+            players,
+            grid,
+            wrap: false,
+            symmetries: Vec::new(),
+            start_bonus: vec![0; players],
+        }
+    }
+
+    fn detect_symmetries(&self) -> Vec<Symmetry> {
+        // Only the terrain that never moves is considered, i.e. water and hills (even if there is an ant on them)
+        // The owner of a hill is ignored since symmetric maps assign a different player to each image of a hill
+        let terrain = |row: usize, col: usize| match self.get(row, col) {
+            Some(entity) if entity.name() == "Water" => 'w',
+            Some(entity) if entity.name() == "Hill" || entity.on_ant_hill().is_some() => 'h',
+            _ => '.',
+        };
+
+        let mut candidates = vec![
+            Symmetry::Rotation180,
+            Symmetry::MirrorHorizontal,
+            Symmetry::MirrorVertical,
+        ];
+        // Quarter rotations only map the grid onto itself when it's square
+        if self.width == self.height {
+            candidates.push(Symmetry::Rotation90);
+            candidates.push(Symmetry::Rotation270);
+        }
+
+        candidates
+            .into_iter()
+            .filter(|symmetry| {
+                (0..self.height).all(|row| {
+                    (0..self.width).all(|col| {
+                        let (i, j) = symmetry.apply((row, col), self.width, self.height);
+                        terrain(row, col) == terrain(i, j)
+                    })
+                })
+            })
+            .collect()
+    }
+
+    fn all(&self, filter: fn(&Box<dyn Entity>) -> bool) -> Vec<(&dyn Entity, usize, usize)> {
+        // Inefficient way to get all entities using some filter (linear time complexity)
+        // But it should be fine since maps are small, the largest having roughly 15K or so cells
+        // If we end up using larger maps, we will need to optimize this
+        self.grid
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entity)| {
+                if let Some(entity) = entity {
+                    if filter(entity) {
+                        let row = index / self.width;
+                        let col = index % self.width;
+                        return Some((entity.as_ref(), row, col));
+                    }
+                }
+                None
+            })
+            .collect()
+    }
+
+    fn index(&self, cell: (usize, usize)) -> usize {
+        cell.0 * self.width + cell.1
+    }
+
+    fn in_bounds(&self, cell: (usize, usize)) -> bool {
+        cell.0 < self.height && cell.1 < self.width
+    }
+
+    /// Returns the cell at the given `(rows, cols)` offset from `cell`, going around the edges when the map wraps,
+    /// or `None` if it's outside a bounded map.
+    fn offset_cell(&self, cell: (usize, usize), offset: (isize, isize)) -> Option<(usize, usize)> {
+        offset_cell((self.width, self.height), self.wrap, cell, offset)
+    }
+
+    fn neighbors(&self, cell: (usize, usize)) -> Vec<(Direction, (usize, usize))> {
+        let (row, col) = cell;
+        let mut neighbors = Vec::with_capacity(4);
+
+        if self.wrap {
+            // Every cell has 4 neighbors, and on a map 1 or 2 cells wide the same cell can be one twice
+            for direction in Direction::all() {
+                let neighbor = self.step(cell, &direction);
+                if neighbor != cell && !neighbors.iter().any(|(_, other)| *other == neighbor) {
+                    neighbors.push((direction, neighbor));
+                }
+            }
+
+            return neighbors;
+        }
+
+        // Always in the same order so that the search is deterministic
+        if row > 0 {
+            neighbors.push((Direction::North, (row - 1, col)));
+        }
+        if col + 1 < self.width {
+            neighbors.push((Direction::East, (row, col + 1)));
+        }
+        if row + 1 < self.height {
+            neighbors.push((Direction::South, (row + 1, col)));
+        }
+        if col > 0 {
+            neighbors.push((Direction::West, (row, col - 1)));
+        }
+
+        neighbors
+    }
+
+    fn is_water(&self, cell: (usize, usize)) -> bool {
+        self.get(cell.0, cell.1)
+            .is_some_and(|entity| entity.name() == "Water")
+    }
+
+    fn is_passable(&self, cell: (usize, usize), avoid_entities: bool) -> bool {
+        match self.get(cell.0, cell.1) {
+            None => true,
+            Some(entity) if entity.name() == "Water" => false,
+            // Hills and corpses can be walked over just like land
+            Some(entity) if matches!(entity.name(), "Hill" | "Corpse") => true,
+            Some(_) => !avoid_entities,
+        }
+    }
+
+    /// Returns, for each cell, the direction and the cell it was reached from, or `None` if it wasn't reached.
+    /// Also returns the first cell reached that is a target, if any, in which case the search stops early.
+    /// Targets are always reachable unless they are water.
+    #[allow(clippy::type_complexity)]
+    fn breadth_first_search(
+        &self,
+        from: (usize, usize),
+        is_target: impl Fn((usize, usize)) -> bool,
+        avoid_entities: bool,
+    ) -> (
+        Vec<Option<(Direction, (usize, usize))>>,
+        Option<(usize, usize)>,
+    ) {
+        let mut parents = vec![None; self.width * self.height];
+        let mut visited = vec![false; self.width * self.height];
+        let mut queue = VecDeque::from([from]);
+        visited[self.index(from)] = true;
+
+        while let Some(cell) = queue.pop_front() {
+            if cell != from && is_target(cell) {
+                return (parents, Some(cell));
+            }
+
+            for (direction, neighbor) in self.neighbors(cell) {
+                let index = self.index(neighbor);
+                if visited[index] || !self.is_passable(neighbor, false) {
+                    continue;
+                }
+
+                if !is_target(neighbor) && !self.is_passable(neighbor, avoid_entities) {
+                    continue;
+                }
+
+                visited[index] = true;
+                parents[index] = Some((direction, cell));
+                queue.push_back(neighbor);
+            }
+        }
+
+        (parents, None)
+    }
+
+    /// Returns why an ant can't move from `from` to `to`, or `None` if the move is valid.
+    pub fn blocked_move_reason(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+    ) -> Option<BlockedMoveReason> {
+        // Staying in place only happens when moving off the edge of the map
+        if from == to || to.0 >= self.height || to.1 >= self.width {
+            return Some(BlockedMoveReason::OutOfBounds);
+        }
+
+        // Only alive ants can move
+        let is_live_ant = from.0 < self.height
+            && from.1 < self.width
+            && self.get(from.0, from.1).is_some_and(|from| {
+                from.name() == "Ant" && from.alive().is_some_and(|alive| alive)
+            });
+        if !is_live_ant {
+            return Some(BlockedMoveReason::NoLiveAnt);
+        }
+
+        // Water, food or a dead ant blocks the movement
+        match self.get(to.0, to.1) {
+            Some(to) if to.name() == "Water" => Some(BlockedMoveReason::Water),
+            Some(to) if to.name() == "Food" => Some(BlockedMoveReason::Food),
+            Some(to) if to.name() == "Ant" && !to.alive().unwrap() => {
+                Some(BlockedMoveReason::DeadAnt)
+            }
+            _ => None,
+        }
+    }
+
+    fn is_valid_move(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        self.blocked_move_reason(from, to).is_none()
+    }
+}
+
+/// Returns all the cells of a map of the given `(width, height)` that are within the radius of the center, see `Map::cells_within`.
+/// It only needs to know where the water is, so it's shared by every representation of the map.
+pub(crate) fn cells_within(
+    (width, height): (usize, usize),
+    wrap: bool,
+    center: (usize, usize),
+    radius2: usize,
+    metric: &DistanceMetric,
+    line_of_sight: bool,
+    is_water: impl Fn((usize, usize)) -> bool,
+) -> Vec<(usize, usize)> {
+    let (row, col) = center;
+    let radius = (radius2 as f64).sqrt() as usize;
+    let mut cells = Vec::new();
+
+    // An empty map has no cells, and the bounds below would underflow
+    if width == 0 || height == 0 {
+        return cells;
+    }
+
+    // These are the cells whose coordinates are at most `radius` distance away from the center
+    // using the given distance metric, e.g. for euclidean: (x1 - x2)^2 + (y1 - y2)^2 <= radius^2
+    // The cells at most `radius` rows and columns away from the center contain the cells within range for all metrics
+    let offsets = |center: usize, size: usize| -> Vec<isize> {
+        match wrap {
+            // Each cell is only reached once, the shortest way around, even if the radius is larger than the map
+            true => {
+                let before = radius.min((size - 1) / 2) as isize;
+                let after = radius.min(size / 2) as isize;
+                (-before..=after).collect()
+            }
+            false => {
+                let before = radius.min(center) as isize;
+                let after = radius.min(size - 1 - center) as isize;
+                (-before..=after).collect()
+            }
+        }
+    };
+    let row_offsets = offsets(row, height);
+    let col_offsets = offsets(col, width);
+
+    for i in &row_offsets {
+        for j in &col_offsets {
+            if metric.within(i.unsigned_abs(), j.unsigned_abs(), radius2)
+                && (!line_of_sight
+                    || trace_line_of_sight((width, height), wrap, center, (*i, *j), &is_water))
+            {
+                cells.push(offset_cell((width, height), wrap, center, (*i, *j)).unwrap());
+            }
+        }
+    }
+
+    // Going around the edges reaches cells in a different order, so they are sorted in board order like on a bounded map
+    if wrap {
+        cells.sort();
+    }
+
+    cells
+}
+
+/// Returns whether `to` can be seen from `from` given where the water is, see `Map::in_line_of_sight`.
+pub(crate) fn in_line_of_sight(
+    (width, height): (usize, usize),
+    wrap: bool,
+    from: (usize, usize),
+    to: (usize, usize),
+    is_water: impl Fn((usize, usize)) -> bool,
+) -> bool {
+    let offset = (
+        axis_offset(from.0, to.0, height, wrap),
+        axis_offset(from.1, to.1, width, wrap),
+    );
+    trace_line_of_sight((width, height), wrap, from, offset, is_water)
+}
+
+/// Returns the offset from `from` to `to` along one axis of the given size, the shortest way around when the map wraps.
+fn axis_offset(from: usize, to: usize, size: usize, wrap: bool) -> isize {
+    let offset = to as isize - from as isize;
+    if !wrap {
+        return offset;
+    }
+
+    // Same as the offsets of `cells_within`, so that a cell is always reached the same way
+    let size = size as isize;
+    if offset > size / 2 {
+        offset - size
+    } else if offset < -((size - 1) / 2) {
+        offset + size
+    } else {
+        offset
+    }
+}
+
+/// Returns the cell at the given `(rows, cols)` offset from `cell` on a map of the given `(width, height)`,
+/// going around the edges when the map wraps, or `None` if it's outside a bounded map.
+pub(crate) fn offset_cell(
+    (width, height): (usize, usize),
+    wrap: bool,
+    cell: (usize, usize),
+    offset: (isize, isize),
+) -> Option<(usize, usize)> {
+    let row = cell.0 as isize + offset.0;
+    let col = cell.1 as isize + offset.1;
+    if wrap {
+        return Some((
+            row.rem_euclid(height as isize) as usize,
+            col.rem_euclid(width as isize) as usize,
+        ));
+    }
+
+    if row < 0 || row >= height as isize || col < 0 || col >= width as isize {
+        return None;
+    }
+
+    Some((row as usize, col as usize))
+}
+
+/// Returns whether the cell at the given offset from `from` can be seen from it, see `Map::in_line_of_sight`.
+/// The line is traced on the offsets, so on a wrapping map it can cross the edges.
+fn trace_line_of_sight(
+    (width, height): (usize, usize),
+    wrap: bool,
+    from: (usize, usize),
+    offset: (isize, isize),
+    is_water: impl Fn((usize, usize)) -> bool,
+) -> bool {
+    let (mut row, mut col) = (0, 0);
+    let (to_row, to_col) = offset;
+    let row_distance = (to_row - row).abs();
+    let col_distance = -(to_col - col).abs();
+    let row_step = if row < to_row { 1 } else { -1 };
+    let col_step = if col < to_col { 1 } else { -1 };
+    let mut error = row_distance + col_distance;
+
+    loop {
+        if (row, col) == (to_row, to_col) {
+            return true;
+        }
+
+        // The line never leaves a bounded map since both ends are on it
+        if (row, col) != (0, 0)
+            && offset_cell((width, height), wrap, from, (row, col)).is_some_and(&is_water)
+        {
+            return false;
+        }
+
+        let double_error = 2 * error;
+        if double_error >= col_distance {
+            error += col_distance;
+            row += row_step;
+        }
+        if double_error <= row_distance {
+            error += row_distance;
+            col += col_step;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::Water;
+
+    #[test]
+    fn when_parsing_a_map_it_is_created_with_the_correct_width_height_and_players() {
+        let map = "\
+            rows 2
+            cols 2
+            players 1
+            m ..
+            m .0";
+        let map = Map::parse(map).unwrap();
+
+        assert_eq!(map.width, 2);
+        assert_eq!(map.height, 2);
+        assert_eq!(map.players, 1);
+    }
+
+    #[test]
+    fn when_parsing_a_map_with_tabs_or_trailing_whitespace_around_the_rows_they_are_ignored() {
+        let expected = "\
+            rows 3
+            cols 4
+            players 2
+            m 0a%.
+            m .*..
+            m .%b1";
+        let map = "rows 3\ncols 4\nplayers 2\n\tm\t0a%.  \n  m  .*..\t\r\nm .%b1 \t";
+
+        assert_eq!(
+            grid(&Map::parse(map).unwrap()),
+            grid(&Map::parse(expected).unwrap())
+        );
+    }
+
+    #[test]
+    fn when_parsing_a_map_with_words_containing_m_they_are_not_rows() {
+        let map = "\
+            rows 1
+            cols 3
+            players 1
+            name from map
+            m 0.*";
+        let map = Map::parse(map).unwrap();
+
+        assert!(map.get(0, 0).is_some_and(|entity| entity.name() == "Hill"));
+        assert!(map.get(0, 2).is_some_and(|entity| entity.name() == "Food"));
+    }
+
+    #[test]
+    fn when_writing_a_map_without_showing_ants_on_hill_they_are_written_as_plain_ants() {
+        let map = "\
+            rows 1
+            cols 4
+            players 2
+            m A.1b";
+        let map = Map::parse(map).unwrap();
+
+        assert!(map.to_contents(true).ends_with("m A.1b\n"));
+        // The ant on its hill hides the hill, while the hill without an ant is still written
+        assert!(map.to_contents(false).ends_with("m a.1b\n"));
+    }
+
+    #[test]
+    fn when_parsing_a_map_with_a_tile_map_the_custom_characters_are_used() {
+        let map = "\
+            rows 2
+            cols 4
+            players 2
+            m 0#@.
+            m a#.1";
+        let tile_map = TileMap::new(HashMap::from([('#', Tile::Water()), ('@', Tile::Food())]));
+        let map = Map::parse_with_tiles(map, &tile_map).unwrap();
+
+        assert_eq!(map.get(0, 1).unwrap().name(), "Water");
+        assert_eq!(map.get(1, 1).unwrap().name(), "Water");
+        assert_eq!(map.food(), vec![(0, 2)]);
+        // The other characters keep their usual meaning
+        assert_eq!(map.get(0, 0).unwrap().name(), "Hill");
+        assert_eq!(map.get(1, 0).unwrap().player(), Some(0));
+        assert_eq!(map.to_contents(true).lines().last(), Some("m a%.1"));
+    }
+
+    #[test]
+    fn when_parsing_a_map_with_a_tile_map_it_can_override_the_usual_characters() {
+        let map = "\
+            rows 1
+            cols 3
+            players 1
+            m A*_";
+        let tile_map = TileMap::new(HashMap::from([
+            ('*', Tile::Water()),
+            ('_', Tile::Land()),
+            ('A', Tile::Hill(0)),
+        ]));
+
+        assert_eq!(
+            translate_map(map, &tile_map, true).unwrap(),
+            "rows 1\ncols 3\nplayers 1\nm 0%.\n"
+        );
+    }
+
+    #[test]
+    fn when_parsing_a_map_with_bonus_lines_each_player_gets_their_bonus() {
+        let map = "\
+            rows 1
+            cols 4
+            players 3
+            bonus 0 3
+            bonus 2 1
+            m 0.12";
+        let map = Map::parse(map).unwrap();
+
+        assert_eq!(map.start_bonus(), &[3, 0, 1]);
+        // The bonus is kept when the map is written back and when it's transformed
+        assert_eq!(
+            Map::parse(&map.to_contents(true)).unwrap().start_bonus(),
+            &[3, 0, 1]
+        );
+        assert_eq!(map.rotate90().start_bonus(), &[3, 0, 1]);
+    }
+
+    #[test]
+    fn when_parsing_a_map_without_bonus_lines_no_player_gets_a_bonus() {
+        let map = "\
+            rows 1
+            cols 4
+            players 2
+            m 0..1";
+
+        assert_eq!(Map::parse(map).unwrap().start_bonus(), &[0, 0]);
+    }
+
+    #[test]
+    fn when_parsing_a_map_with_a_bonus_for_an_unknown_player_an_error_is_returned() {
+        let map = "\
+            rows 1
+            cols 4
+            players 2
+            bonus 2 5
+            m 0..1";
+
+        assert_eq!(
+            Map::parse(map).err(),
+            Some("The bonus is for player 2, but the map only has 2 players.".to_string())
+        );
+    }
+
+    #[test]
+    fn when_parsing_a_malformed_map_an_error_is_returned() {
+        assert_eq!(
+            Map::parse("players 1\nm 0").err(),
+            Some("The map must start with its `rows` and `cols` lines.".to_string())
+        );
+        assert_eq!(
+            Map::parse("rows 1\ncols 2\nm 0.").err(),
+            Some("The map must have a `players` line.".to_string())
+        );
+        assert_eq!(
+            Map::parse("rows 1\ncols 2\nplayers 1\nm 0.\nm ..").err(),
+            Some("The map has a cell at (1, 0), outside its 1 rows and 2 cols.".to_string())
+        );
+        assert_eq!(
+            Map::parse("rows 1\ncols 2\nplayers 1\nm 0#").err(),
+            Some("Invalid character value: #".to_string())
+        );
+    }
+
+    #[test]
+    fn when_parsing_a_map_with_wrap_0_or_no_wrap_line_it_is_bounded() {
+        let bounded = "\
+            rows 1
+            cols 3
+            players 1
+            wrap 0
+            m 0..";
+        let unspecified = "\
+            rows 1
+            cols 3
+            players 1
+            m 0..";
+
+        for map in [bounded, unspecified] {
+            let map = Map::parse(map).unwrap();
+
+            // The first and last columns are not neighbors
+            assert_eq!(map.distance((0, 0), (0, 2), false), Some(2));
+        }
+    }
+
+    #[test]
+    fn when_parsing_a_map_with_wrap_1_it_is_toroidal() {
+        let map = "\
+            rows 3
+            cols 4
+            players 1
+            wrap 1
+            m 0...
+            m ....
+            m ...%";
+        let map = Map::parse(map).unwrap();
+
+        assert!(map.wraps());
+        assert!(Map::parse(&map.to_contents(true)).unwrap().wraps());
+        // The first and last columns and rows are neighbors
+        assert_eq!(map.distance((0, 0), (0, 3), false), Some(1));
+        assert_eq!(map.distance((0, 0), (2, 0), false), Some(1));
+        assert_eq!(map.step((0, 0), &Direction::North), (2, 0));
+        assert_eq!(map.step((0, 0), &Direction::West), (0, 3));
+        assert_eq!(map.step((2, 3), &Direction::South), (0, 3));
+        assert_eq!(map.step((2, 3), &Direction::East), (2, 0));
+        assert_eq!(map.abs_diff((0, 0), (2, 3)), (1, 1));
+    }
+
+    #[test]
+    fn when_getting_the_field_of_vision_on_a_wrapping_map_it_goes_around_the_edges() {
+        let map = "\
+            rows 5
+            cols 5
+            players 1
+            wrap 1
+            m 0....
+            m .....
+            m .....
+            m .....
+            m ....*";
+        let map = Map::parse(map).unwrap();
+
+        let cells = map.cells_within((0, 0), 2, &DistanceMetric::Euclidean2, false);
+        assert_eq!(
+            cells,
+            vec![
+                (0, 0),
+                (0, 1),
+                (0, 4),
+                (1, 0),
+                (1, 1),
+                (1, 4),
+                (4, 0),
+                (4, 1),
+                (4, 4)
+            ]
+        );
+        let fov = map.field_of_vision((0, 0), 2, &DistanceMetric::Euclidean2, true);
+        assert_eq!(fov.len(), 1);
+        assert_eq!((fov[0].0.name(), fov[0].1, fov[0].2), ("Food", 4, 4));
+
+        // Each cell is only seen once even if the radius is larger than the map
+        let cells = map.cells_within((0, 0), 100, &DistanceMetric::Euclidean2, false);
+        assert_eq!(cells.len(), 25);
+    }
+
+    #[test]
+    fn when_rendering_a_map_to_a_string_it_contains_the_game_info_and_the_grid() {
+        let map = "\
+            rows 2
+            cols 3
+            players 2
+            m 0a%
+            m *B1";
+        let map = Map::parse(map).unwrap();
+
+        let names = vec!["Player 0".to_string(), "Player 1".to_string()];
+        let output = map.render_to_string(
+            7,
+            &[1, 2],
+            &[1, 1],
+            &[0, 3],
+            &names,
+            &RenderTheme::default(),
+        );
+
+        assert_eq!(
+            output,
+            "\
+Players: 2
+Turn: 7
+Player 0: Score = 1, Ants = 1, Hive = 0
+Player 1: Score = 2, Ants = 1, Hive = 3
+
+0a%
+*B1
+"
+        );
+    }
+
+    #[test]
+    fn when_rotating_a_map_the_entities_are_moved_and_keep_their_owner() {
+        let map = "\
+            rows 2
+            cols 3
+            players 2
+            m 0a%
+            m *B1";
+        let map = Map::parse(map).unwrap();
+        let id = map.get(0, 1).unwrap().id().to_string();
+
+        let map = map.rotate90();
+
+        assert_eq!(map.width(), 2);
+        assert_eq!(map.height(), 3);
+        assert_eq!(grid(&map), "*0\nBa\n1%\n");
+        assert_eq!(map.get(1, 1).unwrap().id(), id);
+        assert_eq!(map.get(1, 0).unwrap().player(), Some(1));
+    }
+
+    #[test]
+    fn when_rotating_a_map_four_times_it_returns_to_the_original_layout() {
+        let map = "\
+            rows 2
+            cols 3
+            players 2
+            m 0a%
+            m *B1";
+        let map = Map::parse(map).unwrap();
+        let original = grid(&map);
+
+        let map = map.rotate90().rotate90().rotate90().rotate90();
+
+        assert_eq!(map.width(), 3);
+        assert_eq!(map.height(), 2);
+        assert_eq!(grid(&map), original);
+    }
+
+    #[test]
+    fn when_mirroring_a_map_the_entities_are_moved_and_keep_their_owner() {
+        let map = "\
+            rows 2
+            cols 3
+            players 2
+            m 0a%
+            m *B1";
+
+        assert_eq!(
+            grid(&Map::parse(map).unwrap().mirror_horizontal()),
+            "%a0\n1B*\n"
+        );
+        assert_eq!(
+            grid(&Map::parse(map).unwrap().mirror_vertical()),
+            "*B1\n0a%\n"
+        );
+    }
+
+    #[test]
+    fn when_mirroring_a_map_twice_it_returns_to_the_original_layout() {
+        let map = "\
+            rows 2
+            cols 3
+            players 2
+            m 0a%
+            m *B1";
+        let map = Map::parse(map).unwrap();
+        let original = grid(&map);
+
+        let map = map.mirror_horizontal().mirror_horizontal();
+        assert_eq!(grid(&map), original);
+
+        let map = map.mirror_vertical().mirror_vertical();
+        assert_eq!(grid(&map), original);
+    }
+
+    #[test]
+    fn when_transforming_map_contents_the_result_is_a_map_in_the_same_format() {
+        let map = "\
+            rows 2
+            cols 3
+            players 2
+            m 0a%
+            m *B1";
+
+        let rotated = transform_map(map, MapTransform::Rotate90, true).unwrap();
 
-        Map {
-            width,
-            height,
-            players,
-            grid,
-        }
+        assert_eq!(rotated, "rows 3\ncols 2\nplayers 2\nm *0\nm Ba\nm 1%\n");
+        assert_eq!(grid(&Map::parse(&rotated).unwrap()), "*0\nBa\n1%\n");
     }
 
-    fn all(&self, filter: fn(&Box<dyn Entity>) -> bool) -> Vec<(&dyn Entity, usize, usize)> {
-        // Inefficient way to get all entities using some filter (linear time complexity)
-        // But it should be fine since maps are small, the largest having roughly 15K or so cells
-        // If we end up using larger maps, we will need to optimize this
-        self.grid
-            .iter()
-            .enumerate()
-            .filter_map(|(index, entity)| {
-                if let Some(entity) = entity {
-                    if filter(entity) {
-                        let row = index / self.width;
-                        let col = index % self.width;
-                        return Some((entity.as_ref(), row, col));
-                    }
-                }
-                None
-            })
-            .collect()
-    }
+    #[test]
+    fn when_transforming_map_contents_without_showing_ants_on_hill_they_are_written_as_plain_ants()
+    {
+        let map = "\
+            rows 1
+            cols 4
+            players 2
+            m A.1b";
 
-    fn is_valid_move(&self, from: (usize, usize), to: (usize, usize)) -> bool {
-        // If there is no movement, the move is invalid
-        if from == to {
-            return false;
-        }
+        let mirrored = transform_map(map, MapTransform::MirrorHorizontal, false).unwrap();
 
-        // If the coordinates are out of bounds, the move is invalid
-        if from.0 >= self.height
-            || from.1 >= self.width
-            || to.0 >= self.height
-            || to.1 >= self.width
-        {
-            return false;
-        }
+        assert_eq!(mirrored, "rows 1\ncols 4\nplayers 2\nm b1.a\n");
+    }
 
-        let from = self.get(from.0, from.1);
-        if from.is_none() {
-            return false;
-        }
+    #[test]
+    fn when_translating_map_contents_without_showing_ants_on_hill_they_are_written_as_plain_ants() {
+        let map = "\
+            rows 1
+            cols 3
+            players 1
+            m #.a";
+        let tile_map = TileMap::new(HashMap::from([('#', Tile::AntOnHill(0))]));
 
-        // Only alive ants can move
-        let from = from.unwrap();
-        if from.name() != "Ant" || from.alive().is_none() || !from.alive().unwrap() {
-            return false;
-        }
+        assert_eq!(
+            translate_map(map, &tile_map, false).unwrap(),
+            "rows 1\ncols 3\nplayers 1\nm a.a\n"
+        );
+        assert_eq!(
+            translate_map(map, &tile_map, true).unwrap(),
+            "rows 1\ncols 3\nplayers 1\nm A.a\n"
+        );
+    }
 
-        if let Some(to) = self.get(to.0, to.1) {
-            // Water, food or a dead ant blocks the movement
-            if to.name() == "Water"
-                || to.name() == "Food"
-                || (to.name() == "Ant" && !to.alive().unwrap())
-            {
-                return false;
-            }
-        }
+    /// Returns the grid of the map as rendered with the default theme, without the game info.
+    fn grid(map: &Map) -> String {
+        let scores = vec![0; map.players()];
+        let empty = vec![0; map.players()];
+        let names = vec![String::new(); map.players()];
+        let output =
+            map.render_to_string(0, &scores, &empty, &empty, &names, &RenderTheme::default());
+        output.split("\n\n").nth(1).unwrap().to_string()
+    }
 
-        true
+    #[test]
+    fn when_parsing_a_map_with_rotational_symmetry_the_symmetry_is_detected() {
+        let map = "\
+            rows 3
+            cols 4
+            players 2
+            m 0%..
+            m ....
+            m ..%1";
+        let map = Map::parse(map).unwrap();
+
+        assert_eq!(map.symmetries, vec![Symmetry::Rotation180]);
+        assert_eq!(map.symmetric_images(0, 2), vec![(0, 2), (2, 1)]);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::entities::Water;
+    #[test]
+    fn when_parsing_a_map_with_mirror_symmetry_the_symmetry_is_detected() {
+        let map = "\
+            rows 3
+            cols 4
+            players 2
+            m 0%%1
+            m ....
+            m a...";
+        let map = Map::parse(map).unwrap();
+
+        // Ants that are not on a hill are not part of the terrain so they are ignored
+        assert_eq!(map.symmetries, vec![Symmetry::MirrorHorizontal]);
+        assert_eq!(map.symmetric_images(1, 0), vec![(1, 0), (1, 3)]);
+    }
 
     #[test]
-    fn when_parsing_a_map_it_is_created_with_the_correct_width_height_and_players() {
+    fn when_parsing_a_map_without_symmetry_no_symmetry_is_detected() {
         let map = "\
-            rows 2
-            cols 2
-            players 1
-            m ..
-            m .0";
-        let map = Map::parse(map);
+            rows 3
+            cols 4
+            players 2
+            m 0%..
+            m ....
+            m .%.1";
+        let map = Map::parse(map).unwrap();
 
-        assert_eq!(map.width, 2);
-        assert_eq!(map.height, 2);
-        assert_eq!(map.players, 1);
+        assert!(map.symmetries.is_empty());
+        assert_eq!(map.symmetric_images(1, 1), vec![(1, 1)]);
     }
 
     #[test]
@@ -409,7 +1961,7 @@ mod tests {
             players 1
             m .b.
             m *0%";
-        let map = Map::parse(map);
+        let map = Map::parse(map).unwrap();
 
         assert!(map.get(0, 0).is_none());
         assert_eq!(map.get(0, 1).unwrap().name(), "Ant");
@@ -429,7 +1981,7 @@ mod tests {
             players 1
             m ..
             m .a";
-        let mut map = Map::parse(map);
+        let mut map = Map::parse(map).unwrap();
         map.get_mut(1, 1).unwrap().set_alive(false);
 
         assert!(!map.get(1, 1).unwrap().alive().unwrap());
@@ -443,12 +1995,42 @@ mod tests {
             players 1
             m ..
             m .0";
-        let mut map = Map::parse(map);
+        let mut map = Map::parse(map).unwrap();
         map.set(1, 1, Box::new(Water));
 
         assert_eq!(map.get(1, 1).unwrap().name(), "Water");
     }
 
+    #[test]
+    fn when_trying_to_set_the_value_of_an_empty_cell_the_entity_is_set() {
+        let map = "\
+            rows 2
+            cols 2
+            players 1
+            m ..
+            m .0";
+        let mut map = Map::parse(map).unwrap();
+        let did_set = map.try_set(0, 0, Box::new(Water));
+
+        assert!(did_set);
+        assert_eq!(map.get(0, 0).unwrap().name(), "Water");
+    }
+
+    #[test]
+    fn when_trying_to_set_the_value_of_an_occupied_cell_the_entity_is_not_overwritten() {
+        let map = "\
+            rows 2
+            cols 2
+            players 1
+            m ..
+            m .0";
+        let mut map = Map::parse(map).unwrap();
+        let did_set = map.try_set(1, 1, Box::new(Water));
+
+        assert!(!did_set);
+        assert_eq!(map.get(1, 1).unwrap().name(), "Hill");
+    }
+
     #[test]
     fn when_removing_an_entity_the_cell_becomes_empty() {
         let map = "\
@@ -457,7 +2039,7 @@ mod tests {
             players 1
             m ..
             m .0";
-        let mut map = Map::parse(map);
+        let mut map = Map::parse(map).unwrap();
         map.remove(1, 1);
 
         assert!(map.get(1, 1).is_none());
@@ -472,7 +2054,7 @@ mod tests {
             m .0.
             m .1.
             m .2.";
-        let map = Map::parse(map);
+        let map = Map::parse(map).unwrap();
 
         let ant_hills = map.ant_hills();
         assert_eq!(ant_hills.len(), 3);
@@ -502,7 +2084,7 @@ mod tests {
             m ..a
             m b..
             m .c.";
-        let map = Map::parse(map);
+        let map = Map::parse(map).unwrap();
 
         let ants = map.ants();
         assert_eq!(ants.len(), 3);
@@ -523,6 +2105,19 @@ mod tests {
         assert_eq!(ants[2].2, 1);
     }
 
+    #[test]
+    fn when_counting_the_hills_per_player_hills_with_ants_on_them_are_included() {
+        let map = "\
+            rows 2
+            cols 3
+            players 3
+            m 0A.
+            m .b1";
+        let map = Map::parse(map).unwrap();
+
+        assert_eq!(map.hills_per_player(), vec![2, 1, 0]);
+    }
+
     #[test]
     fn when_getting_all_food_the_correct_entities_are_returned() {
         let map = "\
@@ -532,7 +2127,7 @@ mod tests {
             m .0.
             m .*.
             m .0.";
-        let map = Map::parse(map);
+        let map = Map::parse(map).unwrap();
 
         let food = map.food();
         assert_eq!(food.len(), 1);
@@ -550,7 +2145,7 @@ mod tests {
             m .0.
             m .*.
             m .0.";
-        let map = Map::parse(map);
+        let map = Map::parse(map).unwrap();
 
         let land = map.land();
         let expected_land = vec![(0, 0), (0, 2), (1, 0), (1, 2), (2, 0), (2, 2)];
@@ -568,7 +2163,7 @@ mod tests {
             m ...
             m .0.
             m ...";
-        let map = Map::parse(map);
+        let map = Map::parse(map).unwrap();
 
         let lands = map.land_around(1, 1);
         let expected_lands = vec![
@@ -595,7 +2190,7 @@ mod tests {
             m ...
             m ...
             m .0.";
-        let map = Map::parse(map);
+        let map = Map::parse(map).unwrap();
 
         let lands = map.land_around(2, 1);
         let expected_lands = vec![(1, 0), (1, 1), (1, 2), (2, 0), (2, 2)];
@@ -613,7 +2208,7 @@ mod tests {
             m 0..
             m ...
             m ...";
-        let map = Map::parse(map);
+        let map = Map::parse(map).unwrap();
 
         let lands = map.land_around(0, 0);
         let expected_lands = vec![(0, 1), (1, 0), (1, 1)];
@@ -631,13 +2226,49 @@ mod tests {
             m .*0
             m .**
             m ...";
-        let map = Map::parse(map);
+        let map = Map::parse(map).unwrap();
 
         let lands = map.land_around(0, 2);
 
         assert_eq!(lands.len(), 0);
     }
 
+    #[test]
+    fn when_getting_the_land_in_a_ring_around_a_cell_only_the_land_at_that_distance_is_returned() {
+        let map = "\
+            rows 5
+            cols 5
+            players 1
+            m %....
+            m .....
+            m ..0..
+            m ...*.
+            m .....";
+        let map = Map::parse(map).unwrap();
+
+        let lands = map.land_in_ring(2, 2, 2);
+        let expected_lands = vec![
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (0, 4),
+            (1, 0),
+            (1, 4),
+            (2, 0),
+            (2, 4),
+            (3, 0),
+            (3, 4),
+            (4, 0),
+            (4, 1),
+            (4, 2),
+            (4, 3),
+            (4, 4),
+        ];
+
+        assert_eq!(lands, expected_lands);
+        assert_eq!(map.land_in_ring(2, 2, 1).len(), 7);
+    }
+
     #[test]
     fn when_getting_the_field_of_vision_of_a_cell_the_correct_entities_are_returned() {
         let map = "\
@@ -649,10 +2280,10 @@ mod tests {
             m .*A.%
             m .1...
             m ..*..";
-        let map = Map::parse(map);
+        let map = Map::parse(map).unwrap();
 
         // Get the field of vision of the ant at (2, 2), on top of its own hill, with a radius of 2
-        let fov = map.field_of_vision((2, 2), 4);
+        let fov = map.field_of_vision((2, 2), 4, &DistanceMetric::Euclidean2, false);
 
         assert_eq!(fov.len(), 8);
 
@@ -693,6 +2324,277 @@ mod tests {
         assert_eq!(fov[7].2, 2);
     }
 
+    #[test]
+    fn when_getting_the_field_of_vision_with_line_of_sight_water_hides_the_cells_behind_it() {
+        let map = "\
+            rows 5
+            cols 5
+            players 2
+            m .....
+            m .%%..
+            m *%a.b
+            m .....
+            m *....";
+        let map = Map::parse(map).unwrap();
+        let cells = |line_of_sight: bool| {
+            map.field_of_vision((2, 2), 9, &DistanceMetric::Euclidean2, line_of_sight)
+                .into_iter()
+                .map(|(entity, row, col)| (entity.name().to_string(), row, col))
+                .collect::<Vec<(String, usize, usize)>>()
+        };
+
+        // Without line of sight, everything within the radius is seen
+        let without = cells(false);
+        assert!(without.contains(&("Ant".to_string(), 2, 4)));
+        assert!(without.contains(&("Food".to_string(), 4, 0)));
+        assert!(without.contains(&("Food".to_string(), 2, 0)));
+        assert!(
+            without
+                .iter()
+                .filter(|(name, _, _)| name == "Water")
+                .count()
+                == 3
+        );
+
+        // With line of sight, the water wall is still seen but it hides what's behind it
+        let with = cells(true);
+        assert!(with.contains(&("Ant".to_string(), 2, 4)));
+        assert!(with.contains(&("Food".to_string(), 4, 0)));
+        // The food right behind the wall is hidden
+        assert!(!with.contains(&("Food".to_string(), 2, 0)));
+        assert!(with.iter().filter(|(name, _, _)| name == "Water").count() == 3);
+        let hidden = map.cells_within((2, 2), 9, &DistanceMetric::Euclidean2, true);
+        assert!(!hidden.contains(&(2, 0)));
+        assert!(!hidden.contains(&(0, 1)));
+        assert!(hidden.contains(&(3, 0)));
+    }
+
+    #[test]
+    fn when_checking_the_line_of_sight_only_water_between_the_cells_blocks_it() {
+        let map = "\
+            rows 1
+            cols 5
+            players 1
+            m a*%.%";
+        let map = Map::parse(map).unwrap();
+
+        assert!(map.in_line_of_sight((0, 0), (0, 2)));
+        assert!(!map.in_line_of_sight((0, 0), (0, 3)));
+        assert!(!map.in_line_of_sight((0, 0), (0, 4)));
+        assert!(map.in_line_of_sight((0, 3), (0, 4)));
+        assert!(map.in_line_of_sight((0, 2), (0, 0)));
+    }
+
+    #[test]
+    fn when_getting_the_field_of_vision_with_each_distance_metric_the_correct_cells_are_included() {
+        let map = "\
+            rows 5
+            cols 5
+            players 1
+            m *****
+            m *****
+            m **a**
+            m *****
+            m *****";
+        let map = Map::parse(map).unwrap();
+        let cells = |metric: DistanceMetric| {
+            map.field_of_vision((2, 2), 5, &metric, false)
+                .into_iter()
+                .map(|(_, row, col)| (row, col))
+                .collect::<Vec<(usize, usize)>>()
+        };
+
+        // Euclidean includes everything but the corners since 2^2 + 2^2 > 5
+        let euclidean = cells(DistanceMetric::Euclidean2);
+        assert_eq!(euclidean.len(), 20);
+        assert!(euclidean.contains(&(0, 1)));
+        assert!(!euclidean.contains(&(0, 0)));
+
+        // Manhattan only includes cells at most 2 steps away, i.e. a diamond
+        let manhattan = cells(DistanceMetric::Manhattan);
+        assert_eq!(manhattan.len(), 12);
+        assert!(manhattan.contains(&(1, 1)));
+        assert!(!manhattan.contains(&(0, 1)));
+
+        // Chebyshev includes the whole 5x5 square
+        let chebyshev = cells(DistanceMetric::Chebyshev);
+        assert_eq!(chebyshev.len(), 24);
+        assert!(chebyshev.contains(&(0, 0)));
+    }
+
+    #[test]
+    fn when_finding_the_shortest_path_water_is_avoided() {
+        let map = "\
+            rows 3
+            cols 3
+            players 1
+            m a%.
+            m .%.
+            m ...";
+        let map = Map::parse(map).unwrap();
+
+        let path = map.shortest_path((0, 0), (0, 2), false);
+
+        assert_eq!(
+            path,
+            Some(vec![
+                Direction::South,
+                Direction::South,
+                Direction::East,
+                Direction::East,
+                Direction::North,
+                Direction::North
+            ])
+        );
+        assert_eq!(map.distance((0, 0), (0, 2), false), Some(6));
+    }
+
+    #[test]
+    fn when_finding_the_shortest_path_entities_are_only_avoided_if_requested() {
+        let map = "\
+            rows 2
+            cols 3
+            players 2
+            m a*.
+            m .b.";
+        let map = Map::parse(map).unwrap();
+
+        assert_eq!(map.distance((0, 0), (0, 2), false), Some(2));
+        // Both the food and the enemy ant block the shortest routes
+        assert_eq!(map.distance((0, 0), (0, 2), true), None);
+        // But the destination itself is never avoided
+        assert_eq!(
+            map.shortest_path((0, 0), (0, 1), true),
+            Some(vec![Direction::East])
+        );
+    }
+
+    #[test]
+    fn when_finding_the_shortest_path_to_an_unreachable_cell_none_is_returned() {
+        let map = "\
+            rows 1
+            cols 3
+            players 1
+            m a%.";
+        let map = Map::parse(map).unwrap();
+
+        assert!(map.shortest_path((0, 0), (0, 2), false).is_none());
+        assert!(map.shortest_path((0, 0), (0, 1), false).is_none());
+        assert!(map.shortest_path((0, 0), (5, 5), false).is_none());
+        assert_eq!(map.shortest_path((0, 0), (0, 0), false), Some(vec![]));
+    }
+
+    #[test]
+    fn when_finding_the_nearest_food_the_closest_reachable_food_is_returned() {
+        let map = "\
+            rows 3
+            cols 4
+            players 1
+            m a%*.
+            m .%..
+            m ...*";
+        let map = Map::parse(map).unwrap();
+
+        // The food at (0, 2) is closer in a straight line, but the water makes it 6 moves away while (2, 3) is 5 moves away
+        assert_eq!(map.nearest_food((0, 0), |_| true), Some((2, 3)));
+        assert_eq!(
+            map.nearest_food((0, 0), |cell| cell != (2, 3)),
+            Some((0, 2))
+        );
+        assert_eq!(map.nearest_food((0, 0), |_| false), None);
+    }
+
+    #[test]
+    fn when_getting_the_regions_of_a_map_cells_connected_by_land_share_a_region() {
+        let map = "\
+            rows 3
+            cols 4
+            players 2
+            m a%.*
+            m .%%.
+            m .%b%";
+        let map = Map::parse(map).unwrap();
+
+        assert_eq!(
+            map.regions(),
+            vec![
+                vec![Some(0), None, Some(1), Some(1)],
+                vec![Some(0), None, None, Some(1)],
+                vec![Some(0), None, Some(2), None],
+            ]
+        );
+        assert_eq!(map.region_of(2, 0), Some(0));
+        assert_eq!(map.region_of(0, 3), Some(1));
+        assert_eq!(map.region_of(2, 2), Some(2));
+        assert_eq!(map.region_of(0, 1), None);
+        assert_eq!(map.region_of(9, 9), None);
+    }
+
+    #[test]
+    fn when_getting_the_territory_each_cell_belongs_to_the_closest_hill() {
+        let map = "\
+            rows 3
+            cols 5
+            players 2
+            m 0...1
+            m ..%..
+            m %%%..";
+        let mut map = Map::parse(map).unwrap();
+        // A razed hill is walkable but doesn't control any territory
+        map.set(2, 4, Box::new(Hill::new(0, false)));
+
+        let (territory, areas) = map.territory();
+
+        // The cell at (0, 2) is 2 moves away from both hills, so it's contested
+        assert_eq!(
+            territory,
+            vec![
+                vec![Some(0), Some(0), None, Some(1), Some(1)],
+                vec![Some(0), Some(0), None, Some(1), Some(1)],
+                vec![None, None, None, Some(1), Some(1)],
+            ]
+        );
+        assert_eq!(areas, vec![4, 6]);
+    }
+
+    #[test]
+    fn when_getting_the_territory_hills_with_ants_on_them_still_count() {
+        let map = "\
+            rows 1
+            cols 4
+            players 2
+            m A..1";
+        let map = Map::parse(map).unwrap();
+
+        let (territory, areas) = map.territory();
+
+        assert_eq!(territory, vec![vec![Some(0), Some(0), Some(1), Some(1)]]);
+        assert_eq!(areas, vec![2, 2]);
+    }
+
+    #[test]
+    fn when_getting_the_hill_distances_each_cell_has_the_moves_to_the_closest_live_hill() {
+        let map = "\
+            rows 2
+            cols 6
+            players 2
+            m 0..%.A
+            m ...%..";
+        let mut map = Map::parse(map).unwrap();
+        // A razed hill is not a source of distances
+        map.set(1, 2, Box::new(Hill::new(1, false)));
+
+        let distances = map.hill_distances();
+
+        assert_eq!(
+            distances,
+            vec![
+                vec![Some(0), Some(1), Some(2), None, Some(1), Some(0)],
+                vec![Some(1), Some(2), Some(3), None, Some(2), Some(1)],
+            ]
+        );
+    }
+
     #[test]
     fn when_moving_an_ant_to_an_empty_cell_the_ant_is_moved() {
         let map = "\
@@ -702,7 +2604,7 @@ mod tests {
             m ...
             m .a.
             m ...";
-        let mut map = Map::parse(map);
+        let mut map = Map::parse(map).unwrap();
         let did_move = map.move_entity((1, 1), (0, 1));
 
         assert!(map.get(1, 1).is_none());
@@ -719,7 +2621,7 @@ mod tests {
             m ...
             m .A.
             m ...";
-        let mut map = Map::parse(map);
+        let mut map = Map::parse(map).unwrap();
         let did_move = map.move_entity((1, 1), (0, 1));
 
         assert_eq!(map.get(0, 1).unwrap().name(), "Ant");
@@ -736,7 +2638,7 @@ mod tests {
             m ...
             m .a.
             m .0.";
-        let mut map = Map::parse(map);
+        let mut map = Map::parse(map).unwrap();
         let did_move = map.move_entity((1, 1), (2, 1));
 
         assert!(map.get(1, 1).is_none());
@@ -770,7 +2672,7 @@ mod tests {
             m ...
             m .a.
             m ...";
-        let mut map = Map::parse(map);
+        let mut map = Map::parse(map).unwrap();
         let did_move = map.move_entity((0, 1), (0, 2));
 
         assert!(map.get(0, 1).is_none());
@@ -786,7 +2688,7 @@ mod tests {
             m %..
             m .a.
             m ...";
-        let mut map = Map::parse(map);
+        let mut map = Map::parse(map).unwrap();
         let did_move = map.move_entity((0, 0), (1, 0));
 
         assert_eq!(map.get(0, 0).unwrap().name(), "Water");
@@ -803,7 +2705,7 @@ mod tests {
             m ...
             m .a.
             m ...";
-        let mut map = Map::parse(map);
+        let mut map = Map::parse(map).unwrap();
         map.get_mut(1, 1).unwrap().set_alive(false);
         let did_move = map.move_entity((1, 1), (0, 1));
 
@@ -821,7 +2723,7 @@ mod tests {
             m ...
             m .a.
             m .%.";
-        let mut map = Map::parse(map);
+        let mut map = Map::parse(map).unwrap();
         let did_move = map.move_entity((1, 1), (2, 1));
 
         assert_eq!(map.get(1, 1).unwrap().name(), "Ant");
@@ -838,7 +2740,7 @@ mod tests {
             m ...
             m .a*
             m ...";
-        let mut map = Map::parse(map);
+        let mut map = Map::parse(map).unwrap();
         let did_move = map.move_entity((1, 1), (1, 2));
 
         assert_eq!(map.get(1, 1).unwrap().name(), "Ant");
@@ -855,7 +2757,7 @@ mod tests {
             m ...
             m ..a
             m ...";
-        let mut map = Map::parse(map);
+        let mut map = Map::parse(map).unwrap();
         let did_move = map.move_entity((1, 2), (1, 3));
 
         assert_eq!(map.get(1, 2).unwrap().name(), "Ant");
@@ -871,7 +2773,7 @@ mod tests {
             m ...
             m ...
             m ..a";
-        let mut map = Map::parse(map);
+        let mut map = Map::parse(map).unwrap();
         let did_move = map.move_entity((2, 2), (3, 2));
 
         assert_eq!(map.get(2, 2).unwrap().name(), "Ant");
@@ -887,7 +2789,7 @@ mod tests {
             m ...
             m .a.
             m .b.";
-        let mut map = Map::parse(map);
+        let mut map = Map::parse(map).unwrap();
         let did_move = map.move_entity((1, 1), (2, 1));
 
         assert!(!map.get(1, 1).unwrap().alive().unwrap());
@@ -904,7 +2806,7 @@ mod tests {
             m ...
             m .a.
             m .a.";
-        let mut map = Map::parse(map);
+        let mut map = Map::parse(map).unwrap();
         map.get_mut(2, 1).unwrap().set_alive(false);
         let did_move = map.move_entity((1, 1), (2, 1));
 
@@ -916,6 +2818,47 @@ mod tests {
         assert!(!did_move);
     }
 
+    #[test]
+    fn when_checking_why_a_move_is_blocked_the_reason_matches_the_destination() {
+        let map = "\
+            rows 2
+            cols 3
+            players 1
+            m %a*
+            m .a.";
+        let mut map = Map::parse(map).unwrap();
+        map.get_mut(1, 1).unwrap().set_alive(false);
+
+        assert_eq!(
+            map.blocked_move_reason((0, 1), (0, 2)),
+            Some(BlockedMoveReason::Food)
+        );
+        assert_eq!(
+            map.blocked_move_reason((0, 1), (0, 0)),
+            Some(BlockedMoveReason::Water)
+        );
+        assert_eq!(
+            map.blocked_move_reason((0, 1), (1, 1)),
+            Some(BlockedMoveReason::DeadAnt)
+        );
+        assert_eq!(
+            map.blocked_move_reason((0, 1), (0, 1)),
+            Some(BlockedMoveReason::OutOfBounds)
+        );
+        assert_eq!(
+            map.blocked_move_reason((1, 1), (1, 2)),
+            Some(BlockedMoveReason::NoLiveAnt)
+        );
+        assert_eq!(
+            map.blocked_move_reason((1, 0), (1, 1)),
+            Some(BlockedMoveReason::NoLiveAnt)
+        );
+        assert_eq!(
+            map.blocked_move_reason((0, 1), (0, 3)),
+            Some(BlockedMoveReason::OutOfBounds)
+        );
+    }
+
     #[test]
     fn when_moving_an_ant_to_the_same_cell_movement_is_ignored() {
         let map = "\
@@ -925,7 +2868,7 @@ mod tests {
             m ...
             m .a.
             m ...";
-        let mut map = Map::parse(map);
+        let mut map = Map::parse(map).unwrap();
         let id = map.get(1, 1).unwrap().id().to_string();
 
         let did_move = map.move_entity((1, 1), (1, 1));
@@ -935,4 +2878,19 @@ mod tests {
         assert!(map.get(1, 1).unwrap().alive().unwrap());
         assert!(!did_move);
     }
+
+    #[test]
+    fn when_the_map_has_no_cells_field_of_vision_and_land_around_are_empty() {
+        for (width, height) in [(0, 0), (0, 3), (3, 0)] {
+            let map = Map::new(width, height, 1);
+
+            assert!(map
+                .field_of_vision((0, 0), 77, &DistanceMetric::Euclidean2, false)
+                .is_empty());
+            assert!(map
+                .cells_within((0, 0), 77, &DistanceMetric::Euclidean2, true)
+                .is_empty());
+            assert!(map.land_around(0, 0).is_empty());
+        }
+    }
 }
@@ -0,0 +1,200 @@
+use crate::entities::Entity;
+use crossterm::style::Color;
+
+/// The colors used for each player by default, in order.
+const PLAYER_COLORS: [Color; 10] = [
+    Color::Red,
+    Color::Green,
+    Color::Blue,
+    Color::Yellow,
+    Color::Magenta,
+    Color::Cyan,
+    Color::DarkRed,
+    Color::DarkGreen,
+    Color::DarkMagenta,
+    Color::DarkYellow,
+];
+
+/// Represents the symbols and colors used to render the game.
+///
+/// The per player symbols and colors are cycled when there are more players than entries, e.g. with the default theme player 10 looks like player 0.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderTheme {
+    /// The symbol of an empty land cell.
+    pub land: char,
+    /// The symbol and color of water.
+    pub water: (char, Color),
+    /// The symbol and color of food.
+    pub food: (char, Color),
+    /// The symbol of a razed hill. It uses the color of the player that owned it.
+    pub razed_hill: char,
+    /// The symbol of a dead ant's corpse. It uses the color of the player that owned the ant.
+    pub corpse: char,
+    /// The symbol of an ant for each player.
+    pub ants: Vec<char>,
+    /// The symbol of an ant standing on its hill for each player.
+    pub ants_on_hill: Vec<char>,
+    /// Whether an ant standing on its hill uses the `ants_on_hill` symbols, otherwise it looks like any other ant and the hill is hidden.
+    pub show_ants_on_hill: bool,
+    /// The symbol of a hill for each player.
+    pub hills: Vec<char>,
+    /// The color of each player, used for their ants, hills and stats.
+    pub player_colors: Vec<Color>,
+}
+
+impl RenderTheme {
+    /// Returns the color of the given player.
+    ///
+    /// # Arguments
+    /// * `player` - The player number.
+    pub fn player_color(&self, player: usize) -> Color {
+        cycle(&self.player_colors, player, Color::Reset)
+    }
+
+    /// Returns the symbol and color used to render the given cell.
+    ///
+    /// # Arguments
+    /// * `entity` - The entity in the cell or `None` for land.
+    pub fn symbol(&self, entity: Option<&dyn Entity>) -> (char, Color) {
+        let Some(entity) = entity else {
+            return (self.land, Color::Reset);
+        };
+
+        match (entity.name(), entity.player()) {
+            // Dead ants are removed from the map at the end of the turn
+            ("Ant", _) if entity.alive() == Some(false) => (self.land, Color::Reset),
+            ("Ant", Some(player)) => {
+                let symbols = match entity.on_ant_hill() {
+                    Some(_) if self.show_ants_on_hill => &self.ants_on_hill,
+                    _ => &self.ants,
+                };
+                (cycle(symbols, player, '!'), self.player_color(player))
+            }
+            ("Hill", Some(player)) => {
+                let symbol = match entity.alive().unwrap() {
+                    true => cycle(&self.hills, player, '!'),
+                    false => self.razed_hill,
+                };
+                (symbol, self.player_color(player))
+            }
+            ("Corpse", Some(player)) => (self.corpse, self.player_color(player)),
+            ("Food", _) => self.food,
+            ("Water", _) => self.water,
+            _ => ('!', Color::White),
+        }
+    }
+}
+
+impl Default for RenderTheme {
+    /// Creates the default theme, where ants are `a` to `j` (`A` to `J` on their hill), hills are `0` to `9`, food is `*`, water is `%` and corpses are `+`.
+    fn default() -> Self {
+        RenderTheme {
+            land: '.',
+            water: ('%', Color::DarkBlue),
+            food: ('*', Color::Grey),
+            razed_hill: 'X',
+            corpse: '+',
+            ants: ('a'..='j').collect(),
+            ants_on_hill: ('A'..='J').collect(),
+            show_ants_on_hill: true,
+            hills: ('0'..='9').collect(),
+            player_colors: PLAYER_COLORS.to_vec(),
+        }
+    }
+}
+
+/// Returns the default name of the given player, i.e. `Player N`.
+///
+/// # Arguments
+/// * `player` - The player number.
+pub fn default_player_name(player: usize) -> String {
+    format!("Player {}", player)
+}
+
+fn cycle<T: Copy>(values: &[T], index: usize, fallback: T) -> T {
+    if values.is_empty() {
+        return fallback;
+    }
+
+    values[index % values.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Ant, Corpse, Food, Hill, Water};
+
+    #[test]
+    fn when_using_the_default_theme_the_symbols_match_the_map_format() {
+        let theme = RenderTheme::default();
+
+        assert_eq!(theme.symbol(None), ('.', Color::Reset));
+        assert_eq!(theme.symbol(Some(&Water)), ('%', Color::DarkBlue));
+        assert_eq!(theme.symbol(Some(&Food)), ('*', Color::Grey));
+        assert_eq!(
+            theme.symbol(Some(&Ant::new("a".to_string(), 1, true, None))),
+            ('b', Color::Green)
+        );
+        assert_eq!(
+            theme.symbol(Some(&Ant::new(
+                "a".to_string(),
+                2,
+                true,
+                Some(Box::new(Hill::new(2, true)))
+            ))),
+            ('C', Color::Blue)
+        );
+        assert_eq!(
+            theme.symbol(Some(&Hill::new(3, true))),
+            ('3', Color::Yellow)
+        );
+        assert_eq!(
+            theme.symbol(Some(&Hill::new(3, false))),
+            ('X', Color::Yellow)
+        );
+        assert_eq!(theme.symbol(Some(&Corpse::new(0))), ('+', Color::Red));
+    }
+
+    #[test]
+    fn when_there_are_more_players_than_symbols_and_colors_they_are_cycled() {
+        let theme = RenderTheme {
+            ants: vec!['x', 'y'],
+            player_colors: vec![Color::Red, Color::Blue],
+            ..RenderTheme::default()
+        };
+
+        assert_eq!(
+            theme.symbol(Some(&Ant::new("a".to_string(), 3, true, None))),
+            ('y', Color::Blue)
+        );
+        assert_eq!(theme.player_color(4), Color::Red);
+    }
+
+    #[test]
+    fn when_not_showing_ants_on_hill_an_ant_on_its_hill_uses_the_ant_symbol() {
+        let theme = RenderTheme {
+            show_ants_on_hill: false,
+            ..RenderTheme::default()
+        };
+
+        assert_eq!(
+            theme.symbol(Some(&Ant::new(
+                "a".to_string(),
+                2,
+                true,
+                Some(Box::new(Hill::new(2, true)))
+            ))),
+            ('c', Color::Blue)
+        );
+    }
+
+    #[test]
+    fn when_rendering_a_dead_ant_it_is_rendered_as_land() {
+        let theme = RenderTheme::default();
+
+        assert_eq!(
+            theme.symbol(Some(&Ant::new("a".to_string(), 1, false, None))),
+            ('.', Color::Reset)
+        );
+    }
+}
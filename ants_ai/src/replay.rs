@@ -1,6 +1,33 @@
-use serde_json::json;
+use crate::entities::{Ant, Entity, Food, Hill};
+use crate::game::PlayerStats;
+use crate::map::{BlockedMoveReason, Map};
+use crate::render::{default_player_name, RenderTheme};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use serde_json::{json, Value};
 use std::{collections::HashMap, fs::File, io::BufWriter};
 
+/// The version of the replay JSON schema, saved as `schema_version` in every replay.
+/// It must be bumped whenever a field is added, removed or changed so that stored replays can be told apart.
+///
+/// # Changelog
+/// * `1` - `players`, `map` (`width`, `height`, `contents`), `turns` (`turn`, `ants`, `hive`, `scores`, `events`),
+///   `finished_reason`, `winner` and `result` (`reason`, `winner`, `scores`, `ants`, `hive`, `ranking`, `statistics`).
+///   Events have `event_type`, `entity`, `entity_id`, `player`, `location`, `destination` and, for harvested food, `harvester_id`.
+/// * `2` - `BlockedMove` events, with the `reason` the move failed, when blocked moves are logged.
+/// * `3` - `Remove` events of ants have the `location` of the ant, so that replays can be played back on a board.
+/// * `4` - `Remove` events of `Water`, for the water cells dug by ants.
+/// * `5` - `Spawn` events of the initial setup, on turn 0, have `initial` set to `true`.
+/// * `6` - `Attack` events have the `entity_id` of the attacking ant and the `target_id` of the attacked ant.
+/// * `7` - `Harvest` events of the food stored in a hive, with the `player` and the `harvester_id`.
+///   `Remove` events of food are only for the food destroyed without being stored, or picked up by a carrying ant.
+/// * `8` - `player_names`, the name of each player, and `player_colors`, the RGB color of each player or `null`
+///   when they use the default palette.
+/// * `9` - `board_hash` in every turn, the hash of the board when `track_board_hash` is enabled or `null` otherwise.
+/// * `10` - `Spawn` events of the hills placed with `Game::place_hill`, followed by a `Remove` event of the hill
+///   when it's placed razed.
+pub const SCHEMA_VERSION: u32 = 10;
+
 pub fn create_replay_logger(
     filename: Option<String>,
     players: usize,
@@ -21,15 +48,31 @@ pub fn create_replay_logger(
 }
 
 pub trait ReplayLogger: Send + Sync {
+    // Whether `log_turn` keeps the values, so the game can skip computing them otherwise
+    fn records_turns(&self) -> bool {
+        false
+    }
+
     #[allow(unused_variables)]
-    fn log_turn(&mut self, turn: usize, ants: Vec<usize>, hive: Vec<usize>, scores: Vec<usize>) {}
+    fn log_turn(
+        &mut self,
+        turn: usize,
+        ants: &[usize],
+        hive: &[usize],
+        scores: &[isize],
+        board_hash: Option<u64>,
+    ) {
+    }
 
     #[allow(unused_variables)]
-    fn log_end_game(&mut self, reason: String, winner: Option<usize>) {}
+    fn log_end_game(&mut self, result: GameResult) {}
 
     #[allow(unused_variables)]
     fn log_event(&mut self, turn: usize, event: Event) {}
 
+    #[allow(unused_variables)]
+    fn log_players(&mut self, names: &[String], colors: Option<&[(u8, u8, u8)]>) {}
+
     fn clear(&mut self) {}
 
     fn save(&self) {}
@@ -42,8 +85,12 @@ pub trait ReplayLogger: Send + Sync {
         self.log_spawn(turn, "Food".to_string(), None, None, location);
     }
 
-    fn log_remove_ant(&mut self, turn: usize, id: String) {
-        self.log_remove(turn, Some(id), "Ant".to_string(), None);
+    fn log_spawn_hill(&mut self, turn: usize, player: usize, location: (usize, usize)) {
+        self.log_spawn(turn, "Hill".to_string(), None, Some(player), location);
+    }
+
+    fn log_remove_ant(&mut self, turn: usize, id: String, location: (usize, usize)) {
+        self.log_remove(turn, Some(id), "Ant".to_string(), Some(location));
     }
 
     fn log_move_ant(
@@ -62,6 +109,35 @@ pub trait ReplayLogger: Send + Sync {
                 player: None,
                 location: Some(location),
                 destination: Some(destination),
+                harvester_id: None,
+                target_id: None,
+                reason: None,
+                initial: false,
+            },
+        );
+    }
+
+    fn log_blocked_move(
+        &mut self,
+        turn: usize,
+        id: String,
+        location: (usize, usize),
+        destination: (usize, usize),
+        reason: BlockedMoveReason,
+    ) {
+        self.log_event(
+            turn,
+            Event {
+                event_type: EventType::BlockedMove,
+                entity: "Ant".to_string(),
+                entity_id: Some(id),
+                player: None,
+                location: Some(location),
+                destination: Some(destination),
+                harvester_id: None,
+                target_id: None,
+                reason: Some(format!("{:?}", reason)),
+                initial: false,
             },
         );
     }
@@ -70,20 +146,78 @@ pub trait ReplayLogger: Send + Sync {
         self.log_remove(turn, None, "Hill".to_string(), Some(location));
     }
 
-    fn log_remove_food(&mut self, turn: usize, location: (usize, usize)) {
-        self.log_remove(turn, None, "Food".to_string(), Some(location));
+    fn log_remove_water(&mut self, turn: usize, location: (usize, usize)) {
+        self.log_remove(turn, None, "Water".to_string(), Some(location));
     }
 
-    fn log_attack(&mut self, turn: usize, location: (usize, usize), destination: (usize, usize)) {
+    fn log_remove_food(
+        &mut self,
+        turn: usize,
+        location: (usize, usize),
+        harvester: Option<String>,
+    ) {
+        self.log_event(
+            turn,
+            Event {
+                event_type: EventType::Remove,
+                entity: "Food".to_string(),
+                entity_id: None,
+                player: None,
+                location: Some(location),
+                destination: None,
+                harvester_id: harvester,
+                target_id: None,
+                reason: None,
+                initial: false,
+            },
+        );
+    }
+
+    fn log_harvest_food(
+        &mut self,
+        turn: usize,
+        location: (usize, usize),
+        player: usize,
+        harvester: String,
+    ) {
+        self.log_event(
+            turn,
+            Event {
+                event_type: EventType::Harvest,
+                entity: "Food".to_string(),
+                entity_id: None,
+                player: Some(player),
+                location: Some(location),
+                destination: None,
+                harvester_id: Some(harvester),
+                target_id: None,
+                reason: None,
+                initial: false,
+            },
+        );
+    }
+
+    fn log_attack(
+        &mut self,
+        turn: usize,
+        id: String,
+        location: (usize, usize),
+        target_id: String,
+        destination: (usize, usize),
+    ) {
         self.log_event(
             turn,
             Event {
                 event_type: EventType::Attack,
                 entity: "Ant".to_string(),
-                entity_id: None,
+                entity_id: Some(id),
                 player: None,
                 location: Some(location),
                 destination: Some(destination),
+                harvester_id: None,
+                target_id: Some(target_id),
+                reason: None,
+                initial: false,
             },
         );
     }
@@ -105,6 +239,11 @@ pub trait ReplayLogger: Send + Sync {
                 player,
                 location: Some(location),
                 destination: None,
+                harvester_id: None,
+                target_id: None,
+                reason: None,
+                // Everything spawned on turn 0 is part of the initial setup, before any turn is played
+                initial: turn == 0,
             },
         );
     }
@@ -125,49 +264,348 @@ pub trait ReplayLogger: Send + Sync {
                 player: None,
                 location,
                 destination: None,
+                harvester_id: None,
+                target_id: None,
+                reason: None,
+                initial: false,
             },
         );
     }
 }
 
-#[derive(serde::Serialize)]
-enum EventType {
+/// Represents the type of an event in the game.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[pyclass(module = "ants_engine", eq, eq_int)]
+pub enum EventType {
+    /// An entity was added to the map.
     Spawn,
+    /// An entity was removed from the map, e.g. a dead ant, destroyed food, a razed hill or dug water.
     Remove,
+    /// An ant moved.
     Move,
+    /// An ant attacked another ant.
     Attack,
+    /// An ant tried to move but couldn't, only logged when blocked moves are logged.
+    BlockedMove,
+    /// Food was harvested and stored in the hive of a player, which removes it from the map.
+    Harvest,
 }
 
-#[derive(serde::Serialize)]
+/// Represents an event in the game, as logged in the replay.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[pyclass(module = "ants_engine", get_all)]
 pub struct Event {
-    event_type: EventType,
-    entity: String,
-    entity_id: Option<String>,
-    player: Option<usize>,
-    location: Option<(usize, usize)>,
-    destination: Option<(usize, usize)>,
+    /// The type of the event.
+    pub event_type: EventType,
+    /// The name of the entity the event is about, i.e. `Ant`, `Food`, `Hill` or `Water`.
+    pub entity: String,
+    /// The id of the entity, if it has one.
+    pub entity_id: Option<String>,
+    /// The player that owns the entity, only set when an ant is spawned, or the player that stored the food for harvests.
+    pub player: Option<usize>,
+    /// The location of the entity.
+    pub location: Option<(usize, usize)>,
+    /// The destination of the ant for moves and blocked moves, or the location of the attacked ant for attacks.
+    pub destination: Option<(usize, usize)>,
+    /// The id of the ant that harvested the food, only set for harvests and for food picked up by a carrying ant.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub harvester_id: Option<String>,
+    /// Why the ant couldn't move, only set for blocked moves,
+    /// i.e. `OutOfBounds`, `NoLiveAnt`, `Water`, `Food` or `DeadAnt`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Whether the entity was spawned as part of the initial setup of the game, i.e. on turn 0, only set for spawns.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub initial: bool,
+    /// The id of the attacked ant, only set for attacks. Together with `entity_id`, the id of the attacking ant,
+    /// it tells who killed whom, since an ant is only attacked when the battle kills it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_id: Option<String>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// An in-process observer of events, called with the turn and the event.
+pub type EventObserver = Box<dyn Fn(usize, &Event) + Send + Sync>;
+
+/// Wraps a logger so that every event is also sent to an observer before being logged.
+pub fn observe_events(
+    logger: Box<dyn ReplayLogger>,
+    observer: EventObserver,
+) -> Box<dyn ReplayLogger> {
+    Box::new(ObservedReplayLogger { logger, observer })
+}
+
+/// Renders every turn of a saved replay as a plain string, the same as `Game::render` at the end of that turn.
+///
+/// The board is reconstructed by applying the events of each turn, in order, to the map the game started from.
+/// Replays don't record the game settings, so razed hills are always drawn as in `HillRazeMode::Disable`.
+/// Hills placed with `Game::place_hill` are only drawn for replays with schema version 10 or later.
+/// Corpses and the food carried by ants are not reconstructed either, since they are not logged as events.
+///
+/// Returns an error if the replay is not valid JSON, is missing a field, has a malformed map or an event outside it,
+/// or was saved with a schema version older than 3.
+///
+/// # Arguments
+/// * `replay` - The contents of the replay file, saved with schema version 3 or later.
+pub fn render_frames(replay: &str) -> Result<Vec<String>, String> {
+    let replay: Value = serde_json::from_str(replay)
+        .map_err(|error| format!("The replay is not valid JSON: {}.", error))?;
+
+    // Older replays don't have the location of the ants that are removed, so they can't be played back
+    let version = replay["schema_version"].as_u64().unwrap_or(0);
+    if version < 3 {
+        return Err(format!(
+            "Only replays with schema version 3 or later can be rendered, got version {}.",
+            version
+        ));
+    }
+
+    let mut map = Map::parse(
+        replay["map"]["contents"]
+            .as_str()
+            .ok_or("The replay doesn't have the map contents.")?,
+    )
+    .map_err(|error| format!("The map of the replay is malformed: {}", error))?;
+    let theme = RenderTheme::default();
+    // Replays before schema version 8 don't have the player names, so the default ones are used
+    let names: Vec<String> = match replay["player_names"].as_array() {
+        Some(names) => names
+            .iter()
+            .map(|name| name.as_str().map(|name| name.to_string()))
+            .collect::<Option<Vec<String>>>()
+            .ok_or("The player names of the replay are not all strings.")?,
+        None => (0..map.players()).map(default_player_name).collect(),
+    };
+    let numbers = |turn: &Value, field: &str| -> Result<Vec<i64>, String> {
+        turn[field]
+            .as_array()
+            .and_then(|values| values.iter().map(|value| value.as_i64()).collect())
+            .ok_or(format!("A turn of the replay doesn't have the {}.", field))
+    };
+
+    let mut frames = Vec::new();
+    let turns = replay["turns"]
+        .as_array()
+        .ok_or("The replay doesn't have the turns.")?;
+    for turn in turns {
+        let events: Vec<Event> = serde_json::from_value(turn["events"].clone())
+            .map_err(|error| format!("The events of a turn are not valid: {}.", error))?;
+        for event in events {
+            apply_event(&mut map, event)?;
+        }
+
+        let scores: Vec<isize> = numbers(turn, "scores")?
+            .into_iter()
+            .map(|n| n as isize)
+            .collect();
+        let ants: Vec<usize> = numbers(turn, "ants")?
+            .into_iter()
+            .map(|n| n as usize)
+            .collect();
+        let hive: Vec<usize> = numbers(turn, "hive")?
+            .into_iter()
+            .map(|n| n as usize)
+            .collect();
+        let number = turn["turn"]
+            .as_u64()
+            .ok_or("A turn of the replay doesn't have its number.")?;
+        frames.push(map.render_to_string(number as usize, &scores, &ants, &hive, &names, &theme));
+    }
+
+    Ok(frames)
+}
+
+/// Renders every turn of a saved replay as a plain string, see `render_frames`.
+/// In Python, an invalid replay raises a `ValueError`.
+#[pyfunction]
+#[pyo3(name = "render_frames")]
+pub fn render_frames_py(replay: &str) -> PyResult<Vec<String>> {
+    render_frames(replay).map_err(PyValueError::new_err)
+}
+
+/// Applies an event from a replay to the board, the same way the game changed it when the event was logged.
+/// Returns an error if the event is missing a location or player it needs, or if a location is outside the map.
+fn apply_event(map: &mut Map, event: Event) -> Result<(), String> {
+    let (width, height) = (map.width(), map.height());
+    let cell = |cell: Option<(usize, usize)>| -> Result<(usize, usize), String> {
+        match cell {
+            Some((row, col)) if row < height && col < width => Ok((row, col)),
+            Some((row, col)) => Err(format!(
+                "The {:?} event of {} at ({}, {}) is outside the map.",
+                event.event_type, event.entity, row, col
+            )),
+            None => Err(format!(
+                "The {:?} event of {} doesn't have a location.",
+                event.event_type, event.entity
+            )),
+        }
+    };
+    let player = || -> Result<usize, String> {
+        event.player.ok_or(format!(
+            "The {:?} event of {} doesn't have a player.",
+            event.event_type, event.entity
+        ))
+    };
+
+    match (&event.event_type, event.entity.as_str()) {
+        (EventType::Spawn, "Ant") => {
+            let (row, col) = cell(event.location)?;
+            // Ants spawned from the hive stand on their hill
+            let hill = map
+                .get(row, col)
+                .filter(|entity| entity.name() == "Hill")
+                .map(|hill| {
+                    Box::new(Hill::new(hill.player().unwrap(), hill.alive().unwrap()))
+                        as Box<dyn Entity>
+                });
+            let ant = Ant::new(
+                event.entity_id.clone().unwrap_or_default(),
+                player()?,
+                true,
+                hill,
+            );
+            map.set(row, col, Box::new(ant));
+        }
+        (EventType::Spawn, "Food") => {
+            let (row, col) = cell(event.location)?;
+            map.set(row, col, Box::new(Food));
+        }
+        (EventType::Spawn, "Hill") => {
+            let (row, col) = cell(event.location)?;
+            map.set(row, col, Box::new(Hill::new(player()?, true)));
+        }
+        (EventType::Remove, "Ant") => {
+            let (row, col) = cell(event.location)?;
+            // If the ant was on a hill, the hill is left behind
+            match map.get(row, col).and_then(|ant| ant.on_ant_hill()) {
+                Some(hill) => {
+                    let hill = Hill::new(hill.player().unwrap(), hill.alive().unwrap());
+                    map.set(row, col, Box::new(hill));
+                }
+                None => map.remove(row, col),
+            }
+        }
+        (EventType::Remove, "Food") | (EventType::Remove, "Water") | (EventType::Harvest, _) => {
+            let (row, col) = cell(event.location)?;
+            map.remove(row, col);
+        }
+        (EventType::Remove, "Hill") => {
+            // The ant that razed the hill is standing on it, unless the hill was placed razed
+            let (row, col) = cell(event.location)?;
+            if let Some(entity) = map.get_mut(row, col) {
+                if entity.name() == "Hill" {
+                    entity.set_alive(false);
+                } else if let Some(owner) = entity.on_ant_hill().and_then(|hill| hill.player()) {
+                    entity.set_on_ant_hill(Box::new(Hill::new(owner, false)));
+                }
+            }
+        }
+        (EventType::Move, _) => {
+            // Moving onto another ant kills both, just like in the game
+            let (from, to) = (cell(event.location)?, cell(event.destination)?);
+            map.move_entity(from, to);
+        }
+        (EventType::Attack, _) => {
+            // Only the attacks that kill an ant are logged
+            let (row, col) = cell(event.destination)?;
+            if let Some(ant) = map.get_mut(row, col) {
+                ant.set_alive(false);
+            }
+        }
+        // Blocked moves don't change the board
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Represents the final standings of a game, logged once when the game ends.
+#[derive(serde::Serialize)]
+pub struct GameResult {
+    /// The reason the game finished.
+    pub reason: String,
+    /// The player that won the game, if any.
+    pub winner: Option<usize>,
+    /// The final scores of each player.
+    pub scores: Vec<isize>,
+    /// The final number of live ants of each player.
+    pub ants: Vec<usize>,
+    /// The final amount of food in the hive of each player.
+    pub hive: Vec<usize>,
+    /// The players sorted from first to last place.
+    pub ranking: Vec<usize>,
+    /// The cumulative statistics of each player.
+    pub statistics: Vec<PlayerStats>,
 }
 
 struct Turn {
     turn: usize,
     ants: Vec<usize>,
     hive: Vec<usize>,
-    scores: Vec<usize>,
+    scores: Vec<isize>,
+    board_hash: Option<u64>,
 }
 
 struct NoOpReplayLogger;
 impl ReplayLogger for NoOpReplayLogger {}
 
+struct ObservedReplayLogger {
+    logger: Box<dyn ReplayLogger>,
+    observer: EventObserver,
+}
+
+impl ReplayLogger for ObservedReplayLogger {
+    fn records_turns(&self) -> bool {
+        self.logger.records_turns()
+    }
+
+    fn log_turn(
+        &mut self,
+        turn: usize,
+        ants: &[usize],
+        hive: &[usize],
+        scores: &[isize],
+        board_hash: Option<u64>,
+    ) {
+        self.logger.log_turn(turn, ants, hive, scores, board_hash);
+    }
+
+    fn log_end_game(&mut self, result: GameResult) {
+        self.logger.log_end_game(result);
+    }
+
+    fn log_event(&mut self, turn: usize, event: Event) {
+        (self.observer)(turn, &event);
+        self.logger.log_event(turn, event);
+    }
+
+    fn log_players(&mut self, names: &[String], colors: Option<&[(u8, u8, u8)]>) {
+        self.logger.log_players(names, colors);
+    }
+
+    fn clear(&mut self) {
+        self.logger.clear();
+    }
+
+    fn save(&self) {
+        self.logger.save();
+    }
+}
+
 struct JsonReplayLogger {
     filename: String,
     players: usize,
     map_width: usize,
     map_height: usize,
     map_contents: String,
+    player_names: Vec<String>,
+    player_colors: Option<Vec<(u8, u8, u8)>>,
     turns: Vec<Turn>,
     events: HashMap<usize, Vec<Event>>,
-    finished_reason: Option<String>,
-    winner: Option<usize>,
+    result: Option<GameResult>,
 }
 
 impl JsonReplayLogger {
@@ -184,33 +622,51 @@ impl JsonReplayLogger {
             map_width,
             map_height,
             map_contents,
+            player_names: (0..players).map(default_player_name).collect(),
+            player_colors: None,
             turns: Vec::new(),
             events: HashMap::new(),
-            finished_reason: None,
-            winner: None,
+            result: None,
         }
     }
 }
 
 impl ReplayLogger for JsonReplayLogger {
-    fn log_turn(&mut self, turn: usize, ants: Vec<usize>, hive: Vec<usize>, scores: Vec<usize>) {
+    fn records_turns(&self) -> bool {
+        true
+    }
+
+    fn log_turn(
+        &mut self,
+        turn: usize,
+        ants: &[usize],
+        hive: &[usize],
+        scores: &[isize],
+        board_hash: Option<u64>,
+    ) {
+        // Only this logger keeps the values, so it's the only one that pays for copying them
         self.turns.push(Turn {
             turn,
-            ants,
-            hive,
-            scores,
+            ants: ants.to_vec(),
+            hive: hive.to_vec(),
+            scores: scores.to_vec(),
+            board_hash,
         });
     }
 
-    fn log_end_game(&mut self, reason: String, winner: Option<usize>) {
-        self.finished_reason = Some(reason);
-        self.winner = winner;
+    fn log_end_game(&mut self, result: GameResult) {
+        self.result = Some(result);
     }
 
     fn log_event(&mut self, turn: usize, event: Event) {
         self.events.entry(turn).or_default().push(event);
     }
 
+    fn log_players(&mut self, names: &[String], colors: Option<&[(u8, u8, u8)]>) {
+        self.player_names = names.to_vec();
+        self.player_colors = colors.map(|colors| colors.to_vec());
+    }
+
     fn clear(&mut self) {
         self.turns.clear();
         self.events.clear();
@@ -227,21 +683,27 @@ impl ReplayLogger for JsonReplayLogger {
                     "ants": turn.ants,
                     "hive": turn.hive,
                     "scores": turn.scores,
+                    "board_hash": turn.board_hash,
                     "events": self.events.get(&turn.turn).unwrap_or(&Vec::new()),
                 })
             })
             .collect();
 
         let data = json!({
+            "schema_version": SCHEMA_VERSION,
             "players": self.players,
+            "player_names": self.player_names,
+            "player_colors": self.player_colors,
             "map": {
                 "width": self.map_width,
                 "height": self.map_height,
                 "contents": self.map_contents,
             },
             "turns": turns,
-            "finished_reason": self.finished_reason,
-            "winner": self.winner,
+            // The reason and winner are also kept at the top level for viewers that only read those
+            "finished_reason": self.result.as_ref().map(|result| &result.reason),
+            "winner": self.result.as_ref().and_then(|result| result.winner),
+            "result": self.result,
         });
 
         let mut writer = BufWriter::new(&file);
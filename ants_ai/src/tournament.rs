@@ -0,0 +1,359 @@
+//! A headless tournament runner, which plays round-robin matches between agents on a pool of maps and aggregates
+//! their records into a standings table, e.g. to benchmark agents without writing the game loop each time.
+
+use crate::game::{player_seed, Action, FinishedReason, Game, GameState};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+/// An agent of a tournament, which returns the actions for the ants of the given player in the given state.
+pub type Agent<'a> = Box<dyn FnMut(&GameState, usize) -> Vec<Action> + 'a>;
+
+/// Creates the game of a match given the map contents and the seed of the match.
+type GameFactory<'a> = Box<dyn Fn(&str, u64) -> Game + 'a>;
+
+/// Represents the outcome of one match of a tournament.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchResult {
+    /// The index of the map in the pool.
+    pub map: usize,
+    /// The seed the game was created with.
+    pub seed: u64,
+    /// The agent playing as each player, where the index is the player number.
+    pub agents: Vec<usize>,
+    /// The final score of each player, where the index is the player number.
+    pub scores: Vec<isize>,
+    /// The agent that won the match. `None` if the match finished without a winner.
+    pub winner: Option<usize>,
+    /// The reason the match finished.
+    pub finished_reason: FinishedReason,
+    /// The number of turns played.
+    pub turns: usize,
+}
+
+/// Represents the record of an agent over a whole tournament.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Standing {
+    /// The index of the agent, in the order they were added.
+    pub agent: usize,
+    /// The name of the agent.
+    pub name: String,
+    /// The number of matches played.
+    pub played: usize,
+    /// The number of matches won.
+    pub wins: usize,
+    /// The number of matches lost, i.e. another agent won.
+    pub losses: usize,
+    /// The number of matches that finished without a winner.
+    pub draws: usize,
+    /// The sum of the final scores of all the matches played.
+    pub total_score: isize,
+    /// The number of turns the agent ran out of time, see `Tournament::set_turn_timeout`.
+    pub timeouts: usize,
+}
+
+/// Represents the outcome of a whole tournament.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TournamentResult {
+    /// Every match, in the order they were played.
+    pub matches: Vec<MatchResult>,
+    /// The record of each agent, from first to last place.
+    /// Agents are sorted by wins, then by draws, then by total score and finally by the order they were added.
+    pub standings: Vec<Standing>,
+}
+
+/// Plays round-robin matches between agents on a pool of two-player maps.
+///
+/// Every pair of agents plays `games_per_map` matches on each map, switching sides on every match so that neither
+/// agent always gets the same side of the map. Each match gets its own seed, derived from the tournament seed.
+pub struct Tournament<'a> {
+    maps: Vec<String>,
+    seed: u64,
+    create_game: GameFactory<'a>,
+    names: Vec<String>,
+    agents: Vec<Agent<'a>>,
+    games_per_map: usize,
+    turn_timeout: Option<Duration>,
+    max_timeouts: Option<usize>,
+}
+
+impl<'a> Tournament<'a> {
+    /// Creates a new tournament without agents.
+    ///
+    /// # Arguments
+    /// * `maps` - The contents of each map of the pool. Every map must have exactly 2 players.
+    /// * `seed` - The seed the seed of each match is derived from.
+    /// * `create_game` - Creates the game of a match given the map contents and the seed of the match,
+    ///   so that every match is played with the same settings.
+    pub fn new<F>(maps: Vec<String>, seed: u64, create_game: F) -> Tournament<'a>
+    where
+        F: Fn(&str, u64) -> Game + 'a,
+    {
+        Tournament {
+            maps,
+            seed,
+            create_game: Box::new(create_game),
+            names: vec![],
+            agents: vec![],
+            games_per_map: 2,
+            turn_timeout: None,
+            max_timeouts: None,
+        }
+    }
+
+    /// Adds an agent to the tournament. Agents are numbered in the order they are added.
+    /// An agent can only move the ants of the player it plays as, the actions for any other cell are ignored.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the agent, used in the standings.
+    /// * `agent` - Returns the actions for the ants of the given player in the given state.
+    pub fn add_agent<F>(&mut self, name: &str, agent: F)
+    where
+        F: FnMut(&GameState, usize) -> Vec<Action> + 'a,
+    {
+        self.names.push(name.to_string());
+        self.agents.push(Box::new(agent));
+    }
+
+    /// Sets the number of matches each pair of agents plays on each map.
+    ///
+    /// # Arguments
+    /// * `value` - The number of matches, at least `1`. Defaults to `2`, so each agent plays each side of every map once.
+    pub fn set_games_per_map(&mut self, value: usize) {
+        if value == 0 {
+            panic!("The number of games per map must be at least 1.");
+        }
+
+        self.games_per_map = value;
+    }
+
+    /// Sets how long an agent can take to return its actions. When it takes longer, its actions are dropped and its
+    /// turn is skipped with `Game::skip_player_turn`, so its ants hold their position.
+    ///
+    /// # Arguments
+    /// * `value` - The time limit per turn. Defaults to `None`, i.e. no limit.
+    pub fn set_turn_timeout(&mut self, value: Option<Duration>) {
+        self.turn_timeout = value;
+    }
+
+    /// Sets the number of timeouts after which an agent is eliminated from a match with `Game::eliminate_player`.
+    ///
+    /// # Arguments
+    /// * `value` - The number of timeouts in a single match. Defaults to `None`, i.e. agents are never eliminated.
+    pub fn set_max_timeouts(&mut self, value: Option<usize>) {
+        self.max_timeouts = value;
+    }
+
+    /// Plays every match of the tournament and returns the results.
+    pub fn run(&mut self) -> TournamentResult {
+        let mut standings: Vec<Standing> = self
+            .names
+            .iter()
+            .enumerate()
+            .map(|(agent, name)| Standing {
+                agent,
+                name: name.clone(),
+                ..Standing::default()
+            })
+            .collect();
+        let mut matches = vec![];
+
+        for first in 0..self.agents.len() {
+            for second in first + 1..self.agents.len() {
+                for map in 0..self.maps.len() {
+                    for game in 0..self.games_per_map {
+                        // Agents switch sides on every match
+                        let agents = match game % 2 {
+                            0 => vec![first, second],
+                            _ => vec![second, first],
+                        };
+                        let seed = player_seed(self.seed, matches.len());
+                        let result = self.play_match(map, seed, agents, &mut standings);
+                        matches.push(result);
+                    }
+                }
+            }
+        }
+
+        // Sort in descending order of the records, the sort is stable so fully tied agents keep their order
+        standings.sort_by(|a, b| {
+            (b.wins, b.draws, b.total_score).cmp(&(a.wins, a.draws, a.total_score))
+        });
+
+        TournamentResult { matches, standings }
+    }
+
+    fn play_match(
+        &mut self,
+        map: usize,
+        seed: u64,
+        agents: Vec<usize>,
+        standings: &mut [Standing],
+    ) -> MatchResult {
+        let mut game = (self.create_game)(&self.maps[map], seed);
+        if game.players() != agents.len() {
+            panic!(
+                "Round-robin matches need maps with {} players, but map {} has {} players.",
+                agents.len(),
+                map,
+                game.players()
+            );
+        }
+
+        let mut timeouts = vec![0; agents.len()];
+        let mut state = game.start();
+        while !state.finished {
+            let mut actions = vec![];
+            for (player, agent) in agents.iter().enumerate() {
+                let start = Instant::now();
+                let agent_actions = (self.agents[*agent])(&state, player);
+
+                if self
+                    .turn_timeout
+                    .is_some_and(|timeout| start.elapsed() > timeout)
+                {
+                    game.skip_player_turn(player);
+                    timeouts[player] += 1;
+                    standings[*agent].timeouts += 1;
+                    if self.max_timeouts == Some(timeouts[player]) {
+                        game.eliminate_player(player);
+                    }
+                    continue;
+                }
+
+                // Agents can only move their own ants
+                let own_ants: HashSet<(usize, usize)> = state.ants[player]
+                    .iter()
+                    .map(|ant| (ant.row, ant.col))
+                    .collect();
+                actions.extend(
+                    agent_actions
+                        .into_iter()
+                        .filter(|action| own_ants.contains(&action.location())),
+                );
+            }
+
+            state = game.update(actions);
+        }
+
+        let winner = state.winner.map(|player| agents[player]);
+        for (player, agent) in agents.iter().enumerate() {
+            let standing = &mut standings[*agent];
+            standing.played += 1;
+            standing.total_score += state.scores[player];
+            match winner {
+                Some(winner) if winner == *agent => standing.wins += 1,
+                Some(_) => standing.losses += 1,
+                None => standing.draws += 1,
+            }
+        }
+
+        MatchResult {
+            map,
+            seed,
+            agents,
+            scores: state.scores,
+            winner,
+            finished_reason: state.finished_reason.unwrap(),
+            turns: state.turn,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Direction;
+
+    const MAPS: [&str; 2] = [
+        "\
+        rows 3
+        cols 6
+        players 2
+        m 0....1
+        m ......
+        m ......",
+        "\
+        rows 4
+        cols 4
+        players 2
+        m 0...
+        m ....
+        m ....
+        m ...1",
+    ];
+
+    fn create_game(map: &str, seed: u64) -> Game {
+        Game::new(map, 4, 5, 1, 1, 20, 500, seed, None)
+    }
+
+    #[test]
+    fn when_running_a_tournament_every_pair_of_agents_plays_every_map_on_both_sides() {
+        let maps = MAPS.iter().map(|map| map.to_string()).collect();
+        let mut tournament = Tournament::new(maps, 0, create_game);
+        tournament.add_agent("idle", |_, _| vec![]);
+        tournament.add_agent("east", |state, player| {
+            state.ants[player]
+                .iter()
+                .map(|ant| Action::new(ant.row, ant.col, Direction::East))
+                .collect()
+        });
+
+        let result = tournament.run();
+
+        assert_eq!(result.matches.len(), 4);
+        let sides: Vec<(usize, Vec<usize>)> = result
+            .matches
+            .iter()
+            .map(|result| (result.map, result.agents.clone()))
+            .collect();
+        assert_eq!(
+            sides,
+            vec![
+                (0, vec![0, 1]),
+                (0, vec![1, 0]),
+                (1, vec![0, 1]),
+                (1, vec![1, 0])
+            ]
+        );
+        // Every match has its own seed
+        let seeds: HashSet<u64> = result.matches.iter().map(|result| result.seed).collect();
+        assert_eq!(seeds.len(), 4);
+
+        assert_eq!(result.standings.len(), 2);
+        for standing in &result.standings {
+            assert_eq!(standing.played, 4);
+            assert_eq!(standing.wins + standing.losses + standing.draws, 4);
+            let total_score: isize = result
+                .matches
+                .iter()
+                .map(|result| {
+                    let player = result.agents.iter().position(|a| *a == standing.agent);
+                    result.scores[player.unwrap()]
+                })
+                .sum();
+            assert_eq!(standing.total_score, total_score);
+        }
+        assert!(result.standings[0].wins >= result.standings[1].wins);
+    }
+
+    #[test]
+    fn when_an_agent_times_out_too_many_times_it_is_eliminated_from_the_match() {
+        let maps = vec![MAPS[0].to_string()];
+        let mut tournament = Tournament::new(maps, 0, create_game);
+        tournament.set_games_per_map(1);
+        tournament.set_turn_timeout(Some(Duration::from_millis(5)));
+        tournament.set_max_timeouts(Some(2));
+        tournament.add_agent("slow", |_, _| {
+            std::thread::sleep(Duration::from_millis(20));
+            vec![]
+        });
+        tournament.add_agent("idle", |_, _| vec![]);
+
+        let result = tournament.run();
+
+        assert_eq!(result.matches[0].winner, Some(1));
+        assert_eq!(result.matches[0].turns, 2);
+        assert_eq!(result.standings[0].name, "idle");
+        assert_eq!(result.standings[1].timeouts, 2);
+    }
+}
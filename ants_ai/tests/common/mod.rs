@@ -0,0 +1,78 @@
+use ants_engine::{Action, Game, GameState};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fs;
+use std::path::Path;
+
+/// The outcome of playing a game to completion.
+pub struct PlayedGame {
+    /// The game state after every turn, starting with the state returned by `start`.
+    pub states: Vec<GameState>,
+    /// The contents of the replay file.
+    pub replay: Vec<u8>,
+    /// The game rendered as a string after the last turn.
+    // Each test crate compiles its own copy of this module, and not all of them render the game
+    #[allow(dead_code)]
+    pub render: String,
+}
+
+/// Reads one of the example maps.
+///
+/// # Arguments
+/// * `name` - The name of the map file in `examples/maps`.
+pub fn read_map(name: &str) -> String {
+    let map_file = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("examples/maps")
+        .join(name);
+    fs::read_to_string(map_file).unwrap()
+}
+
+/// Plays a game to completion where every ant moves in a random direction.
+///
+/// Both the game and the actions are seeded, so the same arguments should always play the same game.
+///
+/// # Arguments
+/// * `map_contents` - The map as a string.
+/// * `seed` - The seed for the game and the actions.
+/// * `max_turns` - The maximum number of turns before the game ends.
+pub fn play_to_completion(map_contents: &str, seed: u64, max_turns: usize) -> PlayedGame {
+    let replay_filename = std::env::temp_dir()
+        .join(format!("ants_replay_{}.json", uuid::Uuid::new_v4()))
+        .to_str()
+        .unwrap()
+        .to_string();
+    let mut game = Game::new(
+        map_contents,
+        4,
+        5,
+        1,
+        5,
+        max_turns,
+        500,
+        seed,
+        Some(replay_filename.clone()),
+    );
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut states = vec![game.start()];
+    while !states.last().unwrap().finished {
+        let actions = states
+            .last()
+            .unwrap()
+            .ants
+            .iter()
+            .flatten()
+            .map(|ant| Action::new(ant.row, ant.col, rng.gen()))
+            .collect();
+        states.push(game.update(actions));
+    }
+
+    let replay = fs::read(&replay_filename).unwrap();
+    fs::remove_file(&replay_filename).unwrap();
+
+    PlayedGame {
+        states,
+        replay,
+        render: game.render(),
+    }
+}
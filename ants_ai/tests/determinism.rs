@@ -0,0 +1,37 @@
+mod common;
+
+use common::{play_to_completion, read_map};
+
+#[test]
+fn when_playing_the_same_seeded_game_twice_the_replays_are_byte_equal() {
+    let map = read_map("tutorial.map");
+
+    let first = play_to_completion(&map, 0, 200);
+    let second = play_to_completion(&map, 0, 200);
+
+    assert!(!first.replay.is_empty());
+    assert!(first.replay == second.replay);
+}
+
+#[test]
+fn when_playing_the_same_seeded_game_twice_the_game_states_are_identical() {
+    let map = read_map("tutorial.map");
+
+    let first = play_to_completion(&map, 0, 200);
+    let second = play_to_completion(&map, 0, 200);
+
+    assert_eq!(first.states.len(), second.states.len());
+    for (first, second) in first.states.iter().zip(second.states.iter()) {
+        assert_eq!(first, second);
+    }
+}
+
+#[test]
+fn when_playing_games_with_different_seeds_the_replays_differ() {
+    let map = read_map("tutorial.map");
+
+    let first = play_to_completion(&map, 0, 200);
+    let second = play_to_completion(&map, 1, 200);
+
+    assert!(first.replay != second.replay);
+}
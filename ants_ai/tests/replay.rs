@@ -0,0 +1,60 @@
+mod common;
+
+use common::{play_to_completion, read_map};
+
+#[test]
+fn when_rendering_the_frames_of_a_replay_the_last_frame_matches_the_final_render_of_the_game() {
+    let map = read_map("tutorial.map");
+    let played = play_to_completion(&map, 0, 200);
+
+    let frames = ants_engine::render_frames(std::str::from_utf8(&played.replay).unwrap()).unwrap();
+
+    // One frame for the start of the game and one for every turn after that
+    assert_eq!(frames.len(), played.states.len());
+    assert_eq!(frames.last().unwrap(), &played.render);
+}
+
+#[test]
+fn when_rendering_the_frames_of_a_replay_from_an_older_schema_an_error_is_returned() {
+    assert_eq!(
+        ants_engine::render_frames(r#"{"schema_version": 2}"#),
+        Err(
+            "Only replays with schema version 3 or later can be rendered, got version 2."
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn when_rendering_the_frames_of_a_malformed_replay_an_error_is_returned() {
+    assert!(ants_engine::render_frames("not a replay")
+        .unwrap_err()
+        .starts_with("The replay is not valid JSON"));
+
+    let replay = r#"{
+        "schema_version": 3,
+        "map": {"contents": "rows 1\ncols 2\nplayers 1\nm 0."},
+        "turns": [{
+            "turn": 0, "ants": [0], "hive": [0], "scores": [0],
+            "events": [{"event_type": "Spawn", "entity": "Food", "location": [3, 0]}]
+        }]
+    }"#;
+    assert_eq!(
+        ants_engine::render_frames(replay),
+        Err("The Spawn event of Food at (3, 0) is outside the map.".to_string())
+    );
+}
+
+#[test]
+fn when_rendering_the_frames_of_a_replay_with_a_malformed_map_an_error_is_returned() {
+    let replay = r#"{
+        "schema_version": 3,
+        "map": {"contents": "rows 1\ncols 2\nplayers 1\nm 0?"},
+        "turns": []
+    }"#;
+
+    assert_eq!(
+        ants_engine::render_frames(replay),
+        Err("The map of the replay is malformed: Invalid character value: ?".to_string())
+    );
+}
@@ -0,0 +1,92 @@
+import importlib.util
+import pathlib
+import unittest
+from unittest.mock import Mock
+
+# Load the visualizer on its own, so that the tests don't need the compiled engine
+_spec = importlib.util.spec_from_file_location(
+    "visualizer",
+    pathlib.Path(__file__).parent.parent / "ants_ai" / "visualizer.py",
+)
+visualizer = importlib.util.module_from_spec(_spec)
+_spec.loader.exec_module(visualizer)
+
+
+def _event(event_type: str, entity: str, location: tuple[int], **kwargs) -> dict:
+    return {
+        "event_type": event_type,
+        "entity": entity,
+        "entity_id": None,
+        "player": None,
+        "location": location,
+        "destination": None,
+        **kwargs,
+    }
+
+
+def _replay(events: list[dict]) -> dict:
+    return {
+        "players": 1,
+        "map": {"width": 3, "height": 1, "contents": "rows 1\ncols 3\nplayers 1\nm 0*.\n"},
+        "turns": [
+            {"turn": 1, "ants": [1], "hive": [1], "scores": [1], "events": events}
+        ],
+        "finished_reason": "TurnLimitReached",
+        "winner": 0,
+    }
+
+
+class ReplayTest(unittest.TestCase):
+    def test_when_loading_a_replay_with_a_harvest_it_is_drawn_with_the_removals(self):
+        replay = visualizer.Replay.from_json(
+            _replay(
+                [
+                    _event("Remove", "Ant", (0, 2), entity_id="a"),
+                    _event("Harvest", "Food", (0, 1), player=0, harvester_id="b"),
+                    _event("BlockedMove", "Ant", (0, 0), entity_id="b", reason="Water"),
+                ]
+            )
+        )
+
+        events = replay.turns[0].events
+        self.assertEqual(list(events.keys()), [visualizer.TurnPhase.Remove])
+        self.assertEqual(
+            [event.event_type for event in events[visualizer.TurnPhase.Remove]],
+            ["Remove", "Harvest"],
+        )
+        self.assertEqual(events[visualizer.TurnPhase.Remove][1].harvester_id, "b")
+
+
+
+class VisualizerTest(unittest.TestCase):
+    def setUp(self):
+        # Skip the window and the assets, the replay only needs the entities
+        self.visualizer = visualizer.Visualizer.__new__(visualizer.Visualizer)
+        self.visualizer._hill_sprites = (Mock(), Mock())
+        self.visualizer._scale = 10
+        self.visualizer._hills = {}
+
+    def test_when_replaying_a_hill_spawn_the_hill_is_added(self):
+        self.visualizer._do_replay(
+            [visualizer.Event(**_event("Spawn", "Hill", (0, 2), player=1))]
+        )
+
+        hill = self.visualizer._hills[(0, 2)]
+        self.assertEqual(hill.player, 1)
+        self.assertTrue(hill.alive)
+
+    def test_when_replaying_a_hill_placed_razed_the_hill_is_added_razed(self):
+        self.visualizer._do_replay(
+            [
+                visualizer.Event(**_event("Spawn", "Hill", (0, 2), player=1)),
+                visualizer.Event(**_event("Remove", "Hill", (0, 2))),
+            ]
+        )
+
+        hill = self.visualizer._hills[(0, 2)]
+        self.assertEqual(hill.player, 1)
+        self.assertFalse(hill.alive)
+
+
+if __name__ == "__main__":
+    unittest.main()